@@ -0,0 +1,443 @@
+//! Auditing how well a published crate has kept semver over its release history.
+//!
+//! An [`Audit`] lists the crate's versions in its registry index, then checks each
+//! consecutive pair of them, reporting the bump each release declared next to the bump
+//! its changes required.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context as _};
+use itertools::Itertools;
+use trustfall_rustdoc::{VersionedIndex, VersionedRustdocAdapter};
+
+use crate::data_generation::LoadedCrate;
+use crate::data_generation::{CacheSettings, GenerationSettings};
+use crate::query::{ActualSemverUpdate, OverrideStack, RequiredSemverUpdate};
+use crate::rustdoc_gen::{
+    CrateDataForRustdoc, CrateType, FeatureConfig, RustdocFromRegistry, RustdocGenerator,
+};
+use crate::{CheckOutcome, GlobalConfig, LintLevel, ReleaseType, SemverQuery, VersionPolicy};
+
+/// Check every consecutive pair of a crate's published versions for semver violations.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Audit {
+    /// The crate to audit.
+    crate_name: String,
+    /// The registry the crate is published to, if not crates.io.
+    registry: Option<String>,
+    /// Whether to audit yanked versions too.
+    include_yanked: bool,
+    /// Whether to audit pre-release versions too.
+    include_prereleases: bool,
+    /// The oldest version to audit, if not the first one published.
+    since: Option<semver::Version>,
+}
+
+/// Outcome of running an [`Audit`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct AuditReport {
+    pairs: Vec<AuditedPair>,
+}
+
+impl AuditReport {
+    /// Each consecutive pair of audited versions, oldest first.
+    pub fn pairs(&self) -> &[AuditedPair] {
+        &self.pairs
+    }
+
+    /// `true` if every pair was checked and respected semver.
+    pub fn success(&self) -> bool {
+        self.pairs.iter().all(AuditedPair::respects_semver)
+    }
+
+    /// The most severe outcome among all pairs, for use as the exit code of a process
+    /// that ran the audit. Pairs that couldn't be checked make the audit an error.
+    pub fn exit_code(&self) -> CheckOutcome {
+        if self.pairs.iter().any(|pair| pair.error.is_some()) {
+            return CheckOutcome::Error;
+        }
+        self.pairs
+            .iter()
+            .filter(|pair| !pair.respects_semver())
+            .map(|pair| match pair.required_bump() {
+                ReleaseType::Major => CheckOutcome::MajorRequired,
+                _ => CheckOutcome::MinorRequired,
+            })
+            .fold(CheckOutcome::Success, Ord::max)
+    }
+}
+
+/// The semver check of one release against the release before it.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditedPair {
+    baseline_version: semver::Version,
+    current_version: semver::Version,
+    declared_bump: ActualSemverUpdate,
+    required_bump: Option<RequiredSemverUpdate>,
+    lints: Vec<String>,
+    error: Option<String>,
+}
+
+impl AuditedPair {
+    /// The earlier of the two versions.
+    pub fn baseline_version(&self) -> &semver::Version {
+        &self.baseline_version
+    }
+
+    /// The later of the two versions.
+    pub fn current_version(&self) -> &semver::Version {
+        &self.current_version
+    }
+
+    /// The bump declared by the difference between the two version numbers,
+    /// under the version policy set in the later version's manifest.
+    pub fn declared_bump(&self) -> ActualSemverUpdate {
+        self.declared_bump
+    }
+
+    /// The smallest bump that the changes between the two versions required.
+    pub fn required_bump(&self) -> ReleaseType {
+        self.required_bump
+            .map_or(ReleaseType::Patch, ReleaseType::from)
+    }
+
+    /// The ids of the lints that fired between the two versions, sorted.
+    ///
+    /// Lints are run at the levels set in the later version's manifest, so allowed lints
+    /// aren't listed, and lints at the `warn` level don't count towards the required bump.
+    pub fn lints(&self) -> &[String] {
+        &self.lints
+    }
+
+    /// Why the pair couldn't be checked, e.g. because one of the versions failed to build.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// `true` if the pair was checked and the declared bump covers the required one.
+    pub fn respects_semver(&self) -> bool {
+        self.error.is_none()
+            && self
+                .required_bump
+                .is_none_or(|required| self.declared_bump.supports_requirement(required))
+    }
+}
+
+impl Audit {
+    pub fn new(crate_name: impl Into<String>) -> Self {
+        Self {
+            crate_name: crate_name.into(),
+            registry: None,
+            include_yanked: false,
+            include_prereleases: false,
+            since: None,
+        }
+    }
+
+    /// Set the registry the crate is published to, by the name it has in cargo's
+    /// `[registries]` configuration. Defaults to crates.io.
+    pub fn set_registry(&mut self, registry: impl Into<String>) -> &mut Self {
+        self.registry = Some(registry.into());
+        self
+    }
+
+    /// Set whether to audit yanked versions too. Defaults to `false`.
+    pub fn set_include_yanked(&mut self, include_yanked: bool) -> &mut Self {
+        self.include_yanked = include_yanked;
+        self
+    }
+
+    /// Set whether to audit pre-release versions too. Defaults to `false`.
+    pub fn set_include_prereleases(&mut self, include_prereleases: bool) -> &mut Self {
+        self.include_prereleases = include_prereleases;
+        self
+    }
+
+    /// Only audit versions at or after this one.
+    pub fn set_since(&mut self, since: semver::Version) -> &mut Self {
+        self.since = Some(since);
+        self
+    }
+
+    pub fn run(&self, config: &mut GlobalConfig) -> anyhow::Result<AuditReport> {
+        let target_dir = crate::get_cache_dir()?;
+        let mut loader = RustdocFromRegistry::new(&target_dir, self.registry.as_deref(), config)?;
+        let versions = self.audited_versions(loader.published_versions(config, &self.crate_name)?);
+        if versions.len() < 2 {
+            bail!(
+                "found {} matching versions of {}, but at least two are needed to audit",
+                versions.len(),
+                self.crate_name
+            );
+        }
+        config.shell_status(
+            "Auditing",
+            format_args!(
+                "{} pairs of {} versions",
+                versions.len() - 1,
+                self.crate_name
+            ),
+        )?;
+
+        let mut pairs = Vec::with_capacity(versions.len() - 1);
+        let mut baseline = self.load_version(
+            config,
+            &mut loader,
+            &versions[0],
+            CacheSettings::ReadWrite(()),
+        );
+        for (baseline_version, current_version) in versions.iter().tuple_windows() {
+            let mut current = self.load_version(
+                config,
+                &mut loader,
+                current_version,
+                CacheSettings::ReadWrite(()),
+            );
+
+            // Rustdoc cached by an older toolchain may be in a different format,
+            // so regenerate both versions if their formats don't match.
+            if let (Ok(baseline_crate), Ok(current_crate)) = (&baseline, &current) {
+                if baseline_crate.data.version() != current_crate.data.version() {
+                    baseline = self.load_version(
+                        config,
+                        &mut loader,
+                        baseline_version,
+                        CacheSettings::WriteOnly(()),
+                    );
+                    current = self.load_version(
+                        config,
+                        &mut loader,
+                        current_version,
+                        CacheSettings::WriteOnly(()),
+                    );
+                }
+            }
+
+            let audited = match (&baseline, &current) {
+                (Ok(baseline_crate), Ok(current_crate)) => {
+                    audit_pair(baseline_crate, current_crate).map_err(|err| format!("{err:#}"))
+                }
+                (Err(err), _) => Err(format!(
+                    "failed to generate rustdoc for v{baseline_version}: {err:#}"
+                )),
+                (_, Err(err)) => Err(format!(
+                    "failed to generate rustdoc for v{current_version}: {err:#}"
+                )),
+            };
+            let (audited, error) = match audited {
+                Ok(audited) => (audited, None),
+                Err(error) => (PairFindings::default(), Some(error)),
+            };
+
+            pairs.push(AuditedPair {
+                baseline_version: baseline_version.clone(),
+                current_version: current_version.clone(),
+                declared_bump: audited
+                    .version_policy
+                    .classify(baseline_version, current_version),
+                required_bump: audited.required_bump,
+                lints: audited.lints,
+                error,
+            });
+            baseline = current;
+        }
+
+        Ok(AuditReport { pairs })
+    }
+
+    /// Returns the versions to audit among the published `(version, is_yanked)` pairs,
+    /// oldest first.
+    fn audited_versions(
+        &self,
+        published: impl IntoIterator<Item = (semver::Version, bool)>,
+    ) -> Vec<semver::Version> {
+        published
+            .into_iter()
+            .filter(|(version, yanked)| {
+                (self.include_yanked || !yanked)
+                    && (self.include_prereleases || version.pre.is_empty())
+                    && self.since.as_ref().is_none_or(|since| version >= since)
+            })
+            .map(|(version, _)| version)
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    fn load_version(
+        &self,
+        config: &mut GlobalConfig,
+        loader: &mut RustdocFromRegistry,
+        version: &semver::Version,
+        cache_settings: CacheSettings<()>,
+    ) -> anyhow::Result<LoadedCrate> {
+        let generation_settings = GenerationSettings {
+            use_color: config.err_color_choice(),
+            deps: false,
+            pass_through_stderr: config.is_verbose(),
+            locked: false,
+            offline: false,
+        };
+
+        loader.set_version(version.clone());
+        let storage = loader.load_rustdoc(
+            config,
+            generation_settings,
+            cache_settings,
+            CrateDataForRustdoc {
+                crate_type: CrateType::Baseline {
+                    highest_allowed_version: None,
+                },
+                name: &self.crate_name,
+                feature_config: &FeatureConfig::default_for_baseline(),
+                build_target: None,
+                toolchain: None,
+            },
+        );
+        crate::log_terminal_error(config, storage)
+    }
+}
+
+/// What checking one release against the release before it found.
+#[derive(Default)]
+struct PairFindings {
+    /// The ids of the lints that fired, sorted.
+    lints: Vec<String>,
+    /// The bump required by the lints that aren't just warnings.
+    required_bump: Option<RequiredSemverUpdate>,
+    /// How the later version's number declares its kind of release.
+    version_policy: VersionPolicy,
+}
+
+/// Runs the lints between the two versions, with lint levels and the version policy
+/// configured as in the current version's manifest.
+fn audit_pair(baseline: &LoadedCrate, current: &LoadedCrate) -> anyhow::Result<PairFindings> {
+    let (overrides, version_policy) = match &current.package {
+        Some(package) => (
+            crate::package_overrides(None, package)?,
+            crate::manifest::deserialize_version_policy(&package.metadata)
+                .context("[package.metadata.cargo-semver-checks] table is invalid")?
+                .unwrap_or_default(),
+        ),
+        None => (OverrideStack::new(), VersionPolicy::default()),
+    };
+    let baseline_index = VersionedIndex::from_storage(&baseline.data);
+    let current_index = VersionedIndex::from_storage(&current.data);
+    let adapter = VersionedRustdocAdapter::new(&current_index, Some(&baseline_index))?;
+    let findings: BTreeMap<_, _> = crate::bisect::run_lints(&adapter, &overrides)?;
+    let lints: Vec<String> = findings
+        .into_keys()
+        .map(|(lint_id, _)| lint_id)
+        .dedup()
+        .collect();
+
+    let queries = SemverQuery::all_queries();
+    let required_bump = lints
+        .iter()
+        .filter_map(|id| queries.get(id))
+        .filter(|query| overrides.effective_lint_level(query) == LintLevel::Deny)
+        .map(|query| overrides.effective_required_update(query))
+        .max();
+    Ok(PairFindings {
+        lints,
+        required_bump,
+        version_policy,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Audit, AuditReport, AuditedPair};
+    use crate::query::{ActualSemverUpdate, RequiredSemverUpdate};
+    use crate::CheckOutcome;
+
+    fn pair(
+        declared_bump: ActualSemverUpdate,
+        required_bump: Option<RequiredSemverUpdate>,
+        error: Option<&str>,
+    ) -> AuditedPair {
+        AuditedPair {
+            baseline_version: semver::Version::new(1, 0, 0),
+            current_version: semver::Version::new(1, 0, 1),
+            declared_bump,
+            required_bump,
+            lints: vec![],
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn audit_exit_code() {
+        let ok = pair(ActualSemverUpdate::Patch, None, None);
+        let minor = pair(
+            ActualSemverUpdate::Patch,
+            Some(RequiredSemverUpdate::Minor),
+            None,
+        );
+        let major = pair(
+            ActualSemverUpdate::Minor,
+            Some(RequiredSemverUpdate::Major),
+            None,
+        );
+        let errored = pair(ActualSemverUpdate::Patch, None, Some("failed to build"));
+        let report = |pairs: &[&AuditedPair]| AuditReport {
+            pairs: pairs.iter().map(|&pair| pair.clone()).collect(),
+        };
+
+        let covered = pair(
+            ActualSemverUpdate::Major,
+            Some(RequiredSemverUpdate::Major),
+            None,
+        );
+        assert!(report(&[&ok, &covered]).success());
+        assert_eq!(report(&[&ok, &covered]).exit_code(), CheckOutcome::Success);
+
+        assert!(!report(&[&ok, &minor]).success());
+        assert_eq!(
+            report(&[&ok, &minor]).exit_code(),
+            CheckOutcome::MinorRequired
+        );
+        assert_eq!(
+            report(&[&minor, &major]).exit_code(),
+            CheckOutcome::MajorRequired
+        );
+
+        // Pairs that couldn't be checked don't count as respecting semver.
+        assert!(!report(&[&ok, &errored]).success());
+        assert_eq!(report(&[&major, &errored]).exit_code(), CheckOutcome::Error);
+    }
+
+    #[test]
+    fn audited_versions_are_filtered_and_sorted() {
+        let published = [
+            ("1.1.0", false),
+            ("1.0.0", false),
+            ("1.0.1", true),
+            ("1.1.0-rc.1", false),
+            ("2.0.0", false),
+        ]
+        .map(|(version, yanked)| (semver::Version::parse(version).unwrap(), yanked));
+        let audited = |audit: &Audit| {
+            audit
+                .audited_versions(published.clone())
+                .into_iter()
+                .map(|version| version.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let mut audit = Audit::new("my-crate");
+        assert_eq!(audited(&audit), ["1.0.0", "1.1.0", "2.0.0"]);
+
+        audit.set_include_yanked(true).set_include_prereleases(true);
+        assert_eq!(
+            audited(&audit),
+            ["1.0.0", "1.0.1", "1.1.0-rc.1", "1.1.0", "2.0.0"]
+        );
+
+        audit.set_since(semver::Version::new(1, 1, 0));
+        assert_eq!(audited(&audit), ["1.1.0", "2.0.0"]);
+    }
+}
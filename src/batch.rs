@@ -0,0 +1,272 @@
+//! Checking many crates in one run, as listed in a batch file.
+//!
+//! A [`Batch`] runs a [`Check`] for each listed crate with one shared [`GlobalConfig`],
+//! so registry indexes are opened once and all crates share the same thread pool.
+//!
+//! Batch files are TOML, or JSON if they have a `.json` extension:
+//!
+//! ```toml
+//! [[crates]]
+//! name = "my-crate"
+//! current = { root = "crates/my-crate" }
+//! baseline = { version = "1.2.0" }
+//! features = ["serde"]
+//! target = "x86_64-unknown-linux-gnu"
+//! ```
+//!
+//! Relative paths are resolved against the batch file's directory.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context as _};
+use clap::ValueEnum as _;
+use serde::Deserialize;
+
+use crate::{Check, CheckOutcome, GlobalConfig, ReleaseType, Report, Rustdoc};
+
+/// Semver-check many crates in one run.
+#[non_exhaustive]
+#[derive(Debug, Default)]
+pub struct Batch {
+    /// The name of each crate to check, and its check.
+    checks: Vec<(String, Check)>,
+}
+
+/// Outcome of running a [`Batch`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct BatchReport {
+    outcomes: Vec<(String, anyhow::Result<Report>)>,
+}
+
+impl BatchReport {
+    /// `true` if every crate was checked and none of them violate semver.
+    pub fn success(&self) -> bool {
+        self.outcomes
+            .iter()
+            .all(|(_, outcome)| outcome.as_ref().is_ok_and(Report::success))
+    }
+
+    /// The most severe outcome among all crates, for use as the exit code of a process
+    /// that ran the batch. Crates whose check failed with an error make the batch an error.
+    pub fn exit_code(&self) -> CheckOutcome {
+        self.outcomes
+            .iter()
+            .map(|(_, outcome)| match outcome {
+                Ok(report) => report.exit_code(),
+                Err(_) => CheckOutcome::Error,
+            })
+            .fold(CheckOutcome::Success, Ord::max)
+    }
+
+    /// The name of each crate in the batch with the outcome of its check, in batch order.
+    pub fn outcomes(&self) -> impl Iterator<Item = (&str, Result<&Report, &anyhow::Error>)> {
+        self.outcomes
+            .iter()
+            .map(|(name, outcome)| (name.as_str(), outcome.as_ref()))
+    }
+}
+
+/// The contents of a batch file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchFile {
+    crates: Vec<BatchEntry>,
+}
+
+/// One crate to check, as listed in a batch file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct BatchEntry {
+    /// The name of the package to check.
+    name: String,
+    /// Where to get the current version from. Defaults to the batch file's directory.
+    #[serde(default)]
+    current: Option<SourceEntry>,
+    /// Where to get the baseline from. Defaults to the latest version in the registry.
+    #[serde(default)]
+    baseline: Option<SourceEntry>,
+    #[serde(default)]
+    features: Vec<String>,
+    #[serde(default)]
+    all_features: bool,
+    #[serde(default)]
+    default_features: bool,
+    #[serde(default)]
+    target: Option<String>,
+    /// One of `major`, `minor` or `patch`.
+    #[serde(default)]
+    release_type: Option<String>,
+}
+
+/// Where to get one side of a check from. Exactly one of its sources must be set,
+/// except that `rev` is used together with `root`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct SourceEntry {
+    /// Directory containing the crate's source.
+    root: Option<PathBuf>,
+    /// Git revision of the repository at `root`.
+    rev: Option<String>,
+    /// Version in the registry.
+    version: Option<String>,
+    /// Pre-generated rustdoc JSON.
+    rustdoc: Option<PathBuf>,
+    /// A `.crate` file, or a directory of unpacked crate sources.
+    crate_file: Option<PathBuf>,
+}
+
+impl SourceEntry {
+    fn into_rustdoc(self, base_dir: &Path) -> anyhow::Result<Rustdoc> {
+        let Self {
+            root,
+            rev,
+            version,
+            rustdoc,
+            crate_file,
+        } = self;
+        let root = root.map(|root| base_dir.join(root));
+        Ok(match (root, rev, version, rustdoc, crate_file) {
+            (Some(root), None, None, None, None) => Rustdoc::from_root(root),
+            (root, Some(rev), None, None, None) => {
+                Rustdoc::from_git_revision(root.unwrap_or_else(|| base_dir.to_owned()), rev)
+            }
+            (None, None, Some(version), None, None) => Rustdoc::from_registry(version),
+            (None, None, None, Some(rustdoc), None) => Rustdoc::from_path(base_dir.join(rustdoc)),
+            (None, None, None, None, Some(crate_file)) => {
+                Rustdoc::from_crate_file(base_dir.join(crate_file))
+            }
+            _ => bail!(
+                "expected exactly one of `root`, `version`, `rustdoc` or `crate-file`, \
+                optionally with `rev` for a git revision of `root`"
+            ),
+        })
+    }
+}
+
+impl BatchEntry {
+    fn into_check(self, base_dir: &Path) -> anyhow::Result<Check> {
+        let current = match self.current {
+            Some(current) => current.into_rustdoc(base_dir)?,
+            None => Rustdoc::from_root(base_dir),
+        };
+        let mut check = Check::new(current);
+        check.set_packages(vec![self.name]);
+        if let Some(baseline) = self.baseline {
+            check.set_baseline(baseline.into_rustdoc(base_dir)?);
+        }
+
+        if self.all_features && self.default_features {
+            bail!("`all-features` and `default-features` can't be used together");
+        } else if self.all_features {
+            check.with_all_features();
+        } else if self.default_features {
+            check.with_default_features();
+        } else {
+            check.with_heuristically_included_features();
+        }
+        check.set_extra_features(self.features.clone(), self.features);
+
+        if let Some(target) = self.target {
+            check.set_build_target(target);
+        }
+        if let Some(release_type) = self.release_type {
+            let release_type = ReleaseType::from_str(&release_type, true)
+                .map_err(|err| anyhow::anyhow!("invalid `release-type`: {err}"))?;
+            check.set_release_type(release_type);
+        }
+        Ok(check)
+    }
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the crates to check from a batch file.
+    pub fn from_path(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let text = fs_err::read_to_string(path)?;
+        let file: BatchFile = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&text)?
+        } else {
+            toml::from_str(&text)?
+        };
+
+        let base_dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let mut batch = Self::new();
+        for entry in file.crates {
+            let name = entry.name.clone();
+            let check = entry
+                .into_check(base_dir)
+                .with_context(|| format!("invalid batch entry for crate `{name}`"))?;
+            batch.add_check(name, check);
+        }
+        Ok(batch)
+    }
+
+    /// Add a crate to check, reported under the given name.
+    pub fn add_check(&mut self, name: impl Into<String>, check: Check) -> &mut Self {
+        self.checks.push((name.into(), check));
+        self
+    }
+
+    /// Check every crate in the batch. A crate whose check fails with an error
+    /// doesn't stop the others from being checked.
+    pub fn run(&self, config: &mut GlobalConfig) -> anyhow::Result<BatchReport> {
+        let mut outcomes = Vec::with_capacity(self.checks.len());
+        for (name, check) in &self.checks {
+            let outcome = check.check_release(config);
+            if let Err(err) = &outcome {
+                config.shell_error(format_args!("{name}: {err:#}"))?;
+            }
+            outcomes.push((name.clone(), outcome));
+        }
+        Ok(BatchReport { outcomes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BatchFile;
+
+    #[test]
+    fn batch_file_sources() {
+        let file: BatchFile = toml::from_str(
+            r#"
+            [[crates]]
+            name = "a"
+
+            [[crates]]
+            name = "b"
+            current = { root = "crates/b" }
+            baseline = { root = "crates/b", rev = "v1.0.0" }
+            features = ["serde"]
+            release-type = "minor"
+
+            [[crates]]
+            name = "c"
+            baseline = { version = "1.0.0", rustdoc = "c.json" }
+
+            [[crates]]
+            name = "d"
+            all-features = true
+            default-features = true
+            "#,
+        )
+        .expect("valid batch file");
+        let base_dir = std::path::Path::new("batch");
+
+        let mut entries = file.crates.into_iter();
+        assert!(entries.next().unwrap().into_check(base_dir).is_ok());
+        assert!(entries.next().unwrap().into_check(base_dir).is_ok());
+        let err = entries.next().unwrap().into_check(base_dir).unwrap_err();
+        assert!(err.to_string().contains("expected exactly one of"), "{err}");
+        let err = entries.next().unwrap().into_check(base_dir).unwrap_err();
+        assert!(err.to_string().contains("can't be used together"), "{err}");
+    }
+}
@@ -0,0 +1,504 @@
+//! Finding the commits that introduced semver violations.
+//!
+//! A [`Bisect`] checks the crate at the current revision against a baseline revision,
+//! then binary-searches the first-parent history between them to find the first commit
+//! at which each violation appears. The rustdoc of each commit's tree is cached,
+//! so later runs over the same history don't need to generate it again.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Context;
+use itertools::Itertools;
+use trustfall::FieldValue;
+use trustfall_rustdoc::{VersionedIndex, VersionedRustdocAdapter, VersionedStorage};
+
+use crate::data_generation::{CacheSettings, GenerationSettings, TerminalError};
+use crate::query::{LintLevel, OverrideStack};
+use crate::rustdoc_gen::{self, CrateDataForRustdoc, CrateType, FeatureConfig, RustdocGenerator};
+use crate::{GlobalConfig, RustdocSource, SemverQuery};
+
+/// Find the commit that introduced each semver violation between two git revisions.
+///
+/// Only the first-parent history of the current revision is searched, and each violation
+/// is assumed to stay present once introduced. If a violation was introduced, fixed,
+/// and then reintroduced, any one of the commits that introduced it may be reported.
+/// Commits that can't be checked, for example because they don't build, are skipped.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bisect {
+    /// Directory containing the crate's source, inside its git repository.
+    project_root: PathBuf,
+    /// Revision without the violations, e.g. the tag of the previous release.
+    baseline_rev: String,
+    /// Revision with the violations to find the origin of.
+    current_rev: String,
+    /// Which package to check, if not the one at the project root.
+    package: Option<String>,
+}
+
+/// Outcome of running a [`Bisect`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct BisectReport {
+    findings: Vec<BisectFinding>,
+    skipped_commits: Vec<SkippedCommit>,
+}
+
+impl BisectReport {
+    /// Each semver violation present at the current revision, with the commit that introduced it.
+    pub fn findings(&self) -> &[BisectFinding] {
+        &self.findings
+    }
+
+    /// Commits between the baseline and the current revision that couldn't be checked,
+    /// for example because they don't build.
+    pub fn skipped_commits(&self) -> &[SkippedCommit] {
+        &self.skipped_commits
+    }
+
+    /// `true` if every commit the search needed could be checked,
+    /// so each finding is attributed to exactly one commit.
+    pub fn success(&self) -> bool {
+        self.skipped_commits.is_empty()
+    }
+}
+
+/// A semver violation, and the first commit at which it appeared.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BisectFinding {
+    lint_id: String,
+    item: String,
+    commit: String,
+    summary: String,
+    untested_before: usize,
+}
+
+impl BisectFinding {
+    /// The id of the lint that reported the violation.
+    pub fn lint_id(&self) -> &str {
+        &self.lint_id
+    }
+
+    /// The affected item, usually its importable path.
+    pub fn item(&self) -> &str {
+        &self.item
+    }
+
+    /// The full id of the first commit at which the violation appeared.
+    pub fn commit(&self) -> &str {
+        &self.commit
+    }
+
+    /// The first line of that commit's message.
+    pub fn summary(&self) -> &str {
+        &self.summary
+    }
+
+    /// How many of the commits right before [`BisectFinding::commit()`] couldn't be checked.
+    /// Any one of them may have introduced the violation instead.
+    pub fn untested_before(&self) -> usize {
+        self.untested_before
+    }
+
+    /// `true` if the violation is known to have been introduced by [`BisectFinding::commit()`].
+    pub fn is_attributed(&self) -> bool {
+        self.untested_before == 0
+    }
+}
+
+/// A commit that couldn't be checked while bisecting.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedCommit {
+    commit: String,
+    summary: String,
+    error: String,
+}
+
+impl SkippedCommit {
+    /// The full id of the commit.
+    pub fn commit(&self) -> &str {
+        &self.commit
+    }
+
+    /// The first line of the commit's message.
+    pub fn summary(&self) -> &str {
+        &self.summary
+    }
+
+    /// Why the commit couldn't be checked.
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+}
+
+/// A lint result's identity that stays the same across commits: the lint id,
+/// and all of the result's outputs except for the ones describing its location.
+type FindingKey = (String, String);
+
+struct Commit {
+    id: gix::ObjectId,
+    tree: gix::ObjectId,
+    summary: String,
+}
+
+impl Bisect {
+    pub fn new(project_root: impl Into<PathBuf>, baseline_rev: impl Into<String>) -> Self {
+        Self {
+            project_root: project_root.into(),
+            baseline_rev: baseline_rev.into(),
+            current_rev: "HEAD".into(),
+            package: None,
+        }
+    }
+
+    /// Set the revision with the violations to investigate. Defaults to `HEAD`.
+    pub fn set_current_rev(&mut self, current_rev: impl Into<String>) -> &mut Self {
+        self.current_rev = current_rev.into();
+        self
+    }
+
+    /// Set which package to check. Defaults to the package at the project root.
+    pub fn set_package(&mut self, package: impl Into<String>) -> &mut Self {
+        self.package = Some(package.into());
+        self
+    }
+
+    pub fn run(&self, config: &mut GlobalConfig) -> anyhow::Result<BisectReport> {
+        let crate_name = match &self.package {
+            Some(package) => package.clone(),
+            None => {
+                let manifest =
+                    crate::manifest::Manifest::parse(crate::manifest_path(&self.project_root)?)?;
+                crate::manifest::get_package_name(&manifest)
+                    .context("use `--package` to choose which package to bisect")?
+                    .to_string()
+            }
+        };
+        let overrides = crate::project_package_overrides(&self.project_root, &crate_name)?;
+        let commits = self.commit_range()?;
+        let target_dir = crate::get_target_dir_from_project_root(&RustdocSource::Root(
+            self.project_root.clone(),
+        ))?
+        .expect("project roots always have a target directory")
+        .join("git-bisect");
+
+        let (baseline, current) = (&commits[0], &commits[commits.len() - 1]);
+        let baseline_crate = self.load_commit(config, &target_dir, &crate_name, baseline, true)?;
+        let baseline_index = VersionedIndex::from_storage(&baseline_crate);
+
+        // Commits with the same tree have the same findings, so we only check each tree once.
+        let mut findings_by_tree: BTreeMap<gix::ObjectId, BTreeMap<FindingKey, String>> =
+            BTreeMap::new();
+        let mut findings_at = |config: &mut GlobalConfig,
+                               commit: &Commit|
+         -> anyhow::Result<BTreeMap<FindingKey, String>> {
+            if let Some(findings) = findings_by_tree.get(&commit.tree) {
+                return Ok(findings.clone());
+            }
+            let current_crate =
+                self.load_commit(config, &target_dir, &crate_name, commit, false)?;
+            let current_index = VersionedIndex::from_storage(&current_crate);
+            let adapter = VersionedRustdocAdapter::new(&current_index, Some(&baseline_index))?;
+            let findings = run_lints(&adapter, &overrides)?;
+            findings_by_tree.insert(commit.tree, findings.clone());
+            Ok(findings)
+        };
+
+        let current_findings = findings_at(config, current)?;
+        config.shell_status(
+            "Bisecting",
+            format_args!(
+                "{} findings across {} commits",
+                current_findings.len(),
+                commits.len() - 1
+            ),
+        )?;
+
+        // Commits that fail to build are skipped, as with `git bisect skip`.
+        let mut skipped_commits: Vec<SkippedCommit> = vec![];
+        let mut skipped_trees = BTreeSet::new();
+        let mut findings = vec![];
+        for (key, item) in current_findings {
+            // The baseline is known not to have the finding, and the current commit has it.
+            let (good, bad) = search(0, commits.len() - 1, |index| {
+                let commit = &commits[index];
+                if skipped_trees.contains(&commit.tree) {
+                    return Ok(None);
+                }
+                match findings_at(config, commit) {
+                    Ok(findings) => Ok(Some(findings.contains_key(&key))),
+                    Err(err) => {
+                        config.shell_warn(format_args!(
+                            "skipping commit {}, which couldn't be checked: {err:#}",
+                            commit.id
+                        ))?;
+                        skipped_trees.insert(commit.tree);
+                        skipped_commits.push(SkippedCommit {
+                            commit: commit.id.to_string(),
+                            summary: commit.summary.clone(),
+                            error: format!("{err:#}"),
+                        });
+                        Ok(None)
+                    }
+                }
+            })?;
+
+            let commit = &commits[bad];
+            findings.push(BisectFinding {
+                lint_id: key.0,
+                item,
+                commit: commit.id.to_string(),
+                summary: commit.summary.clone(),
+                untested_before: bad - good - 1,
+            });
+        }
+
+        Ok(BisectReport {
+            findings,
+            skipped_commits,
+        })
+    }
+
+    /// Returns the first-parent history from the baseline to the current revision, oldest first.
+    fn commit_range(&self) -> anyhow::Result<Vec<Commit>> {
+        let repo = gix::discover(&self.project_root)?;
+        let resolve = |rev: &str| {
+            repo.rev_parse_single(&*format!("{rev}^{{commit}}"))
+                .with_context(|| format!("failed to resolve revision `{rev}`"))
+        };
+        let baseline = resolve(&self.baseline_rev)?.detach();
+        let current = resolve(&self.current_rev)?.detach();
+
+        let mut commits = vec![];
+        for info in repo.rev_walk([current]).first_parent_only().all()? {
+            let info = info?;
+            let commit = info.object()?;
+            commits.push(Commit {
+                id: info.id,
+                tree: commit.tree_id()?.detach(),
+                summary: commit.message()?.summary().to_string(),
+            });
+            if info.id == baseline {
+                commits.reverse();
+                return Ok(commits);
+            }
+        }
+
+        anyhow::bail!(
+            "`{}` is not in the first-parent history of `{}`",
+            self.baseline_rev,
+            self.current_rev
+        )
+    }
+
+    fn load_commit(
+        &self,
+        config: &mut GlobalConfig,
+        target_dir: &std::path::Path,
+        crate_name: &str,
+        commit: &Commit,
+        is_baseline: bool,
+    ) -> anyhow::Result<VersionedStorage> {
+        let generation_settings = GenerationSettings {
+            use_color: config.err_color_choice(),
+            deps: false,
+            pass_through_stderr: config.is_verbose(),
+            locked: false,
+            offline: false,
+        };
+        let (crate_type, feature_config) = if is_baseline {
+            (
+                CrateType::Baseline {
+                    highest_allowed_version: None,
+                },
+                FeatureConfig::default_for_baseline(),
+            )
+        } else {
+            (CrateType::Current, FeatureConfig::default_for_current())
+        };
+
+        let loader = rustdoc_gen::RustdocFromGitRevision::with_rev(
+            &self.project_root,
+            target_dir,
+            &commit.id.to_string(),
+            config,
+        )?;
+        match loader.load_rustdoc(
+            config,
+            generation_settings,
+            CacheSettings::ReadWrite(()),
+            CrateDataForRustdoc {
+                crate_type,
+                name: crate_name,
+                feature_config: &feature_config,
+                build_target: None,
+                toolchain: None,
+            },
+        ) {
+            Ok(loaded) => Ok(loaded.data),
+            Err(TerminalError::WithAdvice(err, advice)) => {
+                config.log_error(|config| {
+                    writeln!(config.stderr(), "{advice}")?;
+                    Ok(())
+                })?;
+                Err(err)
+            }
+            Err(TerminalError::Other(err)) => Err(err),
+        }
+        .with_context(|| format!("failed to check commit {}", commit.id))
+    }
+}
+
+/// Binary-searches between `good`, which is known not to have a finding, and `bad`,
+/// which is known to have it, returning the last good and the first bad index.
+///
+/// `has_finding` returns `None` for indices that couldn't be checked. Those are skipped,
+/// so every index strictly between the returned ones couldn't be checked.
+fn search(
+    mut good: usize,
+    mut bad: usize,
+    mut has_finding: impl FnMut(usize) -> anyhow::Result<Option<bool>>,
+) -> anyhow::Result<(usize, usize)> {
+    let mut candidates: Vec<usize> = (good + 1..bad).collect();
+    while !candidates.is_empty() {
+        let mid = candidates[candidates.len() / 2];
+        match has_finding(mid)? {
+            Some(true) => {
+                bad = mid;
+                candidates.retain(|&index| index < mid);
+            }
+            Some(false) => {
+                good = mid;
+                candidates.retain(|&index| index > mid);
+            }
+            None => candidates.retain(|&index| index != mid),
+        }
+    }
+    Ok((good, bad))
+}
+
+/// Runs all the lints that aren't allowed, given the overrides,
+/// returning a description of the item affected by each finding.
+pub(crate) fn run_lints(
+    adapter: &VersionedRustdocAdapter<'_>,
+    overrides: &OverrideStack,
+) -> anyhow::Result<BTreeMap<FindingKey, String>> {
+    let mut findings = BTreeMap::new();
+    for semver_query in SemverQuery::all_queries().values() {
+        if overrides.effective_lint_level(semver_query) == LintLevel::Allow {
+            continue;
+        }
+        for result in semver_query.run(adapter)? {
+            let (identity, item) = describe_result(&result);
+            findings.insert((semver_query.id.clone(), identity), item);
+        }
+    }
+    Ok(findings)
+}
+
+/// Returns the result's identity across commits, and a description of the affected item.
+fn describe_result(result: &BTreeMap<Arc<str>, FieldValue>) -> (String, String) {
+    // Values of `@fold`-ed outputs are listed in no particular order, so they're sorted.
+    let identity = result
+        .iter()
+        .filter(|(name, _)| !name.starts_with("span_"))
+        .map(|(name, value)| match value.as_slice() {
+            Some(values) => {
+                let values = values.iter().map(|value| format!("{value:?}")).sorted();
+                format!("{name}=[{}]", values.format(", "))
+            }
+            None => format!("{name}={value:?}"),
+        })
+        .join(", ");
+
+    let item = match result
+        .get("path")
+        .and_then(|path| path.as_vec_with(FieldValue::as_str))
+    {
+        Some(path) => path.join("::"),
+        None => match result.get("name").and_then(FieldValue::as_str) {
+            Some(name) => name.to_string(),
+            None => identity.clone(),
+        },
+    };
+    (identity, item)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use trustfall::FieldValue;
+
+    use super::{describe_result, search};
+
+    #[test]
+    fn result_identity_ignores_spans() {
+        let result = |line: u64| -> BTreeMap<Arc<str>, FieldValue> {
+            BTreeMap::from([
+                (Arc::from("name"), FieldValue::from("foo")),
+                (
+                    Arc::from("path"),
+                    FieldValue::from(vec!["my_crate", "a", "foo"]),
+                ),
+                (Arc::from("span_begin_line"), FieldValue::Uint64(line)),
+            ])
+        };
+
+        let (identity, item) = describe_result(&result(4));
+        assert_eq!(item, "my_crate::a::foo");
+        assert_eq!(describe_result(&result(12)).0, identity);
+    }
+
+    #[test]
+    fn result_identity_ignores_fold_order() {
+        let result = |variants: Vec<&str>| -> BTreeMap<Arc<str>, FieldValue> {
+            BTreeMap::from([
+                (Arc::from("name"), FieldValue::from("Foo")),
+                (Arc::from("variants"), FieldValue::from(variants)),
+            ])
+        };
+
+        assert_eq!(
+            describe_result(&result(vec!["A", "B"])).0,
+            describe_result(&result(vec!["B", "A"])).0,
+        );
+    }
+
+    #[test]
+    fn search_finds_first_bad_index() {
+        for first_bad in 1..=9 {
+            let found = search(0, 9, |index| Ok(Some(index >= first_bad))).unwrap();
+            assert_eq!(found, (first_bad - 1, first_bad));
+        }
+    }
+
+    #[test]
+    fn search_skips_unchecked_indices() {
+        // Index 5 introduced the finding, but 4 and 5 don't build.
+        let found = search(0, 9, |index| {
+            Ok(match index {
+                4 | 5 => None,
+                _ => Some(index >= 5),
+            })
+        })
+        .unwrap();
+        assert_eq!(found, (3, 6));
+
+        // Skipped indices that aren't right before the first bad one don't matter.
+        let found = search(0, 9, |index| {
+            Ok(match index {
+                2 => None,
+                _ => Some(index >= 5),
+            })
+        })
+        .unwrap();
+        assert_eq!(found, (4, 5));
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 
+use crate::progress::ProgressEvent;
 use crate::GlobalConfig;
 
 pub(crate) struct Callbacks<'a> {
@@ -21,7 +22,50 @@ impl<'a> Callbacks<'a> {
 }
 
 impl<'a> crate::data_generation::ProgressCallbacks<'a> for Callbacks<'a> {
+    fn generate_placeholder_project_start(
+        &mut self,
+        crate_name: &'a str,
+        version: &'a str,
+        is_baseline: bool,
+    ) {
+        self.config
+            .progress_event(ProgressEvent::GeneratePlaceholderProjectStart {
+                crate_name: crate_name.to_string(),
+                version: version.to_string(),
+                is_baseline,
+            });
+    }
+
+    fn generate_placeholder_project_success(
+        &mut self,
+        crate_name: &'a str,
+        version: &'a str,
+        is_baseline: bool,
+    ) {
+        self.config
+            .progress_event(ProgressEvent::GeneratePlaceholderProjectSuccess {
+                crate_name: crate_name.to_string(),
+                version: version.to_string(),
+                is_baseline,
+            });
+    }
+
+    fn rustdoc_cache_hit(&mut self, crate_name: &'a str, version: &'a str, is_baseline: bool) {
+        self.config.progress_event(ProgressEvent::RustdocCacheHit {
+            crate_name: crate_name.to_string(),
+            version: version.to_string(),
+            is_baseline,
+        });
+    }
+
     fn generate_rustdoc_start(&mut self, crate_name: &'a str, version: &'a str, is_baseline: bool) {
+        self.config
+            .progress_event(ProgressEvent::GenerateRustdocStart {
+                crate_name: crate_name.to_string(),
+                version: version.to_string(),
+                is_baseline,
+            });
+
         let kind = if is_baseline { "baseline" } else { "current" };
 
         // Ignore terminal printing failures.
@@ -47,12 +91,35 @@ impl<'a> crate::data_generation::ProgressCallbacks<'a> for Callbacks<'a> {
             .generate_starts
             .remove(&(crate_name, version, is_baseline))
             .expect("success on generation task that never started");
+        let duration = start.elapsed();
 
         // Ignore terminal printing failures.
         let _ = self.config.shell_status(
             "Built",
-            format_args!("[{:>8.3}s] ({kind})", start.elapsed().as_secs_f32()),
+            format_args!("[{:>8.3}s] ({kind})", duration.as_secs_f32()),
         );
+
+        self.config
+            .progress_event(ProgressEvent::GenerateRustdocSuccess {
+                crate_name: crate_name.to_string(),
+                version: version.to_string(),
+                is_baseline,
+                duration,
+            });
+    }
+
+    fn rustdoc_cache_populated(
+        &mut self,
+        crate_name: &'a str,
+        version: &'a str,
+        is_baseline: bool,
+    ) {
+        self.config
+            .progress_event(ProgressEvent::RustdocCachePopulated {
+                crate_name: crate_name.to_string(),
+                version: version.to_string(),
+                is_baseline,
+            });
     }
 
     fn parse_rustdoc_start(
@@ -62,6 +129,14 @@ impl<'a> crate::data_generation::ProgressCallbacks<'a> for Callbacks<'a> {
         version: &'a str,
         is_baseline: bool,
     ) {
+        self.config
+            .progress_event(ProgressEvent::ParseRustdocStart {
+                crate_name: crate_name.to_string(),
+                version: version.to_string(),
+                is_baseline,
+                cached,
+            });
+
         let kind = if is_baseline { "baseline" } else { "current" };
         let cached = if cached { ", cached" } else { "" };
 
@@ -79,7 +154,7 @@ impl<'a> crate::data_generation::ProgressCallbacks<'a> for Callbacks<'a> {
 
     fn parse_rustdoc_success(
         &mut self,
-        _cached: bool,
+        cached: bool,
         crate_name: &'a str,
         version: &'a str,
         is_baseline: bool,
@@ -89,12 +164,22 @@ impl<'a> crate::data_generation::ProgressCallbacks<'a> for Callbacks<'a> {
             .parse_starts
             .remove(&(crate_name, version, is_baseline))
             .expect("success on parse task that never started");
+        let duration = start.elapsed();
 
         // Ignore terminal printing failures.
         let _ = self.config.shell_status(
             "Parsed",
-            format_args!("[{:>8.3}s] ({kind})", start.elapsed().as_secs_f32()),
+            format_args!("[{:>8.3}s] ({kind})", duration.as_secs_f32()),
         );
+
+        self.config
+            .progress_event(ProgressEvent::ParseRustdocSuccess {
+                crate_name: crate_name.to_string(),
+                version: version.to_string(),
+                is_baseline,
+                cached,
+                duration,
+            });
     }
 
     fn non_fatal_error(
@@ -104,6 +189,13 @@ impl<'a> crate::data_generation::ProgressCallbacks<'a> for Callbacks<'a> {
         is_baseline: bool,
         error: anyhow::Error,
     ) {
+        self.config.progress_event(ProgressEvent::NonFatalError {
+            crate_name: crate_name.to_string(),
+            version: version.to_string(),
+            is_baseline,
+            message: format!("{error:#}"),
+        });
+
         let kind = if is_baseline { "baseline" } else { "current" };
 
         // Ignore terminal printing failures.
@@ -1,5 +1,9 @@
 use std::io::Write as _;
-use std::{collections::BTreeMap, sync::Arc, time::Instant};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    time::Instant,
+};
 
 use anstyle::{AnsiColor, Color, Reset, Style};
 
@@ -7,22 +11,130 @@ use anyhow::Context;
 use clap::crate_version;
 use itertools::Itertools;
 use rayon::prelude::*;
+use serde::Deserialize;
 use trustfall::{FieldValue, TransparentValue};
 
 use crate::data_generation::DataStorage;
+use crate::downstream::Downstream;
+use crate::progress::ProgressEvent;
+use crate::rename_hints::RenameHints;
+use crate::shims::TraitMethodDeclarations;
+use crate::snippets::{self, SnippetSources};
+use crate::targets::{self, TargetChange};
 use crate::{
     query::{ActualSemverUpdate, LintLevel, OverrideStack, RequiredSemverUpdate, SemverQuery},
     CrateReport, GlobalConfig, ReleaseType, WitnessGeneration,
 };
 
+#[cfg(test)]
 fn classify_semver_version_change(
     current_version: Option<&str>,
     baseline_version: Option<&str>,
 ) -> Option<ActualSemverUpdate> {
-    if let (Some(baseline), Some(current)) = (baseline_version, current_version) {
-        let baseline_version =
-            semver::Version::parse(baseline).expect("baseline not a valid version");
-        let current_version = semver::Version::parse(current).expect("current not a valid version");
+    VersionPolicy::default().classify_version_strings(current_version, baseline_version)
+}
+
+/// How the difference between the baseline and current version numbers maps to
+/// the kind of release that the current version declares.
+///
+/// The default is Cargo's interpretation of `0.y.z` versions, where changes in `y` are major
+/// and changes in `z` are minor. Other policies start from a [`VersionPolicyPreset`]
+/// and can override the kind of release declared by each kind of version change.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionPolicy {
+    /// Declared by `0.y.z` -> `0.y'.z'`.
+    zero_minor: ReleaseType,
+    /// Declared by `0.y.z` -> `0.y.z'`, where `y` isn't zero.
+    zero_patch: ReleaseType,
+    /// Declared by `0.0.z` -> `0.0.z'`.
+    zero_zero_patch: ReleaseType,
+    /// Declared by any change to or from a pre-release version, if set.
+    /// Otherwise, such changes are classified by their version numbers.
+    prerelease: Option<ReleaseType>,
+}
+
+/// Named starting points for a [`VersionPolicy`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionPolicyPreset {
+    /// Cargo's interpretation of versions: in `0.y.z`, changes in `y` are major
+    /// and changes in `z` are minor, and `0.0.z` changes are always major.
+    Cargo,
+    /// Like `cargo`, but `0.y.z` patch releases are patch-only:
+    /// minor-level changes require `0.(y+1)`.
+    StrictZero,
+    /// Like `cargo`, but any change to or from a pre-release version such as `-rc.N`
+    /// may be breaking.
+    PrereleaseTrains,
+}
+
+impl Default for VersionPolicy {
+    fn default() -> Self {
+        Self::preset(VersionPolicyPreset::Cargo)
+    }
+}
+
+impl VersionPolicy {
+    pub fn preset(preset: VersionPolicyPreset) -> Self {
+        let cargo = Self {
+            zero_minor: ReleaseType::Major,
+            zero_patch: ReleaseType::Minor,
+            zero_zero_patch: ReleaseType::Major,
+            prerelease: None,
+        };
+        match preset {
+            VersionPolicyPreset::Cargo => cargo,
+            VersionPolicyPreset::StrictZero => Self {
+                zero_patch: ReleaseType::Patch,
+                ..cargo
+            },
+            VersionPolicyPreset::PrereleaseTrains => Self {
+                prerelease: Some(ReleaseType::Major),
+                ..cargo
+            },
+        }
+    }
+
+    /// Set the kind of release declared by `0.y.z` -> `0.y'.z'`.
+    pub fn set_zero_minor(&mut self, release_type: ReleaseType) -> &mut Self {
+        self.zero_minor = release_type;
+        self
+    }
+
+    /// Set the kind of release declared by `0.y.z` -> `0.y.z'`, where `y` isn't zero.
+    pub fn set_zero_patch(&mut self, release_type: ReleaseType) -> &mut Self {
+        self.zero_patch = release_type;
+        self
+    }
+
+    /// Set the kind of release declared by `0.0.z` -> `0.0.z'`.
+    pub fn set_zero_zero_patch(&mut self, release_type: ReleaseType) -> &mut Self {
+        self.zero_zero_patch = release_type;
+        self
+    }
+
+    /// Set the kind of release declared by any change to or from a pre-release version.
+    pub fn set_prerelease(&mut self, release_type: ReleaseType) -> &mut Self {
+        self.prerelease = Some(release_type);
+        self
+    }
+
+    /// The kind of release that `current` declares relative to `baseline`.
+    pub fn classify(
+        &self,
+        baseline: &semver::Version,
+        current: &semver::Version,
+    ) -> ActualSemverUpdate {
+        let numbers_changed = (baseline.major, baseline.minor, baseline.patch)
+            != (current.major, current.minor, current.patch);
+        let is_prerelease_change = !baseline.pre.is_empty() || !current.pre.is_empty();
+        if let Some(prerelease) = self.prerelease {
+            if is_prerelease_change && (numbers_changed || baseline.pre != current.pre) {
+                return prerelease.into();
+            }
+        }
 
         // From the cargo reference:
         // > Initial development releases starting with "0.y.z" can treat changes
@@ -31,46 +143,80 @@ fn classify_semver_version_change(
         // > the convention that only changes in the left-most non-zero component
         // > are considered incompatible.
         // https://doc.rust-lang.org/cargo/reference/semver.html
-        let update_kind = if baseline_version.major != current_version.major {
+        if baseline.major != current.major {
             ActualSemverUpdate::Major
-        } else if baseline_version.minor != current_version.minor {
-            if current_version.major == 0 {
-                ActualSemverUpdate::Major
+        } else if baseline.minor != current.minor {
+            if current.major == 0 {
+                self.zero_minor.into()
             } else {
                 ActualSemverUpdate::Minor
             }
-        } else if baseline_version.patch != current_version.patch {
-            if current_version.major == 0 {
-                if current_version.minor == 0 {
-                    ActualSemverUpdate::Major
+        } else if baseline.patch != current.patch {
+            if current.major == 0 {
+                if current.minor == 0 {
+                    self.zero_zero_patch.into()
                 } else {
-                    ActualSemverUpdate::Minor
+                    self.zero_patch.into()
                 }
             } else {
                 ActualSemverUpdate::Patch
             }
-        } else if baseline_version.pre != current_version.pre {
+        } else if baseline.pre != current.pre {
             // > A pre-release version indicates that the version is unstable and might not satisfy
             // > the intended compatibility requirements as denoted by its associated normal version
             // https://semver.org/#spec-item-9
             ActualSemverUpdate::Major
         } else {
             ActualSemverUpdate::NotChanged
-        };
+        }
+    }
 
-        Some(update_kind)
-    } else {
-        None
+    fn classify_version_strings(
+        &self,
+        current_version: Option<&str>,
+        baseline_version: Option<&str>,
+    ) -> Option<ActualSemverUpdate> {
+        let (Some(baseline), Some(current)) = (baseline_version, current_version) else {
+            return None;
+        };
+        let baseline = semver::Version::parse(baseline).expect("baseline not a valid version");
+        let current = semver::Version::parse(current).expect("current not a valid version");
+        Some(self.classify(&baseline, &current))
     }
 }
 
 /// Helper function to print details about a triggered lint.
 fn print_triggered_lint(
     config: &mut GlobalConfig,
+    adapter: &trustfall_rustdoc::VersionedRustdocAdapter<'_>,
     semver_query: &SemverQuery,
     results: Vec<BTreeMap<Arc<str>, FieldValue>>,
     witness_generation: &WitnessGeneration,
-) -> anyhow::Result<()> {
+    extras: &ReportExtras,
+) -> anyhow::Result<BTreeSet<String>> {
+    // Both only depend on the lint, not on the individual results.
+    let snippets = extras.snippet_sources.as_ref().map(|sources| {
+        (
+            sources,
+            snippets::span_outputs(adapter.schema(), &semver_query.query),
+        )
+    });
+    let shim_template = semver_query
+        .shim_template
+        .as_deref()
+        .filter(|_| extras.shims);
+    // Shims for missing items need to know where the item went.
+    let rename_hints = if extras.rename_hints || shim_template.is_some() {
+        RenameHints::for_lint(adapter, semver_query)?
+    } else {
+        None
+    };
+    // Shims for newly-required trait methods need the method's declaration.
+    let trait_methods = match shim_template {
+        Some(_) => TraitMethodDeclarations::for_lint(adapter, semver_query)?,
+        None => None,
+    };
+
     if let Some(ref_link) = semver_query.reference_link.as_deref() {
         config.log_info(|config| {
             writeln!(config.stdout(), "{}Description:{}\n{}\n{:>12} {}\n{:>12} https://github.com/obi1kenobi/cargo-semver-checks/tree/v{}/src/lints/{}.ron\n",
@@ -110,6 +256,7 @@ fn print_triggered_lint(
         Ok(())
     })?;
 
+    let mut affected_downstream = BTreeSet::new();
     for semver_violation_result in results {
         let pretty_result: BTreeMap<Arc<str>, TransparentValue> = semver_violation_result
             .into_iter()
@@ -152,6 +299,78 @@ fn print_triggered_lint(
             })?;
         }
 
+        if let Some((sources, spans)) = &snippets {
+            for snippet in sources.render_result(spans, &pretty_result) {
+                config.log_info(|config| {
+                    writeln!(config.stdout(), "{snippet}\n")?;
+                    Ok(())
+                })?;
+            }
+        }
+
+        let relocation = rename_hints
+            .as_ref()
+            .and_then(|hints| hints.find_for_result(&pretty_result));
+        let trait_method_values = trait_methods
+            .as_ref()
+            .and_then(|declarations| declarations.template_values(&pretty_result));
+        let shim = match shim_template {
+            // Without knowing where a missing item went, there's nothing to suggest.
+            Some(_) if rename_hints.is_some() && relocation.is_none() => None,
+            // Likewise without the declaration of a newly-required trait method.
+            Some(_) if trait_methods.is_some() && trait_method_values.is_none() => None,
+            Some(template) => {
+                let mut values = pretty_result.clone();
+                if let (Some(hints), Some((missing_path, relocation))) =
+                    (&rename_hints, &relocation)
+                {
+                    values.extend(hints.template_values(missing_path, relocation));
+                }
+                values.extend(trait_method_values.into_iter().flatten());
+                Some(
+                    config
+                        .handlebars()
+                        .render_template(template, &values)
+                        .context("Error instantiating shim template.")?,
+                )
+            }
+            None => None,
+        };
+
+        if let Some((missing_path, relocation)) = relocation.filter(|_| extras.rename_hints) {
+            config.log_info(|config| {
+                let note = Style::new()
+                    .fg_color(Some(Color::Ansi(AnsiColor::Cyan)))
+                    .bold();
+                match relocation.shim(&missing_path).filter(|_| shim.is_none()) {
+                    Some((module, shim)) => writeln!(
+                        config.stdout(),
+                        "{note}note:{note:#} {}; re-exporting it in `{module}` would restore compatibility:\n\
+                        {shim}\n",
+                        relocation.describe(),
+                    )?,
+                    // Either the shim suggested below restores compatibility,
+                    // or the item is a method, which can't be re-exported.
+                    None => writeln!(config.stdout(), "{note}note:{note:#} {}\n", relocation.describe())?,
+                }
+                Ok(())
+            })?;
+        }
+
+        if let Some(shim) = shim {
+            config.log_info(|config| {
+                let help = Style::new()
+                    .fg_color(Some(Color::Ansi(AnsiColor::Cyan)))
+                    .bold();
+                writeln!(
+                    config.stdout(),
+                    "{help}help:{help:#} code like the following could restore compatibility:\n\
+                    {shim}\n"
+                )?;
+                Ok(())
+            })?;
+        }
+
         if let Some(witness) = &semver_query.witness {
             if witness_generation.show_hints {
                 let message = config
@@ -172,25 +391,114 @@ fn print_triggered_lint(
                 })?;
             }
         }
+
+        if extras.downstream.is_empty() {
+            continue;
+        }
+        let Some(path) = result_path(&pretty_result) else {
+            config.log_info(|config| {
+                let note = Style::new()
+                    .fg_color(Some(Color::Ansi(AnsiColor::Cyan)))
+                    .bold();
+                writeln!(
+                    config.stdout(),
+                    "{note}note:{note:#} not checked against the downstream crates, \
+                    since `{}` doesn't report the item's path\n",
+                    semver_query.id
+                )?;
+                Ok(())
+            })?;
+            continue;
+        };
+        let users = extras
+            .downstream
+            .iter()
+            .filter(|downstream| downstream.uses(&path))
+            .map(Downstream::name)
+            .collect_vec();
+        config.log_info(|config| {
+            let note = Style::new()
+                .fg_color(Some(Color::Ansi(AnsiColor::Cyan)))
+                .bold();
+            if users.is_empty() {
+                writeln!(
+                    config.stdout(),
+                    "{note}note:{note:#} not used by any of the downstream crates\n"
+                )?;
+            } else {
+                writeln!(
+                    config.stdout(),
+                    "{note}note:{note:#} used by downstream crates: {}\n",
+                    users.join(", ")
+                )?;
+            }
+            Ok(())
+        })?;
+        affected_downstream.extend(users.into_iter().map(ToString::to_string));
     }
 
-    Ok(())
+    Ok(affected_downstream)
+}
+
+/// The importable path of the item a lint result reports, if the lint outputs one.
+pub(crate) fn result_path(result: &BTreeMap<Arc<str>, TransparentValue>) -> Option<Vec<String>> {
+    let Some(TransparentValue::List(path)) = result.get("path") else {
+        return None;
+    };
+    path.iter()
+        .map(|segment| match segment {
+            TransparentValue::String(segment) => Some(segment.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The names of the features of the current version.
+fn current_feature_names(
+    adapter: &trustfall_rustdoc::VersionedRustdocAdapter<'_>,
+) -> anyhow::Result<BTreeSet<String>> {
+    let query = "{ CrateDiff { current { feature { name @output } } } }";
+    Ok(adapter
+        .run_query(query, BTreeMap::<&str, FieldValue>::new())?
+        .filter_map(|result| Some(result.get("name")?.as_str()?.to_string()))
+        .collect())
 }
 
+/// Information reported alongside the lint results, beyond the results themselves.
+#[derive(Debug, Default)]
+pub(crate) struct ReportExtras {
+    /// Where to find the source code for snippets of each result's span, if they should be shown.
+    pub(crate) snippet_sources: Option<SnippetSources>,
+    /// Whether to suggest where missing items may have been renamed or moved to.
+    pub(crate) rename_hints: bool,
+    /// Whether to suggest compatibility shims, for lints that define a shim template.
+    pub(crate) shims: bool,
+    /// Describes where the baseline came from, e.g. the git commit it was generated from.
+    pub(crate) baseline_label: Option<String>,
+    /// Downstream crates to cross-reference the lint results with.
+    pub(crate) downstream: Vec<Downstream>,
+    /// Breaking changes to the library target, reported like lint results at their lint level.
+    pub(crate) target_changes: Vec<TargetChange>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(super) fn run_check_release(
     config: &mut GlobalConfig,
     data_storage: &DataStorage,
     crate_name: &str,
     release_type: Option<ReleaseType>,
+    version_policy: &VersionPolicy,
     overrides: &OverrideStack,
+    renamed_features: &BTreeMap<String, String>,
     witness_generation: &WitnessGeneration,
+    extras: &ReportExtras,
 ) -> anyhow::Result<CrateReport> {
     let current_version = data_storage.current_crate().crate_version();
     let baseline_version = data_storage.baseline_crate().crate_version();
 
     let version_change = release_type
         .map(Into::into)
-        .or_else(|| classify_semver_version_change(current_version, baseline_version))
+        .or_else(|| version_policy.classify_version_strings(current_version, baseline_version))
         .unwrap_or_else(|| {
             config
                 .shell_warn(
@@ -219,11 +527,25 @@ pub(super) fn run_check_release(
                 && overrides.effective_lint_level(query) > LintLevel::Allow
         });
     let skipped_queries = queries_to_skip.len();
+    // Library target changes are skipped like lints, by their lint level and required bump.
+    let (target_failures, target_warnings): (Vec<&TargetChange>, Vec<_>) = extras
+        .target_changes
+        .iter()
+        .filter(|change| {
+            !version_change.supports_requirement(change.required_update(overrides))
+                && change.lint_level(overrides) > LintLevel::Allow
+        })
+        .partition(|change| change.lint_level(overrides) == LintLevel::Deny);
 
+    let baseline_label = extras
+        .baseline_label
+        .as_deref()
+        .map(|label| format!(" ({label})"))
+        .unwrap_or_default();
     config.shell_status(
         "Checking",
         format_args!(
-            "{crate_name} v{} -> v{} ({}{} change)",
+            "{crate_name} v{}{baseline_label} -> v{} ({}{} change)",
             baseline_version.unwrap_or("unknown"),
             current_version.unwrap_or("unknown"),
             assume,
@@ -256,20 +578,58 @@ pub(super) fn run_check_release(
         .expect("print failed");
 
     let queries_start_instant = Instant::now();
-    let all_results = queries_to_run
+    let progress = config.progress();
+    let mut all_results = queries_to_run
         .par_iter()
         .map(|semver_query| {
+            if let Some(progress) = &progress {
+                progress.emit(ProgressEvent::QueryStart {
+                    crate_name: crate_name.to_string(),
+                    query_id: semver_query.id.clone(),
+                });
+            }
             let start_instant = std::time::Instant::now();
-            // trustfall::execute_query(...) -> dyn Iterator (without Send)
-            // thus the result must be collect()'ed
-            let results = adapter
-                .run_query(&semver_query.query, semver_query.arguments.clone())?
-                .collect_vec();
+            let results = semver_query.run(&adapter)?;
             let time_to_decide = start_instant.elapsed();
+            if let Some(progress) = &progress {
+                progress.emit(ProgressEvent::QueryFinish {
+                    crate_name: crate_name.to_string(),
+                    query_id: semver_query.id.clone(),
+                    duration: time_to_decide,
+                    results: results.len(),
+                });
+            }
             Ok((semver_query, time_to_decide, results))
         })
         .collect::<anyhow::Result<Vec<_>>>()?;
 
+    // Features that were renamed aren't missing, as long as they exist under their new name.
+    if !renamed_features.is_empty() {
+        if let Some((_, _, results)) = all_results
+            .iter_mut()
+            .find(|(semver_query, _, _)| semver_query.id == "feature_missing")
+        {
+            let current_features = current_feature_names(&adapter)?;
+            results.retain(|result| {
+                let renamed = result
+                    .get("name")
+                    .and_then(FieldValue::as_str)
+                    .and_then(|name| Some((name, renamed_features.get(name)?)))
+                    .filter(|(_, current_name)| current_features.contains(current_name.as_str()));
+                if let Some((name, current_name)) = renamed {
+                    config
+                        .log_verbose(|config| {
+                            config.shell_note(format_args!(
+                                "feature `{name}` was renamed to `{current_name}`"
+                            ))
+                        })
+                        .expect("print failed");
+                }
+                renamed.is_none()
+            });
+        }
+    }
+
     let mut results_with_errors = vec![];
     let mut results_with_warnings = vec![];
     for (semver_query, time_to_decide, results) in all_results {
@@ -319,8 +679,9 @@ pub(super) fn run_check_release(
         }
     }
 
-    let produced_errors = !results_with_errors.is_empty();
-    let produced_warnings = !results_with_warnings.is_empty();
+    let target_changes = target_failures.len() + target_warnings.len();
+    let produced_errors = !results_with_errors.is_empty() || !target_failures.is_empty();
+    let produced_warnings = !results_with_warnings.is_empty() || !target_warnings.is_empty();
     if produced_errors || produced_warnings {
         let status_color = if produced_errors {
             AnsiColor::Red
@@ -333,10 +694,10 @@ pub(super) fn run_check_release(
                 format_args!(
                     "[{:>8.3}s] {} checks: {} pass, {} fail, {} warn, {} skip",
                     queries_start_instant.elapsed().as_secs_f32(),
-                    queries_to_run.len(),
+                    queries_to_run.len() + target_changes,
                     queries_to_run.len() - results_with_errors.len() - results_with_warnings.len(),
-                    results_with_errors.len(),
-                    results_with_warnings.len(),
+                    results_with_errors.len() + target_failures.len(),
+                    results_with_warnings.len() + target_warnings.len(),
                     skipped_queries,
                 ),
                 Color::Ansi(status_color),
@@ -344,37 +705,62 @@ pub(super) fn run_check_release(
             )
             .expect("print failed");
 
-        let mut required_versions = vec![];
-        let mut suggested_versions = vec![];
+        targets::print_target_changes(config, "failure", target_failures.iter().copied())?;
+        targets::print_target_changes(config, "warning", target_warnings.iter().copied())?;
 
-        for (semver_query, results) in results_with_errors {
-            required_versions.push(overrides.effective_required_update(semver_query));
-            config.log_info(|config| {
-                writeln!(
-                    config.stdout(),
-                    "\n--- failure {}: {} ---\n",
-                    &semver_query.id,
-                    &semver_query.human_readable_name
-                )?;
-                Ok(())
-            })?;
-
-            print_triggered_lint(config, semver_query, results, witness_generation)?;
-        }
+        let required_versions = target_failures
+            .iter()
+            .map(|change| change.required_update(overrides))
+            .chain(
+                results_with_errors
+                    .iter()
+                    .map(|(semver_query, _)| overrides.effective_required_update(semver_query)),
+            )
+            .collect_vec();
+        let suggested_versions = target_warnings
+            .iter()
+            .map(|change| change.required_update(overrides))
+            .chain(
+                results_with_warnings
+                    .iter()
+                    .map(|(semver_query, _)| overrides.effective_required_update(semver_query)),
+            )
+            .collect_vec();
 
-        for (semver_query, results) in results_with_warnings {
-            suggested_versions.push(overrides.effective_required_update(semver_query));
+        let mut downstream_lints: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let triggered_lints = results_with_errors
+            .into_iter()
+            .map(|(semver_query, results)| ("failure", semver_query, results))
+            .chain(
+                results_with_warnings
+                    .into_iter()
+                    .map(|(semver_query, results)| ("warning", semver_query, results)),
+            );
+        for (kind, semver_query, results) in triggered_lints {
             config.log_info(|config| {
                 writeln!(
                     config.stdout(),
-                    "\n--- warning {}: {} ---\n",
+                    "\n--- {kind} {}: {} ---\n",
                     semver_query.id,
                     semver_query.human_readable_name
                 )?;
                 Ok(())
             })?;
 
-            print_triggered_lint(config, semver_query, results, witness_generation)?;
+            let affected_downstream = print_triggered_lint(
+                config,
+                &adapter,
+                semver_query,
+                results,
+                witness_generation,
+                extras,
+            )?;
+            for name in affected_downstream {
+                downstream_lints
+                    .entry(name)
+                    .or_default()
+                    .insert(semver_query.id.clone());
+            }
         }
 
         let required_bump = required_versions.iter().max().copied();
@@ -439,9 +825,48 @@ pub(super) fn run_check_release(
             }
         }
 
+        for downstream in &extras.downstream {
+            match downstream_lints.get(downstream.name()) {
+                Some(lints) => config.shell_print(
+                    "Downstream",
+                    format_args!(
+                        "{} uses items reported by: {}",
+                        downstream.name(),
+                        lints.iter().join(", ")
+                    ),
+                    Color::Ansi(AnsiColor::Yellow),
+                    true,
+                )?,
+                None => config.shell_print(
+                    "Downstream",
+                    format_args!("{} uses none of the reported items", downstream.name()),
+                    Color::Ansi(AnsiColor::Green),
+                    true,
+                )?,
+            }
+        }
+
         Ok(CrateReport {
+            baseline_version: baseline_version.map(ToString::to_string),
             required_bump: required_bump.map(ReleaseType::from),
             detected_bump: version_change,
+            produced_warnings,
+            target_changes: target_failures
+                .into_iter()
+                .chain(target_warnings)
+                .cloned()
+                .collect(),
+            downstream: extras
+                .downstream
+                .iter()
+                .map(|downstream| {
+                    downstream.report(
+                        downstream_lints
+                            .remove(downstream.name())
+                            .unwrap_or_default(),
+                    )
+                })
+                .collect(),
         })
     } else {
         config
@@ -467,10 +892,109 @@ pub(super) fn run_check_release(
         )?;
 
         Ok(CrateReport {
+            baseline_version: baseline_version.map(ToString::to_string),
             detected_bump: version_change,
             required_bump: None,
+            produced_warnings: false,
+            target_changes: vec![],
+            downstream: extras
+                .downstream
+                .iter()
+                .map(|downstream| downstream.report(BTreeSet::new()))
+                .collect(),
+        })
+    }
+}
+
+/// Reports a package whose baseline had a library target that the current package lacks.
+/// There is no current rustdoc to run lints against, so this is the only finding.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn report_removed_lib(
+    config: &mut GlobalConfig,
+    crate_name: &str,
+    release_type: Option<ReleaseType>,
+    version_policy: &VersionPolicy,
+    overrides: &OverrideStack,
+    current_version: &str,
+    baseline_version: Option<&str>,
+    change: TargetChange,
+) -> anyhow::Result<CrateReport> {
+    let version_change = release_type
+        .map(Into::into)
+        .or_else(|| {
+            version_policy.classify_version_strings(Some(current_version), baseline_version)
         })
+        .unwrap_or(ActualSemverUpdate::NotChanged);
+    config.shell_status(
+        "Checking",
+        format_args!(
+            "{crate_name} v{} -> v{current_version} (no library target)",
+            baseline_version.unwrap_or("unknown"),
+        ),
+    )?;
+
+    let required_update = change.required_update(overrides);
+    let lint_level = change.lint_level(overrides);
+    if version_change.supports_requirement(required_update) || lint_level == LintLevel::Allow {
+        config.shell_print(
+            "Summary",
+            "no semver update required",
+            Color::Ansi(AnsiColor::Green),
+            true,
+        )?;
+        return Ok(CrateReport {
+            baseline_version: baseline_version.map(ToString::to_string),
+            detected_bump: version_change,
+            required_bump: None,
+            produced_warnings: false,
+            target_changes: vec![],
+            downstream: vec![],
+        });
     }
+
+    let count = |update| usize::from(required_update == update);
+    let (major, minor) = (
+        count(RequiredSemverUpdate::Major),
+        count(RequiredSemverUpdate::Minor),
+    );
+    let changes = vec![change];
+    writeln!(config.stderr())?;
+    let required_bump = if lint_level == LintLevel::Deny {
+        targets::print_target_changes(config, "failure", &changes)?;
+        config.shell_print(
+            "Summary",
+            format_args!(
+                "semver requires new {} version: {major} major and {minor} minor checks failed",
+                required_update.as_str(),
+            ),
+            Color::Ansi(AnsiColor::Red),
+            true,
+        )?;
+        Some(ReleaseType::from(required_update))
+    } else {
+        targets::print_target_changes(config, "warning", &changes)?;
+        config.shell_print(
+            "Summary",
+            "no semver update required",
+            Color::Ansi(AnsiColor::Green),
+            true,
+        )?;
+        config.shell_print(
+            "Warning",
+            format_args!("produced {major} major and {minor} minor level warnings"),
+            Color::Ansi(AnsiColor::Yellow),
+            true,
+        )?;
+        None
+    };
+    Ok(CrateReport {
+        baseline_version: baseline_version.map(ToString::to_string),
+        detected_bump: version_change,
+        required_bump,
+        produced_warnings: required_bump.is_none(),
+        target_changes: changes,
+        downstream: vec![],
+    })
 }
 
 #[cfg(test)]
@@ -593,4 +1117,44 @@ mod test {
         let actual = classify_semver_version_change(baseline, current);
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn classify_with_version_policy_presets() {
+        let classify = |preset, baseline: &str, current: &str| {
+            VersionPolicy::preset(preset).classify(
+                &semver::Version::parse(baseline).unwrap(),
+                &semver::Version::parse(current).unwrap(),
+            )
+        };
+
+        assert_eq!(
+            classify(VersionPolicyPreset::Cargo, "0.3.1", "0.3.2"),
+            ActualSemverUpdate::Minor
+        );
+        assert_eq!(
+            classify(VersionPolicyPreset::StrictZero, "0.3.1", "0.3.2"),
+            ActualSemverUpdate::Patch
+        );
+        assert_eq!(
+            classify(VersionPolicyPreset::StrictZero, "0.3.1", "0.4.0"),
+            ActualSemverUpdate::Major
+        );
+
+        assert_eq!(
+            classify(VersionPolicyPreset::Cargo, "1.1.0", "1.2.0-rc.1"),
+            ActualSemverUpdate::Minor
+        );
+        assert_eq!(
+            classify(VersionPolicyPreset::PrereleaseTrains, "1.1.0", "1.2.0-rc.1"),
+            ActualSemverUpdate::Major
+        );
+        assert_eq!(
+            classify(
+                VersionPolicyPreset::PrereleaseTrains,
+                "1.2.0-rc.1",
+                "1.2.0-rc.1"
+            ),
+            ActualSemverUpdate::NotChanged
+        );
+    }
 }
@@ -1,8 +1,13 @@
 use anstream::{AutoStream, ColorChoice};
 use anstyle::{AnsiColor, Color, Reset, Style};
 use clap::ValueEnum;
-use std::{collections::HashSet, io::Write};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    rc::Rc,
+};
 
+use crate::progress::{ProgressCallbacks, ProgressEvent, ProgressHandle};
 use crate::templating::make_handlebars_registry;
 
 #[allow(dead_code)]
@@ -16,6 +21,11 @@ pub struct GlobalConfig {
     stdout: AutoStream<Box<dyn Write + 'static>>,
     stderr: AutoStream<Box<dyn Write + 'static>>,
     feature_flags: HashSet<FeatureFlag>,
+    /// Registry indexes opened so far, by index URL, so that checks run with the same config
+    /// don't each open and fetch the same index.
+    registry_indexes: HashMap<String, Rc<tame_index::index::ComboIndex>>,
+    /// Where to send progress events, if anywhere.
+    progress: Option<ProgressHandle>,
 }
 
 impl Default for GlobalConfig {
@@ -41,6 +51,8 @@ impl GlobalConfig {
             stdout: AutoStream::new(Box::new(std::io::stdout()), stdout_choice),
             stderr: AutoStream::new(Box::new(std::io::stderr()), stderr_choice),
             feature_flags: HashSet::new(),
+            registry_indexes: HashMap::new(),
+            progress: None,
         }
     }
 
@@ -52,6 +64,49 @@ impl GlobalConfig {
         &self.minimum_rustc_version
     }
 
+    /// The registry index at the given URL, if it was already opened with this config.
+    pub(crate) fn registry_index(&self, url: &str) -> Option<Rc<tame_index::index::ComboIndex>> {
+        self.registry_indexes.get(url).cloned()
+    }
+
+    /// Keep an opened registry index, for reuse by later checks run with this config.
+    pub(crate) fn add_registry_index(
+        &mut self,
+        url: String,
+        index: Rc<tame_index::index::ComboIndex>,
+    ) {
+        self.registry_indexes.insert(url, index);
+    }
+
+    /// Send structured progress events to the given callbacks, in addition to
+    /// the human-readable output. Checks with their own callbacks, set with
+    /// [`Check::set_progress_callbacks()`](crate::Check::set_progress_callbacks),
+    /// send their events to those instead.
+    pub fn set_progress_callbacks(&mut self, callbacks: Box<dyn ProgressCallbacks>) -> &mut Self {
+        self.progress = Some(ProgressHandle::new(callbacks));
+        self
+    }
+
+    /// The progress callbacks, for sending events from threads without access to the config.
+    pub(crate) fn progress(&self) -> Option<ProgressHandle> {
+        self.progress.clone()
+    }
+
+    /// Replace the progress callbacks, returning the previous ones.
+    pub(crate) fn replace_progress(
+        &mut self,
+        progress: Option<ProgressHandle>,
+    ) -> Option<ProgressHandle> {
+        std::mem::replace(&mut self.progress, progress)
+    }
+
+    /// Send the event to the progress callbacks, if any are set.
+    pub(crate) fn progress_event(&mut self, event: ProgressEvent) {
+        if let Some(progress) = &self.progress {
+            progress.emit(event);
+        }
+    }
+
     pub fn set_log_level(&mut self, level: Option<log::Level>) -> &mut Self {
         self.level = level;
         self
@@ -21,6 +21,13 @@ pub(crate) struct GenerationSettings {
 
     /// On `false`, pass `--no-deps` to `cargo`.
     pub(crate) deps: bool,
+
+    /// On `true`, build local projects with the dependency versions in their `Cargo.lock`
+    /// instead of updating them, and require that lockfile to be up to date.
+    pub(crate) locked: bool,
+
+    /// On `true`, pass `--offline` to `cargo`.
+    pub(crate) offline: bool,
 }
 
 impl GenerationSettings {
@@ -40,6 +47,20 @@ impl GenerationSettings {
             "--color=never"
         }
     }
+
+    /// Flags that restrict how `cargo` resolves and fetches dependencies.
+    ///
+    /// `--locked` is never passed here: the placeholder workspace's lockfile can't be
+    /// up to date, since it gains the placeholder package and loses the project's
+    /// dev-dependencies and other workspace members. The project's own lockfile is
+    /// checked with `--locked` instead, and the placeholder is pinned to its versions.
+    fn resolution_flags(&self) -> Vec<String> {
+        let mut flags = vec![];
+        if self.offline {
+            flags.push("--offline".to_string());
+        }
+        flags
+    }
 }
 
 pub(super) fn generate_rustdoc(
@@ -64,7 +85,58 @@ pub(super) fn generate_rustdoc(
             .into_terminal_result()?;
     callbacks.generate_placeholder_project_success();
 
-    if matches!(request.kind, RequestKind::LocalProject(..)) {
+    let metadata = resolve_placeholder_dependencies(
+        request,
+        build_dir,
+        &placeholder_manifest_path,
+        &settings,
+    )?;
+    let placeholder_target_directory = metadata.target_directory.as_path().as_std_path().to_owned();
+    let target_dir = placeholder_target_directory.as_path();
+
+    let rustdoc_data = run_cargo_doc(
+        request,
+        &metadata,
+        &placeholder_manifest_path,
+        target_dir,
+        crate_name,
+        version,
+        &settings,
+        callbacks,
+    )?;
+
+    Ok((rustdoc_data, metadata))
+}
+
+/// Resolves the dependencies of the placeholder workspace, returning its metadata.
+///
+/// Local projects are resolved with the newest compatible dependency versions, or with
+/// the versions in their own `Cargo.lock` if the settings say they're locked.
+fn resolve_placeholder_dependencies(
+    request: &CrateDataRequest<'_>,
+    build_dir: &Path,
+    placeholder_manifest_path: &Path,
+    settings: &GenerationSettings,
+) -> Result<cargo_metadata::Metadata, TerminalError> {
+    let crate_name = request.kind.name().into_terminal_result()?;
+    let version = request.kind.version().into_terminal_result()?;
+
+    let project_lockfile = match &request.kind {
+        RequestKind::LocalProject(project) if settings.locked => {
+            check_project_lockfile(request, &project.manifest.path, settings)
+                .with_context(|| {
+                    format!("the Cargo.lock of crate {crate_name} v{version} is not up to date")
+                })
+                .into_terminal_result()?;
+            Some(
+                copy_project_lockfile(&project.manifest.path, crate_name, version, build_dir)
+                    .into_terminal_result()?,
+            )
+        }
+        _ => None,
+    };
+
+    if matches!(request.kind, RequestKind::LocalProject(..)) && !settings.locked {
         // We have to run `cargo update` inside the newly-generated project, to ensure
         // all dependencies of the library we're scanning are up-to-date.
         //
@@ -85,8 +157,8 @@ pub(super) fn generate_rustdoc(
             version,
             request,
             build_dir,
-            placeholder_manifest_path.as_path(),
-            &settings,
+            placeholder_manifest_path,
+            settings,
         ) {
             CargoUpdateResult::Success => {}
             CargoUpdateResult::IoError(e) => {
@@ -101,30 +173,70 @@ pub(super) fn generate_rustdoc(
         }
     }
 
-    let metadata = cargo_metadata::MetadataCommand::new()
-        .manifest_path(&placeholder_manifest_path)
-        .exec()?;
-    let placeholder_target_directory = metadata.target_directory.as_path().as_std_path().to_owned();
-    let target_dir = placeholder_target_directory.as_path();
+    let mut metadata = placeholder_metadata(request, placeholder_manifest_path, settings)?;
+    if let Some(project_lockfile) = project_lockfile {
+        // Resolving the placeholder workspace keeps the versions from the copied lockfile
+        // wherever it can, but that isn't guaranteed. Pin any that changed.
+        let pinned = pin_locked_versions(
+            request,
+            &project_lockfile,
+            &metadata,
+            placeholder_manifest_path,
+            settings,
+        )
+        .with_context(|| {
+            format!("failed to use the locked dependency versions of crate {crate_name} v{version}")
+        })
+        .into_terminal_result()?;
+        if pinned {
+            metadata = placeholder_metadata(request, placeholder_manifest_path, settings)?;
+        }
+    }
+    Ok(metadata)
+}
 
-    let rustdoc_data = run_cargo_doc(
-        request,
-        &metadata,
-        &placeholder_manifest_path,
-        target_dir,
-        crate_name,
-        version,
-        &settings,
-        callbacks,
-    )?;
+fn placeholder_metadata(
+    request: &CrateDataRequest<'_>,
+    placeholder_manifest_path: &Path,
+    settings: &GenerationSettings,
+) -> Result<cargo_metadata::Metadata, TerminalError> {
+    let mut metadata_command = cargo_metadata::MetadataCommand::new();
+    if let Some(toolchain) = request.toolchain {
+        metadata_command.env("RUSTUP_TOOLCHAIN", toolchain);
+    }
+    Ok(metadata_command
+        .manifest_path(placeholder_manifest_path)
+        .other_options(settings.resolution_flags())
+        .exec()?)
+}
 
-    Ok((rustdoc_data, metadata))
+/// Checks that the lockfile of the project's workspace is up to date, the way `cargo --locked` does.
+fn check_project_lockfile(
+    request: &CrateDataRequest<'_>,
+    manifest_path: &Path,
+    settings: &GenerationSettings,
+) -> anyhow::Result<()> {
+    let mut metadata_command = cargo_metadata::MetadataCommand::new();
+    if let Some(toolchain) = request.toolchain {
+        metadata_command.env("RUSTUP_TOOLCHAIN", toolchain);
+    }
+    let mut flags = settings.resolution_flags();
+    flags.push("--locked".to_string());
+    metadata_command
+        .manifest_path(manifest_path)
+        .other_options(flags)
+        .exec()?;
+    Ok(())
 }
 
 fn produce_repro_workspace_shell_commands(request: &CrateDataRequest<'_>) -> String {
     let selector = match &request.kind {
         RequestKind::Registry { .. } => format!(
-            "{}@={}",
+            "{}{}@={}",
+            request
+                .registry()
+                .map(|registry| format!("--registry {registry} "))
+                .unwrap_or_default(),
             request.kind.name().expect("failed to get crate name"),
             request.kind.version().expect("failed to get crate version")
         ),
@@ -159,6 +271,15 @@ fn produce_repro_workspace_shell_commands(request: &CrateDataRequest<'_>) -> Str
     )
 }
 
+/// Creates a `cargo` command that runs with the request's toolchain, if one was chosen.
+fn cargo_command(request: &CrateDataRequest<'_>) -> std::process::Command {
+    let mut cmd = std::process::Command::new("cargo");
+    if let Some(toolchain) = request.toolchain {
+        cmd.env("RUSTUP_TOOLCHAIN", toolchain);
+    }
+    cmd
+}
+
 enum CargoUpdateResult {
     Success,
     IoError(std::io::Error),
@@ -174,13 +295,14 @@ fn run_cargo_update(
     placeholder_manifest_path: &Path,
     settings: &GenerationSettings,
 ) -> CargoUpdateResult {
-    let mut cmd = std::process::Command::new("cargo");
+    let mut cmd = cargo_command(request);
     cmd.stdout(std::process::Stdio::null()) // Don't pollute output
         .stderr(settings.stderr())
         .current_dir(build_dir)
         .arg("update")
         .arg("--manifest-path")
-        .arg(placeholder_manifest_path);
+        .arg(placeholder_manifest_path)
+        .args(settings.resolution_flags());
 
     // Respect our configured color choice.
     cmd.arg(settings.color_flag());
@@ -278,7 +400,7 @@ fn run_cargo_doc(
     //       those features must be enabled on the dependency in the `Cargo.toml`
     //       of the placeholder project.
     callbacks.generate_rustdoc_start();
-    let mut cmd = std::process::Command::new("cargo");
+    let mut cmd = cargo_command(request);
     cmd.env("RUSTC_BOOTSTRAP", "1")
         .env("RUSTDOCFLAGS", rustdocflags.as_ref())
         .env("RUSTFLAGS", rustflags.as_ref())
@@ -291,7 +413,8 @@ fn run_cargo_doc(
         .arg(target_dir)
         .arg("--package")
         .arg(pkg_spec)
-        .arg("--lib");
+        .arg("--lib")
+        .args(settings.resolution_flags());
     if let Some(build_target) = request.build_target {
         cmd.arg("--target").arg(build_target);
     }
@@ -436,7 +559,7 @@ fn determine_rustdoc_dir(
     // based on its config files and environment variables. The best way to check this
     // is to ask cargo itself.
     let build_target = {
-        let output = std::process::Command::new("cargo")
+        let output = cargo_command(request)
             .env("RUSTC_BOOTSTRAP", "1")
             .args([
                 "config",
@@ -542,6 +665,7 @@ fn create_placeholder_rustdoc_manifest(
                     // give us the latest semver-compatible version which is not we want.
                     // Fixes: https://github.com/obi1kenobi/cargo-semver-checks/issues/261
                     version: Some(format!("={}", request.kind.version()?)),
+                    registry: request.registry().map(ToString::to_string),
                     default_features: request.default_features,
                     features: request
                         .extra_features
@@ -587,6 +711,127 @@ fn create_placeholder_rustdoc_manifest(
     })
 }
 
+/// Copies the lockfile of the project's workspace into the placeholder workspace,
+/// returning the path of the project's lockfile.
+///
+/// Entries that the placeholder workspace doesn't need, such as dev-dependencies
+/// or other workspace members, are pruned by `cargo` when it next resolves.
+fn copy_project_lockfile(
+    manifest_path: &Path,
+    crate_name: &str,
+    version: &str,
+    placeholder_build_dir: &Path,
+) -> anyhow::Result<PathBuf> {
+    let lockfile_path = find_lockfile(manifest_path).with_context(|| {
+        format!(
+            "no Cargo.lock found for crate {crate_name} v{version}, \
+            which is required to use the locked dependency versions"
+        )
+    })?;
+    fs_err::copy(&lockfile_path, placeholder_build_dir.join("Cargo.lock"))?;
+    Ok(lockfile_path)
+}
+
+/// Pins the dependencies of the placeholder workspace to the versions in the project's lockfile
+/// with `cargo update --precise`, returning whether any of them changed.
+///
+/// Only packages locked at a single version for a given source can be pinned unambiguously,
+/// so packages that the project's lockfile contains several versions of are left as resolved.
+fn pin_locked_versions(
+    request: &CrateDataRequest<'_>,
+    project_lockfile: &Path,
+    metadata: &cargo_metadata::Metadata,
+    placeholder_manifest_path: &Path,
+    settings: &GenerationSettings,
+) -> anyhow::Result<bool> {
+    let locked_versions = locked_versions(&fs_err::read_to_string(project_lockfile)?)
+        .with_context(|| format!("failed to parse {}", project_lockfile.display()))?;
+
+    let mut pinned = false;
+    for package in &metadata.packages {
+        // Path dependencies have no source and always use the version on disk.
+        let Some(source) = &package.source else {
+            continue;
+        };
+        let key = (package.name.to_string(), source.repr.clone());
+        let Some(locked) = locked_versions.get(&key).and_then(|versions| {
+            versions
+                .iter()
+                .exactly_one()
+                .ok()
+                .filter(|&locked| *locked != package.version.to_string())
+        }) else {
+            continue;
+        };
+
+        let output = cargo_command(request)
+            .stdout(std::process::Stdio::null())
+            .arg("update")
+            .arg("--manifest-path")
+            .arg(placeholder_manifest_path)
+            .args(settings.resolution_flags())
+            .arg("--package")
+            .arg(format!("{}@{}", package.name, package.version))
+            .arg("--precise")
+            .arg(locked)
+            .output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "failed to pin {} to v{locked}:\n{}",
+                package.name,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        pinned = true;
+    }
+    Ok(pinned)
+}
+
+/// Maps the name and source of each package in a lockfile to the versions it's locked at.
+fn locked_versions(
+    lockfile: &str,
+) -> anyhow::Result<std::collections::BTreeMap<(String, String), Vec<String>>> {
+    let lockfile: toml::Table = lockfile.parse()?;
+    let mut versions = std::collections::BTreeMap::<_, Vec<_>>::new();
+    let packages = lockfile
+        .get("package")
+        .and_then(toml::Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for package in packages {
+        let field = |name: &str| package.get(name).and_then(toml::Value::as_str);
+        if let (Some(name), Some(version), Some(source)) =
+            (field("name"), field("version"), field("source"))
+        {
+            versions
+                .entry((name.to_string(), source.to_string()))
+                .or_default()
+                .push(version.to_string());
+        }
+    }
+    Ok(versions)
+}
+
+/// Finds the `Cargo.lock` of the workspace containing the given manifest,
+/// without looking past the workspace root.
+fn find_lockfile(manifest_path: &Path) -> Option<PathBuf> {
+    for dir in manifest_path.ancestors().skip(1) {
+        let lockfile = dir.join("Cargo.lock");
+        if lockfile.is_file() {
+            return Some(lockfile);
+        }
+
+        let is_workspace_root = std::fs::read_to_string(dir.join("Cargo.toml"))
+            .ok()
+            .and_then(|manifest| manifest.parse::<toml::Table>().ok())
+            .is_some_and(|manifest| manifest.contains_key("workspace"));
+        if is_workspace_root {
+            return None;
+        }
+    }
+    None
+}
+
 fn save_placeholder_rustdoc_manifest(
     placeholder_build_dir: &Path,
     placeholder_manifest: cargo_toml::Manifest<()>,
@@ -606,3 +851,179 @@ fn save_placeholder_rustdoc_manifest(
         .context("failed to create empty lib.rs")?;
     Ok(placeholder_manifest_path)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::path::Path;
+
+    use super::*;
+    use crate::manifest::Manifest;
+
+    fn write_crate(dir: &Path, manifest: &str) {
+        fs_err::create_dir_all(dir.join("src")).expect("failed to create crate dir");
+        fs_err::write(dir.join("Cargo.toml"), manifest).expect("failed to write manifest");
+        fs_err::write(dir.join("src/lib.rs"), "").expect("failed to write lib.rs");
+    }
+
+    #[test]
+    fn find_lockfile_stops_at_workspace_root() {
+        let root = std::env::temp_dir().join(format!(
+            "semver-checks-find-lockfile-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+
+        // A lockfile above the workspace root belongs to some other project.
+        let workspace = root.join("workspace");
+        write_crate(&workspace.join("member"), "[package]\nname = \"member\"\n");
+        fs_err::write(
+            workspace.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"member\"]\n",
+        )
+        .expect("failed to write manifest");
+        fs_err::write(root.join("Cargo.lock"), "").expect("failed to write lockfile");
+        let member_manifest = workspace.join("member/Cargo.toml");
+        assert_eq!(find_lockfile(&member_manifest), None);
+
+        let workspace_lockfile = workspace.join("Cargo.lock");
+        fs_err::write(&workspace_lockfile, "").expect("failed to write lockfile");
+        assert_eq!(find_lockfile(&member_manifest), Some(workspace_lockfile));
+
+        std::fs::remove_dir_all(&root).expect("failed to clean up");
+    }
+
+    #[test]
+    fn copy_project_lockfile_copies_verbatim() {
+        let root = std::env::temp_dir().join(format!(
+            "semver-checks-copy-lockfile-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let project = root.join("project");
+        let build_dir = root.join("build");
+        write_crate(&project, "[package]\nname = \"project\"\n");
+        fs_err::create_dir_all(&build_dir).expect("failed to create build dir");
+        let manifest_path = project.join("Cargo.toml");
+
+        let error = copy_project_lockfile(&manifest_path, "project", "0.1.0", &build_dir)
+            .expect_err("copying a missing lockfile succeeded");
+        assert!(error.to_string().contains("no Cargo.lock found"), "{error}");
+
+        let lockfile = "version = 4\n\n[[package]]\nname = \"project\"\nversion = \"0.1.0\"\n";
+        fs_err::write(project.join("Cargo.lock"), lockfile).expect("failed to write lockfile");
+        let copied = copy_project_lockfile(&manifest_path, "project", "0.1.0", &build_dir)
+            .expect("failed to copy lockfile");
+        assert_eq!(copied, project.join("Cargo.lock"));
+        assert_eq!(
+            fs_err::read_to_string(build_dir.join("Cargo.lock")).expect("failed to read lockfile"),
+            lockfile
+        );
+
+        std::fs::remove_dir_all(&root).expect("failed to clean up");
+    }
+
+    #[test]
+    fn locked_versions_skip_path_packages() {
+        let lockfile = r#"
+version = 4
+
+[[package]]
+name = "project"
+version = "0.1.0"
+
+[[package]]
+name = "dep"
+version = "1.0.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "dep"
+version = "2.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+        let versions = locked_versions(lockfile).expect("failed to parse lockfile");
+        let source = "registry+https://github.com/rust-lang/crates.io-index";
+        assert_eq!(
+            versions.into_iter().collect::<Vec<_>>(),
+            [(
+                ("dep".to_string(), source.to_string()),
+                vec!["1.0.1".to_string(), "2.0.0".to_string()]
+            )]
+        );
+    }
+
+    /// The project's lockfile has entries the placeholder workspace doesn't need,
+    /// which `cargo` prunes. That must not trip the check that the lockfile is up to date.
+    #[test]
+    fn locked_placeholder_resolves_with_dev_dependencies() {
+        let root = std::env::temp_dir().join(format!(
+            "semver-checks-locked-placeholder-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let project = root.join("project");
+        let build_dir = root.join("build");
+        write_crate(
+            &project,
+            "[package]\nname = \"project\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+            [dev-dependencies]\nhelper = { path = \"../helper\" }\n",
+        );
+        write_crate(
+            &root.join("helper"),
+            "[package]\nname = \"helper\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        );
+        let status = std::process::Command::new("cargo")
+            .args([
+                "generate-lockfile",
+                "--offline",
+                "--quiet",
+                "--manifest-path",
+            ])
+            .arg(project.join("Cargo.toml"))
+            .status()
+            .expect("failed to run cargo");
+        assert!(status.success());
+
+        let manifest =
+            Manifest::parse(project.join("Cargo.toml")).expect("failed to parse manifest");
+        let request = CrateDataRequest::from_local_project(
+            &manifest,
+            None,
+            true,
+            BTreeSet::new(),
+            None,
+            None,
+            false,
+        );
+        let placeholder_manifest =
+            create_placeholder_rustdoc_manifest(&request).expect("failed to create manifest");
+        let placeholder_manifest_path =
+            save_placeholder_rustdoc_manifest(&build_dir, placeholder_manifest)
+                .expect("failed to save manifest");
+        let settings = GenerationSettings {
+            pass_through_stderr: false,
+            use_color: false,
+            deps: false,
+            locked: true,
+            offline: true,
+        };
+
+        let metadata = resolve_placeholder_dependencies(
+            &request,
+            &build_dir,
+            &placeholder_manifest_path,
+            &settings,
+        )
+        .map_err(|error| format!("{error:?}"))
+        .expect("failed to resolve the placeholder workspace");
+        assert!(metadata
+            .packages
+            .iter()
+            .any(|package| package.name == "project"));
+
+        std::fs::remove_dir_all(&root).expect("failed to clean up");
+    }
+}
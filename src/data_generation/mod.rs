@@ -3,21 +3,45 @@ mod generate;
 mod progress;
 mod request;
 
+use std::path::PathBuf;
+use std::sync::Arc;
+
 use trustfall_rustdoc::{VersionedIndex, VersionedRustdocAdapter, VersionedStorage};
 
+use crate::targets::LibTarget;
+
 pub(crate) use error::{IntoTerminalResult, TerminalError};
 pub(crate) use generate::GenerationSettings;
 pub(crate) use progress::ProgressCallbacks;
 pub(crate) use request::{CacheSettings, CrateDataRequest};
 
+/// The rustdoc data of a crate, along with what else was learned about it while loading it.
+#[derive(Debug)]
+pub(crate) struct LoadedCrate {
+    /// The crate's rustdoc, with its package metadata if that was loaded too.
+    pub(crate) data: VersionedStorage,
+    /// The rustdoc JSON file the data was loaded from, if it's kept on disk.
+    pub(crate) json_path: Option<PathBuf>,
+    /// The crate's package, if its metadata was loaded along with its rustdoc.
+    pub(crate) package: Option<cargo_metadata::Package>,
+}
+
+impl LoadedCrate {
+    /// The crate's library target, if its package metadata was loaded.
+    pub(crate) fn lib_target(&self) -> Option<LibTarget> {
+        self.package.as_ref().and_then(LibTarget::of_package)
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct DataStorage {
-    current: VersionedStorage,
+    /// Shared, since the same current crate may be checked against several baselines.
+    current: Arc<VersionedStorage>,
     baseline: VersionedStorage,
 }
 
 impl DataStorage {
-    pub(crate) fn new(current: VersionedStorage, baseline: VersionedStorage) -> Self {
+    pub(crate) fn new(current: Arc<VersionedStorage>, baseline: VersionedStorage) -> Self {
         Self { current, baseline }
     }
 
@@ -46,7 +70,7 @@ pub(crate) struct IndexStorage<'a> {
 }
 
 impl IndexStorage<'_> {
-    pub(crate) fn create_adapter(&self) -> VersionedRustdocAdapter {
+    pub(crate) fn create_adapter(&self) -> VersionedRustdocAdapter<'_> {
         VersionedRustdocAdapter::new(&self.current_crate, Some(&self.baseline_crate))
             .expect("failed to construct adapter, this is a bug and should never happen")
     }
@@ -11,15 +11,20 @@ use crate::util::slugify;
 use super::error::{IntoTerminalResult, TerminalError};
 use super::generate::GenerationSettings;
 use super::progress::{CallbackHandler, ProgressCallbacks};
+use super::LoadedCrate;
 
 #[derive(Debug, Clone)]
 pub(super) struct RegistryRequest<'a> {
     index_entry: &'a tame_index::IndexVersion,
+    /// The registry the crate comes from, or `None` for crates.io.
+    registry: Option<&'a str>,
 }
 
 #[derive(Debug, Clone)]
 pub(super) struct ProjectRequest<'a> {
     pub(super) manifest: &'a Manifest,
+    /// The id of the git tree the project was extracted from, if any.
+    tree_id: Option<&'a str>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,8 +36,8 @@ pub(super) enum RequestKind<'a> {
 impl RequestKind<'_> {
     pub(super) fn name(&self) -> anyhow::Result<&str> {
         Ok(match self {
-            Self::Registry(RegistryRequest { index_entry }) => &index_entry.name,
-            Self::LocalProject(ProjectRequest { manifest }) => {
+            Self::Registry(RegistryRequest { index_entry, .. }) => &index_entry.name,
+            Self::LocalProject(ProjectRequest { manifest, .. }) => {
                 crate::manifest::get_package_name(manifest)?
             }
         })
@@ -40,8 +45,8 @@ impl RequestKind<'_> {
 
     pub(super) fn version(&self) -> anyhow::Result<&str> {
         Ok(match self {
-            Self::Registry(RegistryRequest { index_entry }) => index_entry.version.as_str(),
-            Self::LocalProject(ProjectRequest { manifest }) => {
+            Self::Registry(RegistryRequest { index_entry, .. }) => index_entry.version.as_str(),
+            Self::LocalProject(ProjectRequest { manifest, .. }) => {
                 crate::manifest::get_package_version(manifest)?
             }
         })
@@ -100,11 +105,15 @@ impl<'a> CacheUse<'a> {
         request: &CrateDataRequest<'a>,
         settings: CacheSettings<&'a Path>,
     ) -> anyhow::Result<Self> {
-        // We can only cache registry crates. For local crates, we have no idea of the state
-        // of the local filesystem: it can point to an arbitrary git commit, have dirty repo state,
-        // or might not be part of a git repository at all. We cannot guarantee consistency,
+        // We can only cache registry crates, and local crates extracted from a git tree.
+        // For other local crates, we have no idea of the state of the local filesystem:
+        // it can point to an arbitrary git commit, have dirty repo state, or might not be
+        // part of a git repository at all. We cannot guarantee consistency,
         // so we don't offer caching.
-        let settings = if matches!(request.kind, RequestKind::LocalProject(..)) {
+        let settings = if matches!(
+            request.kind,
+            RequestKind::LocalProject(ProjectRequest { tree_id: None, .. })
+        ) {
             CacheSettings::None
         } else {
             settings
@@ -162,15 +171,16 @@ impl<'a> CacheUse<'a> {
         Ok(None)
     }
 
-    /// If the cache policy allows writing to the cache, attempt to cache a path.
+    /// If the cache policy allows writing to the cache, attempt to cache a path,
+    /// returning where the rustdoc JSON was cached.
     ///
-    /// If the cache policy does not allow writing, this returns `Ok(..)`.
+    /// If the cache policy does not allow writing, this returns `Ok(None)`.
     /// Errors are genuine failures to write to the cache, such as I/O errors.
     fn populate(
         &self,
         rustdoc_json: &Path,
         metadata: &cargo_metadata::Metadata,
-    ) -> anyhow::Result<bool> {
+    ) -> anyhow::Result<Option<&Path>> {
         match self.settings {
             CacheSettings::ReadWrite(path) | CacheSettings::WriteOnly(path) => {
                 let json_path = self
@@ -185,9 +195,9 @@ impl<'a> CacheUse<'a> {
                 fs_err::create_dir_all(path)?;
                 fs_err::copy(rustdoc_json, json_path)?;
                 fs_err::write(metadata_path, serde_json::to_string(metadata)?)?;
-                Ok(true)
+                Ok(Some(json_path))
             }
-            CacheSettings::None | CacheSettings::ReadOnly(..) => Ok(false),
+            CacheSettings::None | CacheSettings::ReadOnly(..) => Ok(None),
         }
     }
 }
@@ -203,6 +213,9 @@ pub(crate) struct CrateDataRequest<'a> {
     pub(super) extra_features: BTreeSet<Cow<'a, str>>,
     pub(super) build_target: Option<&'a str>,
 
+    /// The rustup toolchain to generate the rustdoc with, if not the active one.
+    pub(super) toolchain: Option<&'a str>,
+
     /// Purely for progress reporting purposes. Does not change behavior.
     pub(super) is_baseline: bool,
 
@@ -213,17 +226,23 @@ pub(crate) struct CrateDataRequest<'a> {
 impl<'a> CrateDataRequest<'a> {
     pub(crate) fn from_index(
         index_entry: &'a tame_index::IndexVersion,
+        registry: Option<&'a str>,
         default_features: bool,
         extra_features: BTreeSet<Cow<'a, str>>,
         build_target: Option<&'a str>,
+        toolchain: Option<&'a str>,
         is_baseline: bool,
     ) -> Self {
         let features_fingerprint = make_features_hash(default_features, &extra_features);
         Self {
-            kind: RequestKind::Registry(RegistryRequest { index_entry }),
+            kind: RequestKind::Registry(RegistryRequest {
+                index_entry,
+                registry,
+            }),
             default_features,
             extra_features,
             build_target,
+            toolchain,
             is_baseline,
             features_fingerprint,
         }
@@ -231,17 +250,20 @@ impl<'a> CrateDataRequest<'a> {
 
     pub(crate) fn from_local_project(
         manifest: &'a Manifest,
+        tree_id: Option<&'a str>,
         default_features: bool,
         extra_features: BTreeSet<Cow<'a, str>>,
         build_target: Option<&'a str>,
+        toolchain: Option<&'a str>,
         is_baseline: bool,
     ) -> Self {
         let features_fingerprint = make_features_hash(default_features, &extra_features);
         Self {
-            kind: RequestKind::LocalProject(ProjectRequest { manifest }),
+            kind: RequestKind::LocalProject(ProjectRequest { manifest, tree_id }),
             default_features,
             extra_features,
             build_target,
+            toolchain,
             is_baseline,
             features_fingerprint,
         }
@@ -260,7 +282,7 @@ impl<'a> CrateDataRequest<'a> {
         cache_settings: CacheSettings<&'a Path>,
         generation_settings: GenerationSettings,
         callbacks: &'slf mut dyn ProgressCallbacks<'slf>,
-    ) -> Result<VersionedStorage, TerminalError> {
+    ) -> Result<LoadedCrate, TerminalError> {
         let mut callbacks = CallbackHandler::new(
             self.kind
                 .name()
@@ -290,6 +312,7 @@ impl<'a> CrateDataRequest<'a> {
                 match std::fs::read_to_string(entry.metadata) {
                     Ok(text) => match serde_json::from_str(&text) {
                         Ok(metadata) => {
+                            let package = placeholder_dependency(&metadata).cloned();
                             match load_rustdoc_with_optional_metadata(
                                 entry.json,
                                 metadata,
@@ -297,7 +320,11 @@ impl<'a> CrateDataRequest<'a> {
                             ) {
                                 Ok(data) => {
                                     callbacks.parse_rustdoc_success(true);
-                                    return Ok(data);
+                                    return Ok(LoadedCrate {
+                                        data,
+                                        json_path: Some(entry.json.to_owned()),
+                                        package,
+                                    });
                                 }
                                 Err(e) => {
                                     callbacks.non_fatal_error(
@@ -341,18 +368,20 @@ impl<'a> CrateDataRequest<'a> {
         // Check if we need to populate the cache.
         // If the cache doesn't need to be populated, this returns `Ok(false)`.
         // Errors are genuine failures to populate the cache, such as I/O problems.
+        let mut json_path = data_path.clone();
         let mut clean_up_build_dir = false;
         match cache.populate(data_path.as_path(), &metadata) {
             // Populated the cache.
-            Ok(true) => {
+            Ok(Some(cached_json_path)) => {
                 callbacks.rustdoc_cache_populated();
 
                 // Clean up our build dir, since we don't need it anymore.
+                json_path = cached_json_path.to_owned();
                 clean_up_build_dir = true;
             }
 
             // Did not populate the cache.
-            Ok(false) => {}
+            Ok(None) => {}
 
             // Encountered an error while attempting to populate the cache.
             Err(e) => {
@@ -361,6 +390,7 @@ impl<'a> CrateDataRequest<'a> {
         }
 
         // This time, failure to read the rustdoc is fatal.
+        let package = placeholder_dependency(&metadata).cloned();
         callbacks.parse_rustdoc_start(false);
         let data = load_rustdoc_with_optional_metadata(&data_path, metadata, &mut callbacks)
             .into_terminal_result()?;
@@ -376,7 +406,11 @@ impl<'a> CrateDataRequest<'a> {
             }
         }
 
-        Ok(data)
+        Ok(LoadedCrate {
+            data,
+            json_path: Some(json_path),
+            package,
+        })
     }
 
     /// A path-safe unique identifier that includes the crate's source, name, version, and features.
@@ -391,17 +425,54 @@ impl<'a> CrateDataRequest<'a> {
         ))
     }
 
-    /// A path-safe unique identified that includes the crate's name, version, and features.
+    /// A path-safe unique identified that includes the crate's name, version, and features,
+    /// as well as its registry if that isn't crates.io, the git tree it was extracted from
+    /// if any, and its toolchain if one was chosen.
     fn cache_slug(&self) -> anyhow::Result<String> {
-        Ok(format!(
+        let mut slug = format!(
             "{}-{}-{}",
             slugify(self.kind.name()?),
             slugify(self.kind.version()?),
             &self.features_fingerprint,
-        ))
+        );
+        if let Some(registry) = self.registry() {
+            slug = format!("{}-{slug}", slugify(registry));
+        }
+        if let RequestKind::LocalProject(ProjectRequest {
+            tree_id: Some(tree_id),
+            ..
+        }) = &self.kind
+        {
+            slug = format!("git-{tree_id}-{slug}");
+        }
+        if let Some(toolchain) = self.toolchain {
+            slug = format!("{slug}-{}", slugify(toolchain));
+        }
+        Ok(slug)
+    }
+
+    /// The name of the registry the crate comes from, if it isn't crates.io.
+    pub(super) fn registry(&self) -> Option<&'a str> {
+        match &self.kind {
+            RequestKind::Registry(RegistryRequest { registry, .. }) => *registry,
+            RequestKind::LocalProject(..) => None,
+        }
     }
 }
 
+/// The package that the placeholder project depends on, whose rustdoc is generated.
+fn placeholder_dependency(metadata: &cargo_metadata::Metadata) -> Option<&cargo_metadata::Package> {
+    let dependency = metadata.root_package()?.dependencies.first()?;
+    metadata
+        .packages
+        .iter()
+        .filter(|package| package.name == dependency.name)
+        .find(|package| match &dependency.path {
+            Some(path) => package.manifest_path.starts_with(path),
+            None => dependency.req.matches(&package.version),
+        })
+}
+
 fn load_rustdoc_with_optional_metadata(
     json_path: &Path,
     metadata: cargo_metadata::Metadata,
@@ -0,0 +1,443 @@
+//! Cross-referencing lint results with local checkouts of downstream crates.
+//!
+//! Each downstream crate's source is scanned for the paths through which it uses the crate
+//! being checked, such as `use my_crate::module::{Item, Other}` or `my_crate::function()`.
+//! A lint result affects a downstream crate if it reports an item at, inside, or containing
+//! one of those paths. Optionally, each downstream crate is also built against the current
+//! version of the crate being checked, with a `[patch]` pointing at its source.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anstyle::{AnsiColor, Color};
+use anyhow::Context as _;
+
+use crate::GlobalConfig;
+
+/// How one downstream crate is affected by the changes to the crate being checked.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownstreamReport {
+    name: String,
+    lints: BTreeSet<String>,
+    builds: Option<bool>,
+}
+
+impl DownstreamReport {
+    /// The name of the downstream crate.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The ids of the lints whose results report items the downstream crate uses.
+    pub fn lints(&self) -> &BTreeSet<String> {
+        &self.lints
+    }
+
+    /// Whether the downstream crate builds against the current version,
+    /// if it was built against it.
+    pub fn builds(&self) -> Option<bool> {
+        self.builds
+    }
+}
+
+/// A downstream crate in a local checkout, and how it uses the crate being checked.
+#[derive(Debug, Clone)]
+pub(crate) struct Downstream {
+    name: String,
+    manifest_path: PathBuf,
+    /// The `Cargo.lock` of the downstream crate's workspace.
+    lockfile: PathBuf,
+    /// Paths through which the downstream crate uses the crate being checked,
+    /// without the leading crate name. An empty path means any item may be used.
+    used_paths: BTreeSet<Vec<String>>,
+    builds: Option<bool>,
+}
+
+impl Downstream {
+    /// Finds the packages of the workspace at `root` that depend on the `upstream` package,
+    /// and scans their source for the paths through which they use its library,
+    /// which is named `upstream_lib` unless they rename it.
+    pub(crate) fn find(
+        root: &Path,
+        upstream: &str,
+        upstream_lib: &str,
+    ) -> anyhow::Result<Vec<Self>> {
+        let metadata = crate::manifest_metadata_no_deps(root)
+            .with_context(|| format!("failed to read downstream crate at {}", root.display()))?;
+
+        let mut found = vec![];
+        for package in metadata.workspace_packages() {
+            let Some(dependency) = package.dependencies.iter().find(|dep| dep.name == upstream)
+            else {
+                continue;
+            };
+            let crate_ident = dependency
+                .rename
+                .as_deref()
+                .unwrap_or(upstream_lib)
+                .replace('-', "_");
+
+            let package_dir = package
+                .manifest_path
+                .parent()
+                .expect("manifest path has a parent")
+                .as_std_path();
+            let mut used_paths = BTreeSet::new();
+            // Skip nested packages, since they are separate crates.
+            let walk = ignore::WalkBuilder::new(package_dir)
+                .filter_entry(|entry| {
+                    entry.depth() == 0 || !entry.path().join("Cargo.toml").is_file()
+                })
+                .build();
+            for entry in walk {
+                let entry = entry?;
+                if entry.path().extension().is_some_and(|ext| ext == "rs") {
+                    let source = std::fs::read_to_string(entry.path())
+                        .with_context(|| format!("failed to read {}", entry.path().display()))?;
+                    used_paths.extend(used_paths_in(&source, &crate_ident));
+                }
+            }
+
+            found.push(Self {
+                name: package.name.clone(),
+                manifest_path: package.manifest_path.clone().into_std_path_buf(),
+                lockfile: metadata
+                    .workspace_root
+                    .join("Cargo.lock")
+                    .into_std_path_buf(),
+                used_paths,
+                builds: None,
+            });
+        }
+        Ok(found)
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// `true` if the downstream crate uses the item at `path`, which starts with the name
+    /// of the crate being checked, or an item inside or containing it.
+    pub(crate) fn uses(&self, path: &[String]) -> bool {
+        let Some((_, path)) = path.split_first() else {
+            return false;
+        };
+        self.used_paths
+            .iter()
+            .any(|used| used.starts_with(path) || path.starts_with(used))
+    }
+
+    /// Builds the downstream crate with `cargo check`, patching `upstream` to use
+    /// the source in `upstream_dir`. The build uses a temporary target directory,
+    /// and the downstream `Cargo.lock` is restored afterward, or removed if it didn't exist.
+    pub(crate) fn check_build(
+        &mut self,
+        config: &mut GlobalConfig,
+        upstream: &str,
+        upstream_dir: &Path,
+        registry: Option<&str>,
+    ) -> anyhow::Result<()> {
+        config.shell_status(
+            "Building",
+            format_args!("{} (downstream) against current {upstream}", self.name),
+        )?;
+
+        let original_lockfile = std::fs::read(&self.lockfile).ok();
+        // Building against a patched upstream would invalidate the downstream's own build cache.
+        let target_dir = std::env::temp_dir().join(format!(
+            "semver-checks-downstream-{}-{}",
+            self.name,
+            std::process::id()
+        ));
+
+        let patch = format!(
+            "patch.{}.{}.path={}",
+            toml_string(registry.unwrap_or("crates-io")),
+            toml_string(upstream),
+            toml_string(&upstream_dir.to_string_lossy()),
+        );
+        let output = std::process::Command::new("cargo")
+            .arg("check")
+            .arg("--manifest-path")
+            .arg(&self.manifest_path)
+            .arg("--config")
+            .arg(patch)
+            .env("CARGO_TARGET_DIR", &target_dir)
+            .arg(if config.err_color_choice() {
+                "--color=always"
+            } else {
+                "--color=never"
+            })
+            .stdout(std::process::Stdio::null())
+            .output()
+            .context("failed to run cargo check")?;
+
+        match original_lockfile {
+            Some(original_lockfile) => std::fs::write(&self.lockfile, original_lockfile)
+                .with_context(|| format!("failed to restore {}", self.lockfile.display()))?,
+            None if self.lockfile.is_file() => std::fs::remove_file(&self.lockfile)
+                .with_context(|| format!("failed to remove {}", self.lockfile.display()))?,
+            None => {}
+        }
+        if target_dir.exists() {
+            std::fs::remove_dir_all(&target_dir)
+                .with_context(|| format!("failed to remove {}", target_dir.display()))?;
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("was not used in the crate graph") {
+            config.shell_warn(format_args!(
+                "{} requires a version of {upstream} that is incompatible with the current one, \
+                so it was built against the published version instead",
+                self.name
+            ))?;
+            return Ok(());
+        }
+
+        let builds = output.status.success();
+        if builds {
+            config.shell_print(
+                "Built",
+                format_args!("{} (downstream) against current {upstream}", self.name),
+                Color::Ansi(AnsiColor::Green),
+                true,
+            )?;
+        } else {
+            config.shell_print(
+                "Failed",
+                format_args!(
+                    "{} (downstream) doesn't build against current {upstream}",
+                    self.name
+                ),
+                Color::Ansi(AnsiColor::Red),
+                true,
+            )?;
+            config.log_verbose(|config| {
+                config.shell_note(format_args!("cargo check output:\n{stderr}"))
+            })?;
+        }
+        self.builds = Some(builds);
+        Ok(())
+    }
+
+    pub(crate) fn report(&self, lints: BTreeSet<String>) -> DownstreamReport {
+        DownstreamReport {
+            name: self.name.clone(),
+            lints,
+            builds: self.builds,
+        }
+    }
+}
+
+fn toml_string(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+/// Returns the paths after `crate_ident::` used in `source`, without the crate name.
+///
+/// This is a best-effort scan of the source text, not a parse of it:
+/// it follows `use` trees, but not aliases or items used through a glob import.
+fn used_paths_in(source: &str, crate_ident: &str) -> BTreeSet<Vec<String>> {
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut paths = BTreeSet::new();
+    for (start, _) in source.match_indices(crate_ident) {
+        let before = &source[..start];
+        let after = &source[start + crate_ident.len()..];
+        if before.chars().next_back().is_some_and(is_ident_char)
+            || after.chars().next().is_some_and(is_ident_char)
+        {
+            continue;
+        }
+        // `::crate_ident` is the crate, but `module::crate_ident` is something else.
+        if let Some(before) = before.strip_suffix("::") {
+            if before
+                .chars()
+                .next_back()
+                .is_some_and(|c| is_ident_char(c) || c == '>')
+            {
+                continue;
+            }
+        }
+
+        let mut cursor = Cursor { rest: after };
+        if cursor.rest.trim_start().starts_with("::") {
+            parse_suffix(&mut cursor, vec![], &mut paths);
+        } else if before.trim_end().ends_with("use") {
+            // `use crate_ident;` or `use crate_ident as alias;`: any item may be used.
+            paths.insert(vec![]);
+        }
+    }
+    paths
+}
+
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn eat(&mut self, token: &str) -> bool {
+        match self.rest.trim_start().strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        let rest = self.rest.trim_start();
+        let rest = rest.strip_prefix("r#").unwrap_or(rest);
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if len == 0 || rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        self.rest = &rest[len..];
+        Some(&rest[..len])
+    }
+}
+
+/// Parses the `::a::b`, `::{a, b}` or `::*` following `path`.
+fn parse_suffix(cursor: &mut Cursor<'_>, mut path: Vec<String>, paths: &mut BTreeSet<Vec<String>>) {
+    while cursor.eat("::") {
+        if cursor.eat("*") {
+            break;
+        }
+        if cursor.eat("{") {
+            parse_group(cursor, path, paths);
+            return;
+        }
+        match cursor.ident() {
+            Some(ident) if ident != "self" => path.push(ident.to_string()),
+            _ => break,
+        }
+    }
+    paths.insert(path);
+}
+
+/// Parses the comma-separated `use` trees in braces, after the opening brace.
+fn parse_group(cursor: &mut Cursor<'_>, prefix: Vec<String>, paths: &mut BTreeSet<Vec<String>>) {
+    loop {
+        if cursor.eat("}") {
+            return;
+        }
+        if cursor.eat("*") {
+            paths.insert(prefix.clone());
+        } else if cursor.eat("{") {
+            parse_group(cursor, prefix.clone(), paths);
+        } else {
+            match cursor.ident() {
+                Some("self") => {
+                    paths.insert(prefix.clone());
+                }
+                Some(ident) => {
+                    let mut path = prefix.clone();
+                    path.push(ident.to_string());
+                    parse_suffix(cursor, path, paths);
+                }
+                None => return,
+            }
+        }
+        // Skip `as alias`.
+        if cursor.eat("as") {
+            cursor.ident();
+        }
+        if !cursor.eat(",") {
+            cursor.eat("}");
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::{used_paths_in, Downstream};
+
+    #[test]
+    fn used_paths_follow_use_trees() {
+        let source = r#"
+            use my_crate::{
+                config::{self, Config as Cfg},
+                error::*,
+                Client,
+            };
+            use other::my_crate::Ignored;
+
+            fn main() {
+                let value = ::my_crate::util::parse::<u8>("1");
+                my_crate_extra::Unrelated::new();
+            }
+        "#;
+        let used_paths = used_paths_in(source, "my_crate");
+        let paths: BTreeSet<Vec<&str>> = used_paths
+            .iter()
+            .map(|path| path.iter().map(String::as_str).collect())
+            .collect();
+        assert_eq!(
+            paths,
+            BTreeSet::from([
+                vec!["Client"],
+                vec!["config"],
+                vec!["config", "Config"],
+                vec!["error"],
+                vec!["util", "parse"],
+            ])
+        );
+    }
+
+    #[test]
+    fn downstream_uses_are_found_by_library_name() {
+        let root =
+            std::env::temp_dir().join(format!("semver-checks-downstream-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+            [dependencies]\nmy-pkg = \"1\"\n",
+        )
+        .unwrap();
+        // The `my-pkg` package's library target is named `my_lib`.
+        std::fs::write(
+            root.join("src/main.rs"),
+            "use my_lib::Config;\nfn main() { my_pkg::unrelated(); }\n",
+        )
+        .unwrap();
+
+        let found = Downstream::find(&root, "my-pkg", "my_lib").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name(), "app");
+        assert_eq!(
+            found[0].used_paths,
+            BTreeSet::from([vec!["Config".to_string()]])
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn uses_matches_containing_and_contained_items() {
+        let downstream = Downstream {
+            name: "app".into(),
+            manifest_path: "app/Cargo.toml".into(),
+            lockfile: "app/Cargo.lock".into(),
+            used_paths: used_paths_in(
+                "use my_crate::config::Config; my_crate::error::Error::new();",
+                "my_crate",
+            ),
+            builds: None,
+        };
+        let path = |path: &[&str]| path.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert!(downstream.uses(&path(&["my_crate", "config", "Config"])));
+        assert!(downstream.uses(&path(&["my_crate", "config"])));
+        assert!(downstream.uses(&path(&["my_crate", "error", "Error"])));
+        assert!(!downstream.uses(&path(&["my_crate", "config", "Other"])));
+        assert!(!downstream.uses(&path(&["my_crate", "Client"])));
+    }
+}
@@ -0,0 +1,385 @@
+//! Propagating breaking changes across the crates of a workspace.
+//!
+//! When a workspace crate makes a breaking release, every workspace crate that exposes its types
+//! in its own public API breaks too: downstream code may be using the old types through it.
+//! Which dependencies a crate exposes is read from the `paths` and `external_crates` tables
+//! of its rustdoc JSON, so crates are only followed along edges where types actually leak.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use anstyle::{AnsiColor, Color};
+use anyhow::Context as _;
+use itertools::Itertools as _;
+use serde_json::Value;
+
+use crate::{ActualSemverUpdate, CrateReport, GlobalConfig, ReleaseType};
+
+/// A workspace crate that may need a major version bump because it exposes, in its own public API,
+/// the types of a workspace crate with breaking changes.
+///
+/// This is conservative: the crate counts as affected if any of its public items refers to
+/// the other crate, whether or not that item uses any of the changed parts.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceImpact {
+    /// The crates through which the breakage reached this one, starting from the crate
+    /// with breaking changes and ending with this crate.
+    chain: Vec<String>,
+    /// Bump between this crate's current version and its baseline.
+    detected_bump: ActualSemverUpdate,
+}
+
+impl WorkspaceImpact {
+    /// The name of the affected crate.
+    pub fn crate_name(&self) -> &str {
+        self.chain.last().expect("chain is never empty")
+    }
+
+    /// The crates through which the breakage reached this one, starting from the crate
+    /// with breaking changes and ending with this crate.
+    pub fn chain(&self) -> &[String] {
+        &self.chain
+    }
+
+    /// Bump between the affected crate's current version and its baseline.
+    pub fn detected_bump(&self) -> ActualSemverUpdate {
+        self.detected_bump
+    }
+
+    /// `true` if the affected crate's version already declares a major bump.
+    pub fn success(&self) -> bool {
+        self.detected_bump == ActualSemverUpdate::Major
+    }
+}
+
+/// Find the workspace crates affected by breaking changes in other workspace crates,
+/// given each checked crate's report against its first baseline
+/// and where its current rustdoc JSON is.
+pub(crate) fn workspace_impact(
+    config: &mut GlobalConfig,
+    metadata: &cargo_metadata::Metadata,
+    reports: &BTreeMap<&str, &CrateReport>,
+    current_rustdoc_json: &BTreeMap<String, PathBuf>,
+) -> anyhow::Result<Vec<WorkspaceImpact>> {
+    let broken: Vec<&str> = reports
+        .iter()
+        .filter(|(_, report)| {
+            report.detected_bump() == ActualSemverUpdate::Major
+                || report.required_bump() == Some(ReleaseType::Major)
+        })
+        .map(|(&name, _)| name)
+        .collect();
+    if broken.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let members = metadata.workspace_packages();
+    let lib_names: BTreeMap<&str, String> = members
+        .iter()
+        .filter_map(|package| {
+            let lib = package
+                .targets
+                .iter()
+                .find(|target| crate::is_lib_like_checkable_target(target))?;
+            Some((package.name.as_str(), lib.name.replace('-', "_")))
+        })
+        .collect();
+
+    // For each workspace crate, the workspace crates that expose it in their public API.
+    let mut exposed_by: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for package in &members {
+        let Some(json_path) = reports
+            .contains_key(package.name.as_str())
+            .then(|| current_rustdoc_json.get(package.name.as_str()))
+            .flatten()
+        else {
+            continue;
+        };
+        let dependencies: BTreeMap<&str, &str> = package
+            .dependencies
+            .iter()
+            .filter(|dep| dep.kind == cargo_metadata::DependencyKind::Normal && dep.path.is_some())
+            .filter_map(|dep| {
+                lib_names
+                    .get_key_value(dep.name.as_str())
+                    .map(|(&name, lib_name)| (lib_name.as_str(), name))
+            })
+            .collect();
+        if dependencies.is_empty() {
+            continue;
+        }
+
+        let exposed = exposed_crates(json_path, dependencies.keys().copied())
+            .with_context(|| format!("failed to read the public API of {}", package.name))?;
+        for lib_name in exposed {
+            exposed_by
+                .entry(dependencies[lib_name.as_str()].to_string())
+                .or_default()
+                .insert(package.name.clone());
+        }
+    }
+
+    // Only checked crates have rustdoc JSON, so every crate that exposes another one is checked.
+    let impacts: Vec<WorkspaceImpact> = propagate(broken.iter().copied(), &exposed_by)
+        .into_iter()
+        .map(|chain| {
+            let report = reports[chain.last().expect("chain is never empty").as_str()];
+            WorkspaceImpact {
+                chain,
+                detected_bump: report.detected_bump(),
+            }
+        })
+        .collect();
+
+    for impact in &impacts {
+        let (status, color) = if impact.success() {
+            ("new major version already declared", AnsiColor::Green)
+        } else {
+            ("semver may require new major version", AnsiColor::Red)
+        };
+        config.shell_print(
+            "Affected",
+            format_args!(
+                "{} may be affected by breaking changes in {}, which its public API may expose \
+                through {}: {status}",
+                impact.crate_name(),
+                impact.chain[0],
+                impact.chain.join(" -> "),
+            ),
+            Color::Ansi(color),
+            true,
+        )?;
+    }
+
+    // Crates that weren't checked may expose the breakage too, but without their rustdoc JSON
+    // we can't tell, so at least point out the ones that depend on an affected crate.
+    let affected: BTreeSet<&str> = broken
+        .iter()
+        .copied()
+        .chain(impacts.iter().map(WorkspaceImpact::crate_name))
+        .collect();
+    for package in &members {
+        if reports.contains_key(package.name.as_str()) {
+            continue;
+        }
+        let affected_dependencies: BTreeSet<&str> = package
+            .dependencies
+            .iter()
+            .filter(|dep| dep.kind == cargo_metadata::DependencyKind::Normal && dep.path.is_some())
+            .filter_map(|dep| affected.get(dep.name.as_str()).copied())
+            .collect();
+        if !affected_dependencies.is_empty() {
+            config.shell_warn(format_args!(
+                "{} depends on {}, which has breaking changes, but it wasn't checked, \
+                so it may need a new major version too; check it as well to find out",
+                package.name,
+                affected_dependencies.iter().join(", "),
+            ))?;
+        }
+    }
+
+    Ok(impacts)
+}
+
+/// Returns the chain of crates along which breakage reaches each crate affected by
+/// the `broken` ones, following the edges from each crate to the crates that expose it.
+///
+/// Chains are as short as possible and start at one of the `broken` crates.
+/// Crates that are themselves `broken` are not included.
+fn propagate<'a>(
+    broken: impl IntoIterator<Item = &'a str>,
+    exposed_by: &BTreeMap<String, BTreeSet<String>>,
+) -> Vec<Vec<String>> {
+    let broken: BTreeSet<&str> = broken.into_iter().collect();
+    let mut chains: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    let mut queue: VecDeque<Vec<String>> =
+        broken.iter().map(|&name| vec![name.to_string()]).collect();
+
+    while let Some(chain) = queue.pop_front() {
+        let last = chain.last().expect("chain is never empty");
+        for dependent in exposed_by.get(last).into_iter().flatten() {
+            if broken.contains(dependent.as_str()) || chains.contains_key(dependent.as_str()) {
+                continue;
+            }
+            let mut dependent_chain = chain.clone();
+            dependent_chain.push(dependent.clone());
+            chains.insert(dependent, dependent_chain.clone());
+            queue.push_back(dependent_chain);
+        }
+    }
+
+    chains.into_values().collect()
+}
+
+/// Returns which of the given crates, by library name, appear in the public API described by
+/// the rustdoc JSON file at `json_path`.
+///
+/// This is conservative: a crate counts as exposed if any public item, even one that isn't
+/// reachable from the crate root, refers to one of its items.
+fn exposed_crates<'a>(
+    json_path: &Path,
+    lib_names: impl IntoIterator<Item = &'a str>,
+) -> anyhow::Result<BTreeSet<String>> {
+    let text = std::fs::read_to_string(json_path)
+        .with_context(|| format!("failed to read {}", json_path.display()))?;
+    let rustdoc: Value = serde_json::from_str(&text)?;
+    Ok(exposed_crates_in(&rustdoc, lib_names))
+}
+
+fn exposed_crates_in<'a>(
+    rustdoc: &Value,
+    lib_names: impl IntoIterator<Item = &'a str>,
+) -> BTreeSet<String> {
+    let lib_names: BTreeSet<&str> = lib_names.into_iter().collect();
+
+    // The ids rustdoc assigned to each of the crates we're looking for.
+    let crate_ids: BTreeMap<&str, &str> = rustdoc["external_crates"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(id, external)| {
+            let name = external["name"].as_str()?;
+            lib_names.contains(name).then_some((id.as_str(), name))
+        })
+        .collect();
+
+    // The items of those crates that the local crate refers to.
+    let foreign_items: BTreeMap<&str, &str> = rustdoc["paths"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(id, summary)| {
+            let crate_id = id_key(&summary["crate_id"])?;
+            crate_ids
+                .get(crate_id.as_str())
+                .map(|&name| (id.as_str(), name))
+        })
+        .collect();
+
+    let mut exposed = BTreeSet::new();
+    if foreign_items.is_empty() {
+        return exposed;
+    }
+    for item in rustdoc["index"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(_, item)| item)
+    {
+        let is_local = item["crate_id"].as_u64() == Some(0);
+        // Trait impls and the items in them have `default` visibility.
+        let is_public = matches!(item["visibility"].as_str(), Some("public" | "default"));
+        if is_local && is_public {
+            collect_referenced_crates(&item["inner"], &foreign_items, &mut exposed);
+        }
+    }
+    exposed
+}
+
+/// Records the crates of all the `foreign_items` that `value` refers to by id.
+fn collect_referenced_crates(
+    value: &Value,
+    foreign_items: &BTreeMap<&str, &str>,
+    exposed: &mut BTreeSet<String>,
+) {
+    match value {
+        Value::Object(fields) => {
+            if let Some(name) = fields
+                .get("id")
+                .and_then(id_key)
+                .and_then(|id| foreign_items.get(id.as_str()))
+            {
+                exposed.insert(name.to_string());
+            }
+            for field in fields.values() {
+                collect_referenced_crates(field, foreign_items, exposed);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_referenced_crates(value, foreign_items, exposed);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Ids are numbers in recent rustdoc JSON formats and strings in older ones.
+fn id_key(id: &Value) -> Option<String> {
+    match id {
+        Value::Number(id) => Some(id.to_string()),
+        Value::String(id) => Some(id.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use super::{exposed_crates_in, propagate};
+
+    #[test]
+    fn breakage_propagates_along_exposing_crates() {
+        let exposed_by: BTreeMap<String, BTreeSet<String>> = [
+            ("core", &["api", "macros"][..]),
+            ("api", &["app", "cli"]),
+            ("cli", &["core"]),
+        ]
+        .into_iter()
+        .map(|(name, dependents)| {
+            (
+                name.to_string(),
+                dependents.iter().map(|name| name.to_string()).collect(),
+            )
+        })
+        .collect();
+
+        assert_eq!(
+            propagate(["core"], &exposed_by),
+            [
+                vec!["core", "api"],
+                vec!["core", "api", "app"],
+                vec!["core", "api", "cli"],
+                vec!["core", "macros"],
+            ]
+        );
+        assert_eq!(propagate(["core", "app"], &exposed_by).len(), 3);
+        assert!(propagate(["app"], &exposed_by).is_empty());
+    }
+
+    #[test]
+    fn exposed_crates_come_from_public_items() {
+        let rustdoc = serde_json::json!({
+            "external_crates": {
+                "1": { "name": "core_types" },
+                "2": { "name": "helpers" },
+                "3": { "name": "std" },
+            },
+            "paths": {
+                "10": { "crate_id": 1, "path": ["core_types", "Id"], "kind": "struct" },
+                "20": { "crate_id": 2, "path": ["helpers", "Helper"], "kind": "struct" },
+                "30": { "crate_id": 3, "path": ["std", "string", "String"], "kind": "struct" },
+            },
+            "index": {
+                "100": {
+                    "crate_id": 0,
+                    "visibility": "public",
+                    "inner": { "struct_field": { "resolved_path": { "path": "Id", "id": 10 } } },
+                },
+                "101": {
+                    "crate_id": 0,
+                    "visibility": "crate",
+                    "inner": { "struct_field": { "resolved_path": { "path": "Helper", "id": 20 } } },
+                },
+            },
+        });
+
+        assert_eq!(
+            exposed_crates_in(&rustdoc, ["core_types", "helpers"]),
+            BTreeSet::from(["core_types".to_string()])
+        );
+        assert!(exposed_crates_in(&rustdoc, ["helpers"]).is_empty());
+    }
+}
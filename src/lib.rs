@@ -1,12 +1,24 @@
 #![forbid(unsafe_code)]
 
+mod audit;
+mod batch;
+mod bisect;
 mod callbacks;
 mod check_release;
 mod config;
 mod data_generation;
+mod downstream;
+mod impact;
+mod lint_test;
+mod lint_validation;
 mod manifest;
+mod progress;
 mod query;
+mod rename_hints;
 mod rustdoc_gen;
+mod shims;
+mod snippets;
+mod targets;
 mod templating;
 mod util;
 
@@ -16,20 +28,33 @@ use clap::ValueEnum;
 use data_generation::{DataStorage, IntoTerminalResult as _, TerminalError};
 use directories::ProjectDirs;
 use itertools::Itertools;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use trustfall_rustdoc::VersionedStorage;
 
 use std::collections::{BTreeMap, HashSet};
 use std::io::Write as _;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use check_release::run_check_release;
+use check_release::{run_check_release, ReportExtras};
+pub use check_release::{VersionPolicy, VersionPolicyPreset};
 use rustdoc_gen::CrateDataForRustdoc;
+use targets::LibTarget;
 
+pub use audit::{Audit, AuditReport, AuditedPair};
+pub use batch::{Batch, BatchReport};
+pub use bisect::{Bisect, BisectFinding, BisectReport, SkippedCommit};
 pub use config::{FeatureFlag, GlobalConfig};
+pub use downstream::DownstreamReport;
+pub use impact::WorkspaceImpact;
+pub use lint_test::{LintTest, LintTestReport};
+pub use lint_validation::{validate_lint, LintDefinitionError};
+pub use progress::{ProgressCallbacks, ProgressEvent};
 pub use query::{
     ActualSemverUpdate, LintLevel, OverrideMap, OverrideStack, QueryOverride, RequiredSemverUpdate,
     SemverQuery, Witness,
 };
+pub use targets::TargetChange;
 
 /// Test a release for semver violations.
 #[non_exhaustive]
@@ -39,6 +64,8 @@ pub struct Check {
     scope: Scope,
     current: Rustdoc,
     baseline: Rustdoc,
+    /// Further baselines to check against, each reported separately.
+    additional_baselines: Vec<Rustdoc>,
     release_type: Option<ReleaseType>,
     current_feature_config: rustdoc_gen::FeatureConfig,
     baseline_feature_config: rustdoc_gen::FeatureConfig,
@@ -46,6 +73,31 @@ pub struct Check {
     build_target: Option<String>,
     /// Options for generating [witnesses](Witness).
     witness_generation: WitnessGeneration,
+    /// Whether to show the source code of each lint result's span.
+    source_snippets: bool,
+    /// Whether to suggest where missing items may have been renamed or moved to.
+    rename_hints: bool,
+    /// Whether to suggest code that would restore compatibility, for lints that support it.
+    shim_suggestions: bool,
+    /// The registry to look up baseline versions in, if not crates.io.
+    registry: Option<String>,
+    /// Whether to resolve dependencies from the project's own `Cargo.lock`.
+    locked: bool,
+    /// Whether to prevent cargo from accessing the network.
+    offline: bool,
+    /// The rustup toolchain to generate the current rustdoc with, if not the active one.
+    current_toolchain: Option<String>,
+    /// The rustup toolchain to generate the baseline rustdoc with, if not the active one.
+    baseline_toolchain: Option<String>,
+    /// Whether to propagate breaking changes to the workspace crates that expose them.
+    workspace_impact: bool,
+    /// Local checkouts of downstream crates to cross-reference lint results with.
+    downstream: Vec<PathBuf>,
+    /// Whether to build the downstream crates against the current version.
+    downstream_build: bool,
+    /// Where to send progress events, if not to the config's progress callbacks.
+    #[serde(skip)]
+    progress: Option<progress::ProgressHandle>,
 }
 
 /// The kind of release we're making.
@@ -53,10 +105,13 @@ pub struct Check {
 /// Affects which lints are executed.
 /// Non-exhaustive in case we want to add "pre-release" as an option in the future.
 #[non_exhaustive]
-#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ReleaseType {
+    #[serde(alias = "major")]
     Major,
+    #[serde(alias = "minor")]
     Minor,
+    #[serde(alias = "patch")]
     Patch,
 }
 
@@ -94,6 +149,40 @@ impl Rustdoc {
         }
     }
 
+    /// Generate the rustdoc file from the project at the commit where the current `HEAD`
+    /// forked from the given branch, i.e. the merge base of `HEAD` and the branch.
+    pub fn from_git_merge_base(
+        project_root: impl Into<PathBuf>,
+        branch: impl Into<String>,
+    ) -> Self {
+        Self {
+            source: RustdocSource::MergeBase(project_root.into(), branch.into()),
+        }
+    }
+
+    /// Generate the rustdoc file from the project at the git tag of its most recent release.
+    ///
+    /// Tags are matched against the `baseline-tag-patterns` listed in the workspace's
+    /// `[workspace.metadata.cargo-semver-checks]` table, which default to
+    /// `["{crate}-v{version}", "v{version}"]`.  Among the tagged versions,
+    /// the baseline is chosen the same way as for versions in the registry.
+    pub fn from_git_tags(project_root: impl Into<PathBuf>) -> Self {
+        Self {
+            source: RustdocSource::GitTags(project_root.into()),
+        }
+    }
+
+    /// Generate the rustdoc file from a `.crate` file, such as one downloaded from a registry
+    /// or created by `cargo package`. No registry access is needed.
+    ///
+    /// The path may also be a directory with the unpacked contents of `.crate` files,
+    /// such as the output of `cargo vendor`.
+    pub fn from_crate_file(crate_file: impl Into<PathBuf>) -> Self {
+        Self {
+            source: RustdocSource::CrateFile(crate_file.into()),
+        }
+    }
+
     /// Generate the rustdoc file from the largest-numbered non-yanked non-prerelease version
     /// published to the cargo registry. If no such version, uses
     /// the largest-numbered version including yanked and prerelease versions.
@@ -109,6 +198,15 @@ impl Rustdoc {
             source: RustdocSource::VersionFromRegistry(Some(crate_version.into())),
         }
     }
+
+    /// Generate a rustdoc file for the latest non-yanked non-prerelease version of each
+    /// semver-compatible release line published to the cargo registry, e.g. `1.4.2` and `2.1.0`,
+    /// and check against each of them. Only versions up to the current version are used.
+    pub fn from_registry_latest_in_each_major() -> Self {
+        Self {
+            source: RustdocSource::LatestInEachMajorFromRegistry,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize)]
@@ -121,11 +219,19 @@ enum RustdocSource {
     Root(PathBuf),
     /// Project root directory and Git Revision.
     Revision(PathBuf, String),
+    /// Project root directory, whose git tags name its released versions.
+    GitTags(PathBuf),
+    /// Project root directory and the branch whose merge base with `HEAD` to use.
+    MergeBase(PathBuf, String),
+    /// Path to a `.crate` file, or to a directory with unpacked crate sources.
+    CrateFile(PathBuf),
     /// Version from cargo registry to lookup. E.g. "1.0.0".
     /// If `None`, uses the largest-numbered non-yanked non-prerelease version
     /// published to the cargo registry. If no such version, uses
     /// the largest-numbered version including yanked and prerelease versions.
     VersionFromRegistry(Option<String>),
+    /// The latest version of each semver-compatible release line in the cargo registry.
+    LatestInEachMajorFromRegistry,
 }
 
 /// Which packages to analyze.
@@ -241,15 +347,15 @@ impl Scope {
 ///
 /// This is a broader definition than cargo's own "lib" definition, since we can also
 /// semver-check rlib, dylib, and staticlib targets as well.
-fn is_lib_like_checkable_target(target: &cargo_metadata::Target) -> bool {
+pub(crate) fn is_lib_like_checkable_target(target: &cargo_metadata::Target) -> bool {
     target.is_lib()
         || target.kind.iter().any(|kind| {
             matches!(
                 kind,
-                cargo_metadata::TargetKind::RLib { .. }
-                    | cargo_metadata::TargetKind::DyLib { .. }
-                    | cargo_metadata::TargetKind::CDyLib { .. }
-                    | cargo_metadata::TargetKind::StaticLib { .. }
+                cargo_metadata::TargetKind::RLib
+                    | cargo_metadata::TargetKind::DyLib
+                    | cargo_metadata::TargetKind::CDyLib
+                    | cargo_metadata::TargetKind::StaticLib
             )
         })
 }
@@ -260,11 +366,24 @@ impl Check {
             scope: Scope::default(),
             current,
             baseline: Rustdoc::from_registry_latest_crate_version(),
+            additional_baselines: Vec::new(),
             release_type: None,
             current_feature_config: rustdoc_gen::FeatureConfig::default_for_current(),
             baseline_feature_config: rustdoc_gen::FeatureConfig::default_for_baseline(),
             build_target: None,
             witness_generation: WitnessGeneration::default(),
+            source_snippets: false,
+            rename_hints: false,
+            shim_suggestions: false,
+            registry: None,
+            locked: false,
+            offline: false,
+            current_toolchain: None,
+            baseline_toolchain: None,
+            workspace_impact: false,
+            downstream: Vec::new(),
+            downstream_build: false,
+            progress: None,
         }
     }
 
@@ -283,6 +402,14 @@ impl Check {
         self
     }
 
+    /// Also check against another baseline, such as the latest release of an older
+    /// release line that is still maintained. The current rustdoc is generated once,
+    /// and each baseline gets its own report.
+    pub fn add_baseline(&mut self, baseline: Rustdoc) -> &mut Self {
+        self.additional_baselines.push(baseline);
+        self
+    }
+
     pub fn set_release_type(&mut self, release_type: ReleaseType) -> &mut Self {
         self.release_type = Some(release_type);
         self
@@ -335,6 +462,102 @@ impl Check {
         self
     }
 
+    /// Set whether to show the source code of the affected items for each lint result,
+    /// similar to rustc diagnostics.
+    pub fn set_source_snippets(&mut self, source_snippets: bool) -> &mut Self {
+        self.source_snippets = source_snippets;
+        self
+    }
+
+    /// Set whether to suggest where items reported as missing may have gone,
+    /// e.g. a new item with the same name at another path, along with
+    /// a `pub use` that would restore compatibility.
+    pub fn set_rename_hints(&mut self, rename_hints: bool) -> &mut Self {
+        self.rename_hints = rename_hints;
+        self
+    }
+
+    /// Set whether to suggest compatibility shims, such as a `pub use` re-exporting
+    /// a moved item, for lint results whose lint defines a shim template.
+    pub fn set_shim_suggestions(&mut self, shim_suggestions: bool) -> &mut Self {
+        self.shim_suggestions = shim_suggestions;
+        self
+    }
+
+    /// Set the registry in which to look up baseline versions of the crate,
+    /// by the name it has in cargo's `[registries]` configuration.
+    ///
+    /// Registries with a `file://` index URL, and registries replaced by a local registry
+    /// in cargo's `[source]` configuration, are supported. Defaults to crates.io.
+    pub fn set_registry(&mut self, registry: impl Into<String>) -> &mut Self {
+        self.registry = Some(registry.into());
+        self
+    }
+
+    /// Set whether to build local crates with the dependency versions in their `Cargo.lock`,
+    /// like `cargo build --locked`, instead of the newest compatible versions.
+    ///
+    /// This applies to the current version and to baselines from a git revision or a crate file,
+    /// which use the lockfile as it was at that revision or when the crate was packaged.
+    /// Baselines from a registry still resolve their dependencies anew.
+    pub fn set_locked(&mut self, locked: bool) -> &mut Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Set whether to run cargo with `--offline`, so that it doesn't access the network.
+    pub fn set_offline(&mut self, offline: bool) -> &mut Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Set the rustup toolchain used to generate the current rustdoc,
+    /// as if running `cargo +<toolchain>`. Defaults to the active toolchain.
+    pub fn set_current_toolchain(&mut self, toolchain: impl Into<String>) -> &mut Self {
+        self.current_toolchain = Some(toolchain.into());
+        self
+    }
+
+    /// Set the rustup toolchain used to generate the baseline rustdoc,
+    /// as if running `cargo +<toolchain>`. Defaults to the active toolchain.
+    ///
+    /// Both toolchains must emit the same rustdoc JSON format version.
+    pub fn set_baseline_toolchain(&mut self, toolchain: impl Into<String>) -> &mut Self {
+        self.baseline_toolchain = Some(toolchain.into());
+        self
+    }
+
+    /// Set whether to report the workspace crates that need a major bump because
+    /// they expose, in their own public API, types of a workspace crate with breaking changes.
+    /// Only applies when checking a project root. Defaults to `false`.
+    pub fn set_workspace_impact(&mut self, workspace_impact: bool) -> &mut Self {
+        self.workspace_impact = workspace_impact;
+        self
+    }
+
+    /// Send structured progress events of this check to the given callbacks,
+    /// in addition to the human-readable output. Takes precedence over the callbacks
+    /// set with [`GlobalConfig::set_progress_callbacks()`].
+    pub fn set_progress_callbacks(&mut self, callbacks: Box<dyn ProgressCallbacks>) -> &mut Self {
+        self.progress = Some(progress::ProgressHandle::new(callbacks));
+        self
+    }
+
+    /// Add a local checkout of crates that depend on the checked crates.
+    /// Each lint result is cross-referenced with the paths through which they use
+    /// the checked crates, to report which results affect them.
+    pub fn add_downstream(&mut self, root: impl Into<PathBuf>) -> &mut Self {
+        self.downstream.push(root.into());
+        self
+    }
+
+    /// Set whether to also run `cargo check` on the downstream crates,
+    /// with a `[patch]` pointing to the current version. Defaults to `false`.
+    pub fn set_downstream_build(&mut self, downstream_build: bool) -> &mut Self {
+        self.downstream_build = downstream_build;
+        self
+    }
+
     /// Some `RustdocSource`s don't contain a path to the project root,
     /// so they don't have a target directory. We try to deduce the target directory
     /// on a "best effort" basis -- when the source contains a target dir,
@@ -379,58 +602,436 @@ impl Check {
                     config,
                 )?)
             }
+            RustdocSource::MergeBase(root, branch) => {
+                let metadata = manifest_metadata_no_deps(root)?;
+                let source = metadata.workspace_root.as_std_path();
+                Box::new(rustdoc_gen::RustdocFromGitRevision::with_merge_base(
+                    source,
+                    &target_dir,
+                    branch,
+                    config,
+                )?)
+            }
+            RustdocSource::GitTags(root) => {
+                let metadata = manifest_metadata_no_deps(root)?;
+                let tag_patterns =
+                    manifest::deserialize_baseline_tag_patterns(&metadata.workspace_metadata)
+                        .context("[workspace.metadata.cargo-semver-checks] table is invalid")?
+                        .unwrap_or_else(|| {
+                            rustdoc_gen::DEFAULT_BASELINE_TAG_PATTERNS
+                                .iter()
+                                .map(|pattern| pattern.to_string())
+                                .collect()
+                        });
+                Box::new(rustdoc_gen::RustdocFromGitTags::new(
+                    metadata.workspace_root.as_std_path(),
+                    &target_dir,
+                    tag_patterns,
+                )?)
+            }
+            RustdocSource::CrateFile(crate_file) => {
+                let target = target_dir.join(format!(
+                    "crate-file-{}",
+                    util::slugify(&crate_file.to_string_lossy())
+                ));
+                Box::new(rustdoc_gen::RustdocFromCrateFile::new(crate_file, &target)?)
+            }
             RustdocSource::VersionFromRegistry(version) => {
-                let mut registry = rustdoc_gen::RustdocFromRegistry::new(&target_dir, config)?;
+                let mut registry = rustdoc_gen::RustdocFromRegistry::new(
+                    &target_dir,
+                    self.registry.as_deref(),
+                    config,
+                )?;
                 if let Some(ver) = version {
                     let semver = semver::Version::parse(ver)?;
                     registry.set_version(semver);
                 }
                 Box::new(registry)
             }
+            RustdocSource::LatestInEachMajorFromRegistry => {
+                Box::new(rustdoc_gen::RustdocFromRegistry::new(
+                    &target_dir,
+                    self.registry.as_deref(),
+                    config,
+                )?)
+            }
         })
     }
 
-    pub fn check_release(&self, config: &mut GlobalConfig) -> anyhow::Result<Report> {
-        let generation_settings = data_generation::GenerationSettings {
+    fn get_baseline_loader(
+        &self,
+        config: &mut GlobalConfig,
+        source: &RustdocSource,
+    ) -> anyhow::Result<BaselineLoader> {
+        Ok(match source {
+            RustdocSource::LatestInEachMajorFromRegistry => {
+                BaselineLoader::LatestInEachMajor(Box::new(rustdoc_gen::RustdocFromRegistry::new(
+                    &self.get_target_dir(source)?,
+                    self.registry.as_deref(),
+                    config,
+                )?))
+            }
+            _ => BaselineLoader::Single(self.get_rustdoc_generator(config, source)?),
+        })
+    }
+
+    fn generation_settings(&self, config: &GlobalConfig) -> data_generation::GenerationSettings {
+        data_generation::GenerationSettings {
             use_color: config.err_color_choice(),
             deps: false,
             pass_through_stderr: config.is_verbose(),
-        };
+            locked: self.locked,
+            offline: self.offline,
+        }
+    }
 
-        // If both the current and baseline rustdoc are given explicitly as a file path,
-        // we don't need to use the installed rustc, and this check can be skipped.
-        if !(matches!(self.current.source, RustdocSource::Rustdoc(_))
-            && matches!(self.baseline.source, RustdocSource::Rustdoc(_)))
-        {
-            let rustc_version_needed = config.minimum_rustc_version();
-            match rustc_version::version() {
-                Ok(rustc_version) => {
-                    if rustc_version < *rustc_version_needed {
-                        let help = "HELP: to use the latest rustc, run `rustup update stable && cargo +stable semver-checks <args>`";
-                        anyhow::bail!("rustc version is not high enough: >={rustc_version_needed} needed, got {rustc_version}\n\n{help}");
+    fn report_extras(
+        &self,
+        current_loader: &dyn rustdoc_gen::RustdocGenerator,
+        baseline_loader: &dyn rustdoc_gen::RustdocGenerator,
+        crate_name: &str,
+        downstream: &[downstream::Downstream],
+        target_changes: Vec<TargetChange>,
+    ) -> ReportExtras {
+        ReportExtras {
+            snippet_sources: self.source_snippets.then(|| snippets::SnippetSources {
+                current: current_loader.package_source_dir(crate_name),
+                baseline: baseline_loader.package_source_dir(crate_name),
+            }),
+            rename_hints: self.rename_hints,
+            shims: self.shim_suggestions,
+            baseline_label: baseline_loader.source_label(),
+            downstream: downstream.to_vec(),
+            target_changes,
+        }
+    }
+
+    /// The settings for checking a package whose manifest has the given
+    /// `[package.metadata]`, with the settings of this check layered on top.
+    /// Features chosen heuristically don't override the ones in the metadata,
+    /// since the heuristic is the default.
+    fn package_settings(
+        &self,
+        metadata: &serde_json::Value,
+        overrides: OverrideStack,
+        version_policy: VersionPolicy,
+        renamed_features: BTreeMap<String, String>,
+    ) -> anyhow::Result<PackageSettings> {
+        let mut current_feature_config = rustdoc_gen::FeatureConfig::default_for_current();
+        let mut baseline_feature_config = rustdoc_gen::FeatureConfig::default_for_baseline();
+        manifest::apply_feature_tables(
+            metadata,
+            &mut current_feature_config,
+            &mut baseline_feature_config,
+        )?;
+        for (feature_config, layered) in [
+            (&mut current_feature_config, &self.current_feature_config),
+            (&mut baseline_feature_config, &self.baseline_feature_config),
+        ] {
+            let features_group = (layered.features_group != rustdoc_gen::FeaturesGroup::Heuristic)
+                .then(|| layered.features_group.clone());
+            feature_config.layer(
+                features_group,
+                &layered.extra_features,
+                &layered.excluded_features,
+            );
+        }
+        rustdoc_gen::FeatureConfig::apply_renames(
+            &renamed_features,
+            &mut current_feature_config,
+            &mut baseline_feature_config,
+        );
+
+        Ok(PackageSettings {
+            overrides,
+            version_policy,
+            renamed_features,
+            current_feature_config,
+            baseline_feature_config,
+        })
+    }
+
+    /// The settings for checking a package without a manifest, e.g. from rustdoc JSON.
+    fn default_package_settings(&self) -> PackageSettings {
+        PackageSettings {
+            overrides: OverrideStack::new(),
+            version_policy: VersionPolicy::default(),
+            renamed_features: BTreeMap::new(),
+            current_feature_config: self.current_feature_config.clone(),
+            baseline_feature_config: self.baseline_feature_config.clone(),
+        }
+    }
+
+    /// Checks the crate against each of its baselines, generating its current rustdoc once.
+    ///
+    /// Records where the current rustdoc JSON is in `current_rustdoc_json`, by crate name.
+    #[allow(clippy::too_many_arguments)]
+    fn check_crate(
+        &self,
+        config: &mut GlobalConfig,
+        current_loader: &dyn rustdoc_gen::RustdocGenerator,
+        baseline_loaders: &[BaselineLoader],
+        crate_name: &str,
+        version: Option<&semver::Version>,
+        package: &PackageSettings,
+        current_rustdoc_json: &mut BTreeMap<String, PathBuf>,
+    ) -> anyhow::Result<Vec<CrateReport>> {
+        let generation_settings = self.generation_settings(config);
+        let start = std::time::Instant::now();
+
+        let current_crate_data = CrateDataForRustdoc {
+            crate_type: rustdoc_gen::CrateType::Current,
+            name: crate_name,
+            feature_config: &package.current_feature_config,
+            build_target: self.build_target.as_deref(),
+            toolchain: self.current_toolchain.as_deref(),
+        };
+        let current_crate = current_loader.load_rustdoc(
+            config,
+            generation_settings,
+            data_generation::CacheSettings::ReadWrite(()),
+            current_crate_data.clone(),
+        );
+        let current_crate = log_terminal_error(config, current_crate)?;
+        let current_lib = current_crate.lib_target();
+        if let Some(json_path) = &current_crate.json_path {
+            current_rustdoc_json.insert(crate_name.to_string(), json_path.clone());
+        }
+        let current_crate = Arc::new(current_crate.data);
+
+        let mut downstream = Vec::new();
+        for root in &self.downstream {
+            let lib_name = current_lib.as_ref().map_or(crate_name, LibTarget::name);
+            let found = downstream::Downstream::find(root, crate_name, lib_name)?;
+            if found.is_empty() {
+                config.shell_warn(format_args!(
+                    "no crate at {} depends on {crate_name}",
+                    root.display()
+                ))?;
+            }
+            downstream.extend(found);
+        }
+        if self.downstream_build && !downstream.is_empty() {
+            match current_loader.package_source_dir(crate_name) {
+                Some(source_dir) => {
+                    for downstream in &mut downstream {
+                        downstream.check_build(
+                            config,
+                            crate_name,
+                            &source_dir,
+                            self.registry.as_deref(),
+                        )?;
                     }
                 }
-                Err(error) => {
-                    let help = format!("HELP: to avoid errors please ensure rustc >={rustc_version_needed} is used");
-                    config.shell_warn(format_args!(
-                        "failed to determine the current rustc version: {error}\n\n{help}"
-                    ))?;
-                }
-            };
+                None => config.shell_warn(format_args!(
+                    "can't build downstream crates against the current {crate_name}, \
+                    since its source isn't available locally"
+                ))?,
+            }
+        }
+
+        let mut reports = Vec::with_capacity(baseline_loaders.len());
+        for baseline_loader in baseline_loaders {
+            let pinned_versions = baseline_loader.pinned_versions(config, crate_name, version)?;
+            for pinned_version in &pinned_versions {
+                let data_storage = generate_baseline_data(
+                    config,
+                    generation_settings,
+                    current_loader,
+                    baseline_loader.generator(),
+                    &current_crate,
+                    current_crate_data.clone(),
+                    CrateDataForRustdoc {
+                        crate_type: rustdoc_gen::CrateType::Baseline {
+                            highest_allowed_version: pinned_version.as_ref().or(version),
+                        },
+                        name: crate_name,
+                        feature_config: &package.baseline_feature_config,
+                        build_target: self.build_target.as_deref(),
+                        toolchain: self.baseline_toolchain.as_deref(),
+                    },
+                );
+                let (data_storage, baseline_lib) = log_terminal_error(config, data_storage)?;
+                // Library targets are only compared if both packages' metadata was loaded,
+                // which isn't the case for pre-generated rustdoc JSON.
+                let target_changes = match (baseline_lib, &current_lib) {
+                    (Some(baseline_lib), Some(current_lib)) => {
+                        targets::target_changes(&baseline_lib, Some(current_lib))
+                    }
+                    _ => vec![],
+                };
+
+                reports.push(run_check_release(
+                    config,
+                    &data_storage,
+                    crate_name,
+                    self.release_type,
+                    &package.version_policy,
+                    &package.overrides,
+                    &package.renamed_features,
+                    &self.witness_generation,
+                    &self.report_extras(
+                        current_loader,
+                        baseline_loader.generator(),
+                        crate_name,
+                        &downstream,
+                        target_changes,
+                    ),
+                )?);
+            }
+        }
+
+        let duration = start.elapsed();
+        config.shell_status(
+            "Finished",
+            format_args!("[{:>8.3}s] {crate_name}", duration.as_secs_f32()),
+        )?;
+        config.progress_event(ProgressEvent::CrateFinish {
+            crate_name: crate_name.to_string(),
+            duration,
+            success: reports.iter().all(CrateReport::success),
+        });
+        Ok(reports)
+    }
+
+    /// Checks a package without a library target against each baseline,
+    /// reporting the baselines in which it still had one.
+    ///
+    /// Baselines that can't be loaded, e.g. because the package was never published,
+    /// aren't reported, since there is no library target to compare against.
+    fn check_removed_lib(
+        &self,
+        config: &mut GlobalConfig,
+        baseline_loaders: &[BaselineLoader],
+        package: &cargo_metadata::Package,
+        overrides: OverrideStack,
+        version_policy: VersionPolicy,
+    ) -> anyhow::Result<Vec<CrateReport>> {
+        let generation_settings = self.generation_settings(config);
+        let settings = self.package_settings(
+            &package.metadata,
+            overrides,
+            version_policy,
+            BTreeMap::new(),
+        )?;
+        let crate_name = package.name.as_str();
+
+        let mut reports = Vec::new();
+        for baseline_loader in baseline_loaders {
+            // Only look for baselines that may exist: generating one is costly,
+            // and most packages without a library target never had one.
+            let has_lib_target = baseline_loader
+                .generator()
+                .package_has_lib_target(crate_name);
+            if has_lib_target == Some(false)
+                || (has_lib_target.is_none()
+                    && !baseline_loader
+                        .generator()
+                        .package_has_history(config, crate_name)?)
+            {
+                continue;
+            }
+            let pinned_versions =
+                baseline_loader.pinned_versions(config, crate_name, Some(&package.version))?;
+            for pinned_version in &pinned_versions {
+                let baseline = baseline_loader.generator().load_rustdoc(
+                    config,
+                    generation_settings,
+                    data_generation::CacheSettings::ReadWrite(()),
+                    CrateDataForRustdoc {
+                        crate_type: rustdoc_gen::CrateType::Baseline {
+                            highest_allowed_version: pinned_version
+                                .as_ref()
+                                .or(Some(&package.version)),
+                        },
+                        name: crate_name,
+                        feature_config: &settings.baseline_feature_config,
+                        build_target: self.build_target.as_deref(),
+                        toolchain: self.baseline_toolchain.as_deref(),
+                    },
+                );
+                let Some((baseline, baseline_lib)) = baseline.ok().and_then(|baseline| {
+                    let baseline_lib = baseline.lib_target()?;
+                    Some((baseline.data, baseline_lib))
+                }) else {
+                    config.log_verbose(|config| {
+                        config.shell_status(
+                            "Skipping",
+                            format_args!("{crate_name}, no baseline with a library target"),
+                        )
+                    })?;
+                    continue;
+                };
+
+                let change = targets::target_changes(&baseline_lib, None)
+                    .pop()
+                    .expect("a missing library target is a change");
+                reports.push(check_release::report_removed_lib(
+                    config,
+                    crate_name,
+                    self.release_type,
+                    &settings.version_policy,
+                    &settings.overrides,
+                    &package.version.to_string(),
+                    baseline.crate_version(),
+                    change,
+                )?);
+            }
+        }
+        Ok(reports)
+    }
+
+    pub fn check_release(&self, config: &mut GlobalConfig) -> anyhow::Result<Report> {
+        let Some(progress) = &self.progress else {
+            return self.check_all_crates(config);
+        };
+        let config_progress = config.replace_progress(Some(progress.clone()));
+        let report = self.check_all_crates(config);
+        config.replace_progress(config_progress);
+        report
+    }
+
+    fn check_all_crates(&self, config: &mut GlobalConfig) -> anyhow::Result<Report> {
+        let baselines: Vec<&Rustdoc> = std::iter::once(&self.baseline)
+            .chain(&self.additional_baselines)
+            .collect();
+
+        // Check the rustc version of each toolchain that will generate rustdoc.
+        // Sides whose rustdoc is given explicitly as a file path don't need the installed rustc,
+        // so their check is skipped.
+        let mut toolchains: Vec<Option<&str>> = Vec::with_capacity(2);
+        let sides = std::iter::once((&self.current, self.current_toolchain.as_deref())).chain(
+            baselines
+                .iter()
+                .map(|&baseline| (baseline, self.baseline_toolchain.as_deref())),
+        );
+        for (rustdoc, toolchain) in sides {
+            if !matches!(rustdoc.source, RustdocSource::Rustdoc(_))
+                && !toolchains.contains(&toolchain)
+            {
+                toolchains.push(toolchain);
+            }
+        }
+        for toolchain in toolchains {
+            check_rustc_version(config, toolchain)?;
         }
 
         let current_loader = self.get_rustdoc_generator(config, &self.current.source)?;
-        let baseline_loader = self.get_rustdoc_generator(config, &self.baseline.source)?;
+        let baseline_loaders = baselines
+            .iter()
+            .map(|baseline| self.get_baseline_loader(config, &baseline.source))
+            .collect::<anyhow::Result<Vec<_>>>()?;
 
         // Create a report for each crate.
         // We want to run all the checks, even if one returns `Err`.
-        let all_outcomes: Vec<anyhow::Result<(String, Option<CrateReport>)>> = match &self
-            .current
-            .source
-        {
+        let (all_outcomes, workspace_impact) = match &self.current.source {
             RustdocSource::Rustdoc(_)
             | RustdocSource::Revision(_, _)
-            | RustdocSource::VersionFromRegistry(_) => {
+            | RustdocSource::GitTags(_)
+            | RustdocSource::MergeBase(_, _)
+            | RustdocSource::CrateFile(_)
+            | RustdocSource::VersionFromRegistry(_)
+            | RustdocSource::LatestInEachMajorFromRegistry => {
                 let names = match &self.scope.mode {
                     ScopeMode::DenyList(_) =>
                         match &self.current.source {
@@ -443,62 +1044,76 @@ impl Check {
                         }
                     ScopeMode::AllowList(lst) => lst.clone(),
                 };
-                names
+                let outcomes = names
                     .into_iter()
                     .map(|name| {
-                        let start = std::time::Instant::now();
-                        let version = None;
-                        let data_storage = match generate_crate_data(
+                        let reports = self.check_crate(
                             config,
-                            generation_settings,
                             &*current_loader,
-                            &*baseline_loader,
-                            CrateDataForRustdoc {
-                                crate_type: rustdoc_gen::CrateType::Current,
-                                name: &name,
-                                feature_config: &self.current_feature_config,
-                                build_target: self.build_target.as_deref(),
-                            },
-                            CrateDataForRustdoc {
-                                crate_type: rustdoc_gen::CrateType::Baseline {
-                                    highest_allowed_version: version,
-                                },
-                                name: &name,
-                                feature_config: &self.baseline_feature_config,
-                                build_target: self.build_target.as_deref(),
-                            },
-                        ) {
-                            Ok(data) => data,
-                            Err(TerminalError::WithAdvice(err, advice)) => {
-                                config.log_error(|config| {
-                                    writeln!(config.stderr(), "{advice}")?;
-                                    Ok(())
-                                })?;
-                                return Err(err);
-                            }
-                            Err(TerminalError::Other(err)) => return Err(err),
-                        };
-
-                        let report = run_check_release(
-                            config,
-                            &data_storage,
+                            &baseline_loaders,
                             &name,
-                            self.release_type,
-                            &OverrideStack::new(),
-                            &self.witness_generation,
+                            None,
+                            &self.default_package_settings(),
+                            &mut BTreeMap::new(),
                         )?;
-                        config.shell_status(
-                            "Finished",
-                            format_args!("[{:>8.3}s] {name}", start.elapsed().as_secs_f32()),
-                        )?;
-                        Ok((name, Some(report)))
+                        Ok((name, reports))
                     })
-                    .collect()
+                    .collect();
+                (outcomes, vec![])
             }
             RustdocSource::Root(project_root) => {
                 let metadata = manifest_metadata(project_root)?;
                 let (selected, skipped) = self.scope.selected_packages(&metadata);
-                if selected.is_empty() {
+                let workspace_overrides =
+                    manifest::deserialize_lint_table(&metadata.workspace_metadata)
+                        .context("[workspace.metadata.cargo-semver-checks] table is invalid")?
+                        .map(|table| table.into_stack());
+                let workspace_version_policy =
+                    manifest::deserialize_version_policy(&metadata.workspace_metadata)
+                        .context("[workspace.metadata.cargo-semver-checks] table is invalid")?;
+                let workspace_renamed_features =
+                    manifest::deserialize_renamed_features(&metadata.workspace_metadata)
+                        .context("[workspace.metadata.cargo-semver-checks] table is invalid")?
+                        .unwrap_or_default();
+
+                // Packages without a library target that were explicitly selected,
+                // or that the manifest points to and are published,
+                // may have removed it since the baseline.
+                let root = metadata
+                    .resolve
+                    .as_ref()
+                    .and_then(|resolve| resolve.root.as_ref());
+                let mut removed_lib_outcomes = Vec::new();
+                for &package in &skipped {
+                    if matches!(self.scope.mode, ScopeMode::AllowList(..))
+                        || (root == Some(&package.id) && package.publish != Some(vec![]))
+                    {
+                        let version_policy =
+                            manifest::deserialize_version_policy(&package.metadata)
+                                .with_context(|| {
+                                    format!(
+                                "package `{}`'s [package.metadata.cargo-semver-checks] table is invalid (at {})",
+                                package.name,
+                                package.manifest_path,
+                            )
+                                })?
+                                .or(workspace_version_policy)
+                                .unwrap_or_default();
+                        let overrides = package_overrides(workspace_overrides.as_deref(), package)?;
+                        let reports = self.check_removed_lib(
+                            config,
+                            &baseline_loaders,
+                            package,
+                            overrides,
+                            version_policy,
+                        )?;
+                        if !reports.is_empty() {
+                            removed_lib_outcomes.push(Ok((package.name.clone(), reports)));
+                        }
+                    }
+                }
+
+                if selected.is_empty() && removed_lib_outcomes.is_empty() {
                     let help = if skipped.is_empty() {
                         "".to_string()
                     } else {
@@ -514,12 +1129,8 @@ note: skipped the following crates since they have no library target: {skipped}"
                     );
                 }
 
-                let workspace_overrides =
-                    manifest::deserialize_lint_table(&metadata.workspace_metadata)
-                        .context("[workspace.metadata.cargo-semver-checks] table is invalid")?
-                        .map(|table| table.into_stack());
-
-                selected
+                let mut current_rustdoc_json = BTreeMap::new();
+                let mut outcomes: Vec<anyhow::Result<(String, Vec<CrateReport>)>> = selected
                     .iter()
                     .map(|selected| {
                         let crate_name = &selected.name;
@@ -539,107 +1150,96 @@ note: skipped the following crates since they have no library target: {skipped}"
                                     format_args!("{crate_name} v{version} (current)"),
                                 )
                             })?;
-                            Ok((crate_name.clone(), None))
+                            Ok((crate_name.clone(), vec![]))
                         } else {
-                            let package_overrides =
-                                manifest::deserialize_lint_table(&selected.metadata)
+                            let version_policy =
+                                manifest::deserialize_version_policy(&selected.metadata)
                                     .with_context(|| {
                                         format!(
                                     "package `{}`'s [package.metadata.cargo-semver-checks] table is invalid (at {})",
                                     selected.name,
                                     selected.manifest_path,
                                 )
-                                    })?;
-
-                            let mut overrides = OverrideStack::new();
+                                    })?
+                                    .or(workspace_version_policy)
+                                    .unwrap_or_default();
 
-                            let selected_manifest = manifest::Manifest::parse(selected.manifest_path.clone().into_std_path_buf())?;
-                            // the key `lints.workspace` for the Cargo lints table
-                            let lint_workspace_key = selected_manifest.parsed.lints.is_some_and(|x| x.workspace);
-                            let metadata_workspace_key = package_overrides.as_ref().is_some_and(|x| x.workspace);
-
-                            if lint_workspace_key || metadata_workspace_key {
-                                if let Some(workspace) = &workspace_overrides {
-                                    for level in workspace {
-                                        overrides.push(level);
-                                    }
-                                }
-                            }
-
-                            if let Some(package) = package_overrides {
-                                for level in package.into_stack() {
-                                    overrides.push(&level);
-                                }
-                            }
-
-                            let start = std::time::Instant::now();
-                            let data_storage = match generate_crate_data(
+                            let mut renamed_features = workspace_renamed_features.clone();
+                            renamed_features.extend(
+                                manifest::deserialize_renamed_features(&selected.metadata)
+                                    .with_context(|| {
+                                        format!(
+                                    "package `{}`'s [package.metadata.cargo-semver-checks] table is invalid (at {})",
+                                    selected.name,
+                                    selected.manifest_path,
+                                )
+                                    })?
+                                    .unwrap_or_default(),
+                            );
+
+                            let overrides =
+                                package_overrides(workspace_overrides.as_deref(), selected)?;
+
+                            let package = self
+                                .package_settings(
+                                    &selected.metadata,
+                                    overrides,
+                                    version_policy,
+                                    renamed_features,
+                                )
+                                .with_context(|| {
+                                    format!(
+                                "package `{}`'s [package.metadata.cargo-semver-checks] table is invalid (at {})",
+                                selected.name,
+                                selected.manifest_path,
+                            )
+                                })?;
+                            let reports = self.check_crate(
                                 config,
-                                generation_settings,
                                 &*current_loader,
-                                &*baseline_loader,
-                                CrateDataForRustdoc {
-                                    crate_type: rustdoc_gen::CrateType::Current,
-                                    name: crate_name,
-                                    feature_config: &self.current_feature_config,
-                                    build_target: self.build_target.as_deref(),
-                                },
-                                CrateDataForRustdoc {
-                                    crate_type: rustdoc_gen::CrateType::Baseline {
-                                        highest_allowed_version: Some(version),
-                                    },
-                                    name: crate_name,
-                                    feature_config: &self.baseline_feature_config,
-                                    build_target: self.build_target.as_deref(),
-                                },
-                            ) {
-                                Ok(data) => data,
-                                Err(TerminalError::WithAdvice(err, advice)) => {
-                                    config.log_error(|config| {
-                                        writeln!(config.stderr(), "{advice}")?;
-                                        Ok(())
-                                    })?;
-                                    return Err(err);
-                                }
-                                Err(TerminalError::Other(err)) => return Err(err),
-                            };
-
-                            let result = Ok((
-                                crate_name.clone(),
-                                Some(run_check_release(
-                                    config,
-                                    &data_storage,
-                                    crate_name,
-                                    self.release_type,
-                                    &overrides,
-                                    &self.witness_generation
-                                )?),
-                            ));
-                            config.shell_status(
-                                "Finished",
-                                format_args!(
-                                    "[{:>8.3}s] {crate_name}",
-                                    start.elapsed().as_secs_f32()
-                                ),
+                                &baseline_loaders,
+                                crate_name,
+                                Some(version),
+                                &package,
+                                &mut current_rustdoc_json,
                             )?;
-                            result
+                            Ok((crate_name.clone(), reports))
                         }
                     })
-                    .collect()
+                    .collect();
+                outcomes.extend(removed_lib_outcomes);
+
+                let workspace_impact = if self.workspace_impact {
+                    let reports = outcomes
+                        .iter()
+                        .filter_map(|outcome| {
+                            let (name, reports) = outcome.as_ref().ok()?;
+                            Some((name.as_str(), reports.first()?))
+                        })
+                        .collect();
+                    impact::workspace_impact(config, &metadata, &reports, &current_rustdoc_json)?
+                } else {
+                    vec![]
+                };
+                (outcomes, workspace_impact)
             }
         };
-        let crate_reports: BTreeMap<String, CrateReport> = {
-            let mut reports = BTreeMap::new();
-            for outcome in all_outcomes {
-                let (name, outcome) = outcome?;
-                if let Some(outcome) = outcome {
-                    reports.insert(name, outcome);
-                }
+        let mut crate_reports: BTreeMap<String, CrateReport> = BTreeMap::new();
+        let mut additional_reports: Vec<(String, CrateReport)> = Vec::new();
+        for outcome in all_outcomes {
+            let (name, outcomes) = outcome?;
+            let mut outcomes = outcomes.into_iter();
+            if let Some(outcome) = outcomes.next() {
+                crate_reports.insert(name.clone(), outcome);
             }
-            reports
-        };
+            additional_reports.extend(outcomes.map(|outcome| (name.clone(), outcome)));
+        }
 
-        Ok(Report { crate_reports })
+        Ok(Report {
+            crate_reports,
+            additional_reports,
+            workspace_impact,
+        })
     }
 }
 
@@ -647,12 +1247,20 @@ note: skipped the following crates since they have no library target: {skipped}"
 #[non_exhaustive]
 #[derive(Debug)]
 pub struct CrateReport {
+    /// The version of the baseline the crate was checked against, if known.
+    baseline_version: Option<String>,
     /// Bump between the current version and the baseline one.
     detected_bump: ActualSemverUpdate,
     /// Minimum additional bump (on top of `detected_bump`) required to respect semver.
     /// For example, if the crate contains breaking changes, this is [`Some(ReleaseType::Major)`].
     /// If no additional bump beyond the already-detected one is required, this is [`Option::None`].
     required_bump: Option<ReleaseType>,
+    /// Whether any lint at the warn level produced results.
+    produced_warnings: bool,
+    /// Breaking changes to the library target that were reported as failures or warnings.
+    target_changes: Vec<TargetChange>,
+    /// How each downstream crate is affected by the lint results.
+    downstream: Vec<DownstreamReport>,
 }
 
 impl CrateReport {
@@ -698,6 +1306,44 @@ impl CrateReport {
     pub fn detected_bump(&self) -> ActualSemverUpdate {
         self.detected_bump
     }
+
+    /// `true` if any lint at the warn level produced results, whether or not the check failed.
+    pub fn has_warnings(&self) -> bool {
+        self.produced_warnings
+    }
+
+    /// The exit code for this report alone. See [`Report::exit_code()`].
+    pub fn exit_code(&self) -> CheckOutcome {
+        if !self.success() {
+            match self.required_bump {
+                Some(ReleaseType::Major) => CheckOutcome::MajorRequired,
+                _ => CheckOutcome::MinorRequired,
+            }
+        } else if self.produced_warnings {
+            CheckOutcome::Warnings
+        } else {
+            CheckOutcome::Success
+        }
+    }
+
+    /// The version of the baseline the crate was checked against, if known.
+    pub fn baseline_version(&self) -> Option<&str> {
+        self.baseline_version.as_deref()
+    }
+
+    /// Breaking changes to the library target, such as it being renamed,
+    /// or to the package's `links` key.
+    ///
+    /// Like lints, changes whose lint level is overridden to `allow` aren't reported,
+    /// see [`TargetChange::id()`].
+    pub fn target_changes(&self) -> &[TargetChange] {
+        &self.target_changes
+    }
+
+    /// How each downstream crate added with [`Check::add_downstream()`] is affected.
+    pub fn downstream(&self) -> &[DownstreamReport] {
+        &self.downstream
+    }
 }
 
 /// Report of the whole analysis.
@@ -705,20 +1351,102 @@ impl CrateReport {
 #[non_exhaustive]
 #[derive(Debug)]
 pub struct Report {
-    /// Collection containing the name and the report of each crate checked.
+    /// Collection containing the name and the report of each crate checked,
+    /// against its first baseline.
     crate_reports: BTreeMap<String, CrateReport>,
+    /// The name and report of each crate checked against further baselines, in check order.
+    additional_reports: Vec<(String, CrateReport)>,
+    /// Workspace crates affected by breaking changes in the workspace crates they expose.
+    workspace_impact: Vec<WorkspaceImpact>,
 }
 
 impl Report {
-    /// `true` if none of the crates violate semver.
+    /// `true` if none of the crates violate semver against any baseline,
+    /// and every crate affected by breaking changes in the workspace crates it exposes
+    /// declares a major bump.
     pub fn success(&self) -> bool {
         self.crate_reports.values().all(|report| report.success())
+            && self
+                .additional_reports
+                .iter()
+                .all(|(_, report)| report.success())
+            && self.workspace_impact.iter().all(WorkspaceImpact::success)
+    }
+
+    /// The most severe outcome among all crates and baselines, for use as the exit code
+    /// of a process that ran the check. Crates affected by breaking changes in the workspace
+    /// crates they expose count as requiring a major bump.
+    pub fn exit_code(&self) -> CheckOutcome {
+        let impact = if self.workspace_impact.iter().all(WorkspaceImpact::success) {
+            CheckOutcome::Success
+        } else {
+            CheckOutcome::MajorRequired
+        };
+        self.baseline_reports()
+            .map(|(_, report)| report.exit_code())
+            .fold(impact, Ord::max)
     }
 
     /// Reports of each crate checked, sorted by crate name.
+    ///
+    /// When checking against several baselines, this holds each crate's report
+    /// against its first baseline; see [`Report::baseline_reports()`] for the rest.
     pub fn crate_reports(&self) -> &BTreeMap<String, CrateReport> {
         &self.crate_reports
     }
+
+    /// Workspace crates that need a major bump because they expose, in their own public API,
+    /// types of a workspace crate with breaking changes. Only computed when
+    /// [`Check::set_workspace_impact()`] is enabled.
+    pub fn workspace_impact(&self) -> &[WorkspaceImpact] {
+        &self.workspace_impact
+    }
+
+    /// The name and report of each crate checked, for every baseline it was checked against.
+    pub fn baseline_reports(&self) -> impl Iterator<Item = (&str, &CrateReport)> {
+        self.crate_reports
+            .iter()
+            .chain(
+                self.additional_reports
+                    .iter()
+                    .map(|(name, report)| (name, report)),
+            )
+            .map(|(name, report)| (name.as_str(), report))
+    }
+}
+
+/// The outcome of a semver check, as a process exit code.
+///
+/// Variants are ordered from least to most severe, except for [`CheckOutcome::Error`],
+/// which means the check didn't complete and so has no other outcome.
+/// Exit code `2` isn't used, since it's what the command line parser exits with
+/// on invalid arguments.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckOutcome {
+    /// No crate violates semver, and no lint produced warnings. Exit code `0`.
+    Success,
+    /// No crate violates semver, but some lints produced warnings. Exit code `3`.
+    Warnings,
+    /// Some crate needs a new minor version, and none needs a new major version. Exit code `4`.
+    MinorRequired,
+    /// Some crate needs a new major version. Exit code `5`.
+    MajorRequired,
+    /// The check failed to run, e.g. because rustdoc couldn't be generated. Exit code `1`.
+    Error,
+}
+
+impl CheckOutcome {
+    /// The numeric exit code.
+    pub fn code(self) -> i32 {
+        match self {
+            Self::Success => 0,
+            Self::Error => 1,
+            Self::Warnings => 3,
+            Self::MinorRequired => 4,
+            Self::MajorRequired => 5,
+        }
+    }
 }
 
 /// Options for generating **witness code**.  A witness is a minimal buildable
@@ -748,6 +1476,108 @@ impl WitnessGeneration {
     }
 }
 
+/// Logs the advice of a [`TerminalError::WithAdvice`], leaving just the error to return.
+fn log_terminal_error<T>(
+    config: &mut GlobalConfig,
+    result: Result<T, TerminalError>,
+) -> anyhow::Result<T> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(TerminalError::WithAdvice(err, advice)) => {
+            config.log_error(|config| {
+                writeln!(config.stderr(), "{advice}")?;
+                Ok(())
+            })?;
+            Err(err)
+        }
+        Err(TerminalError::Other(err)) => Err(err),
+    }
+}
+
+/// Settings that can differ between the packages checked in one run.
+struct PackageSettings {
+    overrides: OverrideStack,
+    version_policy: VersionPolicy,
+    /// Features renamed since the baseline, from their baseline name to their current one.
+    renamed_features: BTreeMap<String, String>,
+    current_feature_config: rustdoc_gen::FeatureConfig,
+    baseline_feature_config: rustdoc_gen::FeatureConfig,
+}
+
+/// Where the baselines a crate is checked against come from.
+enum BaselineLoader {
+    /// A single baseline, chosen by the generator.
+    Single(Box<dyn rustdoc_gen::RustdocGenerator>),
+    /// The latest release of each semver-compatible release line in the registry.
+    LatestInEachMajor(Box<rustdoc_gen::RustdocFromRegistry>),
+}
+
+impl BaselineLoader {
+    fn generator(&self) -> &dyn rustdoc_gen::RustdocGenerator {
+        match self {
+            Self::Single(generator) => &**generator,
+            Self::LatestInEachMajor(registry) => &**registry,
+        }
+    }
+
+    /// The versions to check the crate against, each generated separately.
+    /// `None` lets the generator choose the baseline version itself.
+    fn pinned_versions(
+        &self,
+        config: &mut GlobalConfig,
+        crate_name: &str,
+        highest_allowed_version: Option<&semver::Version>,
+    ) -> anyhow::Result<Vec<Option<semver::Version>>> {
+        match self {
+            Self::Single(_) => Ok(vec![None]),
+            Self::LatestInEachMajor(registry) => Ok(registry
+                .latest_in_each_major(config, crate_name, highest_allowed_version)?
+                .into_iter()
+                .map(Some)
+                .collect()),
+        }
+    }
+}
+
+/// Ensure the given rustup toolchain's rustc (or the active one, if `None`)
+/// is recent enough to emit a supported rustdoc JSON format.
+fn check_rustc_version(config: &mut GlobalConfig, toolchain: Option<&str>) -> anyhow::Result<()> {
+    let rustc_version_needed = config.minimum_rustc_version();
+    let mut rustc = std::process::Command::new("rustc");
+    if let Some(toolchain) = toolchain {
+        rustc.env("RUSTUP_TOOLCHAIN", toolchain);
+    }
+    match rustc_version::VersionMeta::for_command(rustc) {
+        Ok(rustc_version) => {
+            let rustc_version = rustc_version.semver;
+            if rustc_version < *rustc_version_needed {
+                match toolchain {
+                    Some(toolchain) => {
+                        let help = format!("HELP: to use the latest rustc, run `rustup update {toolchain}` or choose a newer toolchain");
+                        anyhow::bail!("rustc version of toolchain `{toolchain}` is not high enough: >={rustc_version_needed} needed, got {rustc_version}\n\n{help}");
+                    }
+                    None => {
+                        let help = "HELP: to use the latest rustc, run `rustup update stable && cargo +stable semver-checks <args>`";
+                        anyhow::bail!("rustc version is not high enough: >={rustc_version_needed} needed, got {rustc_version}\n\n{help}");
+                    }
+                }
+            }
+        }
+        Err(error) => {
+            let help = format!(
+                "HELP: to avoid errors please ensure rustc >={rustc_version_needed} is used"
+            );
+            let toolchain = toolchain
+                .map(|toolchain| format!(" of toolchain `{toolchain}`"))
+                .unwrap_or_default();
+            config.shell_warn(format_args!(
+                "failed to determine the rustc version{toolchain}: {error}\n\n{help}"
+            ))?;
+        }
+    };
+    Ok(())
+}
+
 fn generate_crate_data(
     config: &mut GlobalConfig,
     generation_settings: data_generation::GenerationSettings,
@@ -760,55 +1590,170 @@ fn generate_crate_data(
         config,
         generation_settings,
         data_generation::CacheSettings::ReadWrite(()),
+        current_crate_data.clone(),
+    )?;
+
+    let (data_storage, _) = generate_baseline_data(
+        config,
+        generation_settings,
+        current_loader,
+        baseline_loader,
+        &Arc::new(current_crate.data),
         current_crate_data,
+        baseline_crate_data,
     )?;
+    Ok(data_storage)
+}
 
-    let baseline_crate_name = baseline_crate_data.name;
+/// Loads the baseline rustdoc to compare against an already-loaded current crate.
+///
+/// If the two sides are built with toolchains that emit different rustdoc JSON formats,
+/// one side is regenerated with the other side's toolchain so both share a format.
+///
+/// Returns the baseline's library target alongside the data, if its package metadata was loaded.
+fn generate_baseline_data(
+    config: &mut GlobalConfig,
+    generation_settings: data_generation::GenerationSettings,
+    current_loader: &dyn rustdoc_gen::RustdocGenerator,
+    baseline_loader: &dyn rustdoc_gen::RustdocGenerator,
+    current_crate: &Arc<VersionedStorage>,
+    current_crate_data: rustdoc_gen::CrateDataForRustdoc,
+    baseline_crate_data: rustdoc_gen::CrateDataForRustdoc,
+) -> Result<(DataStorage, Option<LibTarget>), TerminalError> {
+    let crate_name = baseline_crate_data.name;
+    let current_toolchain = current_crate_data.toolchain;
+    let baseline_toolchain = baseline_crate_data.toolchain;
     let current_rustdoc_version = current_crate.version();
 
-    let baseline_crate = {
-        let mut baseline_crate = baseline_loader.load_rustdoc(
+    let mut baseline_crate = baseline_loader.load_rustdoc(
+        config,
+        generation_settings,
+        data_generation::CacheSettings::ReadWrite(()),
+        baseline_crate_data.clone(),
+    )?;
+
+    // The baseline rustdoc JSON may have been cached; ensure its rustdoc version matches
+    // the version emitted by the currently-installed toolchain.
+    //
+    // The baseline and current rustdoc JSONs should have the same version.
+    // If the baseline rustdoc version doesn't match, delete the cached baseline and rebuild it.
+    //
+    // Fix for: https://github.com/obi1kenobi/cargo-semver-checks/issues/415
+    if baseline_crate.data.version() != current_rustdoc_version {
+        config
+            .shell_status(
+                "Removing",
+                format_args!("stale cached baseline rustdoc for {crate_name}"),
+            )
+            .into_terminal_result()?;
+
+        baseline_crate = baseline_loader.load_rustdoc(
             config,
             generation_settings,
-            data_generation::CacheSettings::ReadWrite(()),
+            data_generation::CacheSettings::WriteOnly(()),
             baseline_crate_data.clone(),
         )?;
+    }
 
-        // The baseline rustdoc JSON may have been cached; ensure its rustdoc version matches
-        // the version emitted by the currently-installed toolchain.
-        //
-        // The baseline and current rustdoc JSONs should have the same version.
-        // If the baseline rustdoc version doesn't match, delete the cached baseline and rebuild it.
-        //
-        // Fix for: https://github.com/obi1kenobi/cargo-semver-checks/issues/415
-        if baseline_crate.version() != current_rustdoc_version {
-            let crate_name = baseline_crate_name;
-            config
-                .shell_status(
-                    "Removing",
-                    format_args!("stale cached baseline rustdoc for {crate_name}"),
-                )
-                .into_terminal_result()?;
-
-            baseline_crate = baseline_loader.load_rustdoc(
-                config,
-                generation_settings,
-                data_generation::CacheSettings::WriteOnly(()),
-                baseline_crate_data,
-            )?;
-
-            assert_eq!(
-                baseline_crate.version(),
-                current_rustdoc_version,
-                "Deleting and regenerating the baseline JSON file did not resolve the rustdoc \
-                version mismatch."
-            );
+    if baseline_crate.data.version() == current_rustdoc_version {
+        let baseline_lib = baseline_crate.lib_target();
+        return Ok((
+            DataStorage::new(Arc::clone(current_crate), baseline_crate.data),
+            baseline_lib,
+        ));
+    }
+    assert_ne!(
+        baseline_toolchain, current_toolchain,
+        "Deleting and regenerating the baseline JSON file did not resolve the rustdoc \
+        version mismatch."
+    );
+
+    // Different toolchains may legitimately emit different rustdoc formats, which can't be
+    // compared against each other. Both formats are supported, since both sides loaded,
+    // so the common format is whichever one a single toolchain can build both sides in.
+    // The baseline toolchain is tried first, since it's usually chosen because
+    // the baseline no longer builds with the newer toolchain.
+    let describe = |toolchain: Option<&str>| {
+        toolchain
+            .map(|toolchain| format!("toolchain `{toolchain}`"))
+            .unwrap_or_else(|| "the active toolchain".to_string())
+    };
+    let describe_error = |error: TerminalError| match error {
+        TerminalError::WithAdvice(error, _) | TerminalError::Other(error) => format!("{error:#}"),
+    };
+    let baseline_rustdoc_version = baseline_crate.data.version();
+    config
+        .shell_note(format_args!(
+            "the current rustdoc JSON for {crate_name} is in format v{current_rustdoc_version} \
+            but the baseline is in v{baseline_rustdoc_version}, \
+            regenerating the current rustdoc with {}",
+            describe(baseline_toolchain),
+        ))
+        .into_terminal_result()?;
+    let regenerated_current = current_loader.load_rustdoc(
+        config,
+        generation_settings,
+        data_generation::CacheSettings::ReadWrite(()),
+        rustdoc_gen::CrateDataForRustdoc {
+            toolchain: baseline_toolchain,
+            ..current_crate_data
+        },
+    );
+    let current_error = match regenerated_current {
+        Ok(current) if current.data.version() == baseline_rustdoc_version => {
+            let baseline_lib = baseline_crate.lib_target();
+            return Ok((
+                DataStorage::new(Arc::new(current.data), baseline_crate.data),
+                baseline_lib,
+            ));
         }
+        Ok(current) => format!("is in format v{}", current.data.version()),
+        Err(error) => format!("failed to build: {}", describe_error(error)),
+    };
 
-        baseline_crate
+    config
+        .shell_note(format_args!(
+            "regenerating the baseline rustdoc for {crate_name} with {} instead",
+            describe(current_toolchain),
+        ))
+        .into_terminal_result()?;
+    let regenerated_baseline = baseline_loader.load_rustdoc(
+        config,
+        generation_settings,
+        data_generation::CacheSettings::ReadWrite(()),
+        rustdoc_gen::CrateDataForRustdoc {
+            toolchain: current_toolchain,
+            ..baseline_crate_data
+        },
+    );
+    let baseline_error = match regenerated_baseline {
+        Ok(baseline) if baseline.data.version() == current_rustdoc_version => {
+            let baseline_lib = baseline.lib_target();
+            return Ok((
+                DataStorage::new(Arc::clone(current_crate), baseline.data),
+                baseline_lib,
+            ));
+        }
+        Ok(baseline) => format!("is in format v{}", baseline.data.version()),
+        Err(error) => format!("failed to build: {}", describe_error(error)),
     };
 
-    Ok(DataStorage::new(current_crate, baseline_crate))
+    Err(TerminalError::WithAdvice(
+        anyhow::anyhow!(
+            "the current and baseline rustdoc JSON formats differ: \
+            v{current_rustdoc_version} from {} but v{baseline_rustdoc_version} from {}",
+            describe(current_toolchain),
+            describe(baseline_toolchain),
+        ),
+        format!(
+            "note: with {}, the current rustdoc {current_error}\n\
+            note: with {}, the baseline rustdoc {baseline_error}\n\
+            help: choose a current and baseline toolchain pair \
+            that emit the same rustdoc JSON format version",
+            describe(baseline_toolchain),
+            describe(current_toolchain),
+        ),
+    ))
 }
 
 fn manifest_path(project_root: &Path) -> anyhow::Result<PathBuf> {
@@ -845,6 +1790,64 @@ fn manifest_metadata(project_root: &Path) -> anyhow::Result<cargo_metadata::Meta
     Ok(metadata)
 }
 
+/// The lint overrides of a workspace package: the workspace's, if the package inherits them,
+/// then the package's own.
+fn package_overrides(
+    workspace_overrides: Option<&[OverrideMap]>,
+    package: &cargo_metadata::Package,
+) -> anyhow::Result<OverrideStack> {
+    let package_overrides =
+        manifest::deserialize_lint_table(&package.metadata).with_context(|| {
+            format!(
+                "package `{}`'s [package.metadata.cargo-semver-checks] table is invalid (at {})",
+                package.name, package.manifest_path,
+            )
+        })?;
+
+    let mut overrides = OverrideStack::new();
+
+    let manifest = manifest::Manifest::parse(package.manifest_path.clone().into_std_path_buf())?;
+    // the key `lints.workspace` for the Cargo lints table
+    let lint_workspace_key = manifest.parsed.lints.is_some_and(|x| x.workspace);
+    let metadata_workspace_key = package_overrides.as_ref().is_some_and(|x| x.workspace);
+
+    if lint_workspace_key || metadata_workspace_key {
+        if let Some(workspace) = workspace_overrides {
+            for level in workspace {
+                overrides.push(level);
+            }
+        }
+    }
+
+    if let Some(package) = package_overrides {
+        for level in package.into_stack() {
+            overrides.push(&level);
+        }
+    }
+
+    Ok(overrides)
+}
+
+/// The lint overrides configured for the named package of the project at the given root,
+/// or no overrides if the project has no such package.
+pub(crate) fn project_package_overrides(
+    project_root: &Path,
+    crate_name: &str,
+) -> anyhow::Result<OverrideStack> {
+    let metadata = manifest_metadata_no_deps(project_root)?;
+    let Some(package) = metadata
+        .workspace_packages()
+        .into_iter()
+        .find(|package| package.name == crate_name)
+    else {
+        return Ok(OverrideStack::new());
+    };
+    let workspace_overrides = manifest::deserialize_lint_table(&metadata.workspace_metadata)
+        .context("[workspace.metadata.cargo-semver-checks] table is invalid")?
+        .map(|table| table.into_stack());
+    package_overrides(workspace_overrides.as_deref(), package)
+}
+
 fn manifest_metadata_no_deps(project_root: &Path) -> anyhow::Result<cargo_metadata::Metadata> {
     let manifest_path = manifest_path(project_root)?;
     let mut command = cargo_metadata::MetadataCommand::new();
@@ -873,7 +1876,202 @@ fn get_target_dir_from_project_root(source: &RustdocSource) -> anyhow::Result<Op
             let target = target.join(format!("git-{}", util::slugify(rev)));
             Some(target)
         }
+        RustdocSource::GitTags(root) => {
+            let metadata = manifest_metadata_no_deps(root)?;
+            let target = metadata.target_directory.as_std_path().join(util::SCOPE);
+            Some(target)
+        }
+        RustdocSource::MergeBase(root, branch) => {
+            let metadata = manifest_metadata_no_deps(root)?;
+            let target = metadata.target_directory.as_std_path().join(util::SCOPE);
+            let target = target.join(format!("git-merge-base-{}", util::slugify(branch)));
+            Some(target)
+        }
         RustdocSource::Rustdoc(_path) => None,
+        RustdocSource::CrateFile(_path) => None,
         RustdocSource::VersionFromRegistry(_version) => None,
+        RustdocSource::LatestInEachMajorFromRegistry => None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use rustdoc_gen::{CrateType, FeatureConfig, RustdocGenerator};
+
+    /// The format each toolchain emits, or `None` if the crate fails to build with it.
+    type Formats = BTreeMap<Option<&'static str>, Option<u32>>;
+
+    /// The toolchain of each build, in order.
+    type Builds = Vec<Option<String>>;
+
+    /// Generates empty rustdoc JSON in the format each side emits with each toolchain.
+    struct FakeGenerator {
+        dir: PathBuf,
+        formats: Formats,
+        builds: RefCell<Builds>,
+    }
+
+    impl RustdocGenerator for FakeGenerator {
+        fn load_rustdoc(
+            &self,
+            _config: &mut GlobalConfig,
+            _generation_settings: data_generation::GenerationSettings,
+            _cache_settings: data_generation::CacheSettings<()>,
+            crate_data: CrateDataForRustdoc,
+        ) -> Result<data_generation::LoadedCrate, TerminalError> {
+            self.builds
+                .borrow_mut()
+                .push(crate_data.toolchain.map(str::to_string));
+            let Some(format) = self.formats[&crate_data.toolchain] else {
+                return Err(TerminalError::Other(anyhow::anyhow!("build failed")));
+            };
+
+            let path = self.dir.join(format!("v{format}.json"));
+            fs_err::write(
+                &path,
+                format!(
+                    r#"{{"root":0,"crate_version":null,"includes_private":false,"index":{{}},
+                    "paths":{{}},"external_crates":{{}},"format_version":{format}}}"#
+                ),
+            )?;
+            Ok(data_generation::LoadedCrate {
+                data: trustfall_rustdoc::load_rustdoc(&path, None)?,
+                json_path: Some(path),
+                package: None,
+            })
+        }
+    }
+
+    fn load_with_formats(
+        test_name: &str,
+        current: Formats,
+        baseline: Formats,
+    ) -> (Result<DataStorage, TerminalError>, Builds, Builds) {
+        let dir =
+            std::env::temp_dir().join(format!("semver-checks-{test_name}-{}", std::process::id()));
+        fs_err::create_dir_all(&dir).expect("failed to create dir");
+        let current_loader = FakeGenerator {
+            dir: dir.clone(),
+            formats: current,
+            builds: RefCell::default(),
+        };
+        let baseline_loader = FakeGenerator {
+            dir: dir.clone(),
+            formats: baseline,
+            builds: RefCell::default(),
+        };
+
+        let mut config = GlobalConfig::new();
+        let settings = data_generation::GenerationSettings {
+            pass_through_stderr: false,
+            use_color: false,
+            deps: false,
+            locked: false,
+            offline: false,
+        };
+        let current_feature_config = FeatureConfig::default_for_current();
+        let baseline_feature_config = FeatureConfig::default_for_baseline();
+        let current_crate_data = CrateDataForRustdoc {
+            crate_type: CrateType::Current,
+            name: "example",
+            feature_config: &current_feature_config,
+            build_target: None,
+            toolchain: Some("new"),
+        };
+        let baseline_crate_data = CrateDataForRustdoc {
+            crate_type: CrateType::Baseline {
+                highest_allowed_version: None,
+            },
+            name: "example",
+            feature_config: &baseline_feature_config,
+            build_target: None,
+            toolchain: Some("old"),
+        };
+
+        let current_crate = current_loader
+            .load_rustdoc(
+                &mut config,
+                settings,
+                data_generation::CacheSettings::ReadWrite(()),
+                current_crate_data.clone(),
+            )
+            .map_err(|_| "failed to load current crate")
+            .expect("current crate loads");
+        current_loader.builds.borrow_mut().clear();
+
+        let result = generate_baseline_data(
+            &mut config,
+            settings,
+            &current_loader,
+            &baseline_loader,
+            &Arc::new(current_crate.data),
+            current_crate_data,
+            baseline_crate_data,
+        )
+        .map(|(data_storage, _)| data_storage);
+        std::fs::remove_dir_all(&dir).expect("failed to clean up");
+        (
+            result,
+            current_loader.builds.into_inner(),
+            baseline_loader.builds.into_inner(),
+        )
+    }
+
+    #[test]
+    fn format_mismatch_rebuilds_current_with_baseline_toolchain() {
+        let (result, current_builds, baseline_builds) = load_with_formats(
+            "format-mismatch-current",
+            [(Some("new"), Some(39)), (Some("old"), Some(37))].into(),
+            [(Some("new"), None), (Some("old"), Some(37))].into(),
+        );
+        let data = result
+            .map_err(|_| "failed to load data")
+            .expect("a common format exists");
+        assert_eq!(data.current_crate().version(), 37);
+        assert_eq!(data.baseline_crate().version(), 37);
+        assert_eq!(current_builds, [Some("old".to_string())]);
+        // The first baseline may have been cached in another format, so it's rebuilt once.
+        assert_eq!(
+            baseline_builds,
+            [Some("old".to_string()), Some("old".to_string())]
+        );
+    }
+
+    #[test]
+    fn format_mismatch_rebuilds_baseline_with_current_toolchain() {
+        let (result, current_builds, baseline_builds) = load_with_formats(
+            "format-mismatch-baseline",
+            [(Some("new"), Some(39)), (Some("old"), None)].into(),
+            [(Some("new"), Some(39)), (Some("old"), Some(37))].into(),
+        );
+        let data = result
+            .map_err(|_| "failed to load data")
+            .expect("a common format exists");
+        assert_eq!(data.current_crate().version(), 39);
+        assert_eq!(data.baseline_crate().version(), 39);
+        assert_eq!(current_builds, [Some("old".to_string())]);
+        assert_eq!(baseline_builds.last(), Some(&Some("new".to_string())));
+    }
+
+    #[test]
+    fn format_mismatch_without_common_format() {
+        let (result, _, _) = load_with_formats(
+            "format-mismatch-none",
+            [(Some("new"), Some(39)), (Some("old"), None)].into(),
+            [(Some("new"), None), (Some("old"), Some(37))].into(),
+        );
+        let Err(TerminalError::WithAdvice(error, advice)) = result else {
+            panic!("expected an error with advice");
+        };
+        assert_eq!(
+            error.to_string(),
+            "the current and baseline rustdoc JSON formats differ: \
+            v39 from toolchain `new` but v37 from toolchain `old`"
+        );
+        assert!(advice.contains("with toolchain `old`, the current rustdoc failed to build"));
+        assert!(advice.contains("with toolchain `new`, the baseline rustdoc failed to build"));
+    }
+}
@@ -0,0 +1,422 @@
+//! Support for testing lints, including ones that live outside this repository.
+//!
+//! A [`LintTest`] runs a single lint definition against a baseline and current version
+//! of a crate, then compares the lint's output against a previously-recorded expectation file.
+
+use std::collections::BTreeMap;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Context;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use trustfall::{FieldValue, TransparentValue};
+use trustfall_rustdoc::{VersionedIndex, VersionedRustdocAdapter};
+
+use crate::data_generation::{DataStorage, TerminalError};
+use crate::rustdoc_gen::{self, CrateDataForRustdoc, FeatureConfig};
+use crate::{GlobalConfig, RustdocSource, SemverQuery};
+
+/// Test a single lint against a pair of crate versions.
+///
+/// The lint's results are compared against an expectation file,
+/// which is RON-encoded unless its extension is `.json`.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintTest {
+    /// Path to the lint's `.ron` definition file.
+    lint_path: PathBuf,
+    /// Directory containing the baseline version of the test crate.
+    baseline_root: PathBuf,
+    /// Directory containing the current version of the test crate.
+    current_root: PathBuf,
+    /// Path to the file containing the expected lint output.
+    expected_path: PathBuf,
+    /// Whether to overwrite the expectation file instead of comparing against it.
+    bless: bool,
+}
+
+/// Outcome of running a [`LintTest`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct LintTestReport {
+    /// Difference between the expected and actual output, if any.
+    diff: Option<String>,
+    /// Whether the expectation file was (re)written with the actual output.
+    blessed: bool,
+}
+
+impl LintTestReport {
+    /// `true` if the lint produced the expected output, or the expectation file was blessed.
+    pub fn success(&self) -> bool {
+        self.diff.is_none()
+    }
+
+    /// Line diff between the expected and actual output, if they don't match.
+    pub fn diff(&self) -> Option<&str> {
+        self.diff.as_deref()
+    }
+
+    /// `true` if the expectation file was overwritten with the actual output.
+    pub fn blessed(&self) -> bool {
+        self.blessed
+    }
+}
+
+/// The recorded output of a lint, as stored in the expectation file.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct LintTestExpectation {
+    /// The `@output` values of each result of the lint's query.
+    results: Vec<BTreeMap<String, FieldValue>>,
+    /// Each result rendered with the lint's `per_result_error_template`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    messages: Vec<String>,
+    /// Each result rendered with the lint's witness `hint_template`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    witness_hints: Vec<String>,
+    /// Each result rendered with the lint's `shim_template`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    shims: Vec<String>,
+}
+
+impl LintTest {
+    pub fn new(
+        lint_path: impl Into<PathBuf>,
+        baseline_root: impl Into<PathBuf>,
+        current_root: impl Into<PathBuf>,
+        expected_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            lint_path: lint_path.into(),
+            baseline_root: baseline_root.into(),
+            current_root: current_root.into(),
+            expected_path: expected_path.into(),
+            bless: false,
+        }
+    }
+
+    /// Write the lint's actual output to the expectation file instead of comparing against it.
+    pub fn set_bless(&mut self, bless: bool) -> &mut Self {
+        self.bless = bless;
+        self
+    }
+
+    pub fn run(&self, config: &mut GlobalConfig) -> anyhow::Result<LintTestReport> {
+        let query_text = fs_err::read_to_string(&self.lint_path)?;
+        let semver_query = SemverQuery::from_ron_str(&query_text)
+            .with_context(|| format!("failed to parse lint file {}", self.lint_path.display()))?;
+        let errors = crate::validate_lint(&semver_query);
+        if !errors.is_empty() {
+            anyhow::bail!(
+                "lint file {} is invalid:\n{}",
+                self.lint_path.display(),
+                errors.iter().map(|e| format!("  {e}")).join("\n")
+            );
+        }
+
+        config.shell_status(
+            "Testing",
+            format_args!(
+                "lint `{}` on {} -> {}",
+                semver_query.id,
+                self.baseline_root.display(),
+                self.current_root.display(),
+            ),
+        )?;
+
+        let data_storage = self.generate_crate_data(config)?;
+        let current = VersionedIndex::from_storage(data_storage.current_crate());
+        let baseline = VersionedIndex::from_storage(data_storage.baseline_crate());
+
+        // Comparing a crate against itself must never produce any results.
+        for (index, version) in [(&baseline, "baseline"), (&current, "current")] {
+            let adapter = VersionedRustdocAdapter::new(index, Some(index))?;
+            let results = semver_query.run_with_ordered_folds(&adapter)?;
+            if !results.is_empty() {
+                anyhow::bail!(
+                    "lint `{}` produced {} result(s) when comparing the {version} crate \
+                    against itself, which indicates a false positive:\n{}",
+                    semver_query.id,
+                    results.len(),
+                    ron::ser::to_string_pretty(&results, ron::ser::PrettyConfig::default())?,
+                );
+            }
+        }
+
+        let adapter = VersionedRustdocAdapter::new(&current, Some(&baseline))?;
+        let results = sort_results(
+            &semver_query,
+            semver_query.run_with_ordered_folds(&adapter)?,
+        )?;
+
+        let actual = render_expectation(config, &semver_query, results)?;
+        let actual_text = serialize_expectation(&self.expected_path, &actual)?;
+
+        if self.bless {
+            fs_err::write(&self.expected_path, &actual_text)?;
+            config.shell_status("Blessed", self.expected_path.display())?;
+            return Ok(LintTestReport {
+                diff: None,
+                blessed: true,
+            });
+        }
+
+        let expected_text = match fs_err::read_to_string(&self.expected_path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => anyhow::bail!(
+                "expectation file {} does not exist, rerun with `--bless` to create it",
+                self.expected_path.display()
+            ),
+            Err(e) => return Err(e.into()),
+        };
+        let expected = deserialize_expectation(&self.expected_path, &expected_text)?;
+
+        let diff = if expected == actual {
+            None
+        } else {
+            // Compare against the normalized serialization of the expectation,
+            // so formatting differences in the file don't show up in the diff.
+            let expected_text = serialize_expectation(&self.expected_path, &expected)?;
+            Some(
+                similar::TextDiff::from_lines(&expected_text, &actual_text)
+                    .unified_diff()
+                    .header("expected", "actual")
+                    .to_string(),
+            )
+        };
+
+        Ok(LintTestReport {
+            diff,
+            blessed: false,
+        })
+    }
+
+    fn generate_crate_data(&self, config: &mut GlobalConfig) -> anyhow::Result<DataStorage> {
+        let generation_settings = crate::data_generation::GenerationSettings {
+            use_color: config.err_color_choice(),
+            deps: false,
+            pass_through_stderr: config.is_verbose(),
+            locked: false,
+            offline: false,
+        };
+
+        let baseline_name = package_name(&self.baseline_root)?;
+        let current_name = package_name(&self.current_root)?;
+        if baseline_name != current_name {
+            anyhow::bail!(
+                "baseline package `{baseline_name}` and current package `{current_name}` \
+                must have the same name"
+            );
+        }
+
+        let baseline_loader = project_root_loader(&self.baseline_root)?;
+        let current_loader = project_root_loader(&self.current_root)?;
+        let current_feature_config = FeatureConfig::default_for_current();
+        let baseline_feature_config = FeatureConfig::default_for_baseline();
+
+        match crate::generate_crate_data(
+            config,
+            generation_settings,
+            &current_loader,
+            &baseline_loader,
+            CrateDataForRustdoc {
+                crate_type: rustdoc_gen::CrateType::Current,
+                name: &current_name,
+                feature_config: &current_feature_config,
+                build_target: None,
+                toolchain: None,
+            },
+            CrateDataForRustdoc {
+                crate_type: rustdoc_gen::CrateType::Baseline {
+                    highest_allowed_version: None,
+                },
+                name: &baseline_name,
+                feature_config: &baseline_feature_config,
+                build_target: None,
+                toolchain: None,
+            },
+        ) {
+            Ok(data) => Ok(data),
+            Err(TerminalError::WithAdvice(err, advice)) => {
+                config.log_error(|config| {
+                    writeln!(config.stderr(), "{advice}")?;
+                    Ok(())
+                })?;
+                Err(err)
+            }
+            Err(TerminalError::Other(err)) => Err(err),
+        }
+    }
+}
+
+fn package_name(project_root: &Path) -> anyhow::Result<String> {
+    let manifest = crate::manifest::Manifest::parse(crate::manifest_path(project_root)?)?;
+    crate::manifest::get_package_name(&manifest).map(ToString::to_string)
+}
+
+fn project_root_loader(project_root: &Path) -> anyhow::Result<rustdoc_gen::RustdocFromProjectRoot> {
+    let target_dir =
+        crate::get_target_dir_from_project_root(&RustdocSource::Root(project_root.to_owned()))?
+            .expect("project roots always have a target directory");
+    rustdoc_gen::RustdocFromProjectRoot::new(project_root, &target_dir)
+}
+
+/// Sorts results into a deterministic order, the same way this repository's lint tests do.
+///
+/// Lints must either output an explicit `ordering_key` string, or output both
+/// `span_filename` and `span_begin_line` values where the lint is being raised.
+fn sort_results(
+    semver_query: &SemverQuery,
+    results: Vec<BTreeMap<String, FieldValue>>,
+) -> anyhow::Result<Vec<BTreeMap<String, FieldValue>>> {
+    let mut keyed = results
+        .into_iter()
+        .map(|result| {
+            let key = if let Some(key) = result.get("ordering_key").and_then(FieldValue::as_arc_str)
+            {
+                (Arc::clone(key), 0)
+            } else {
+                let filename = result.get("span_filename").and_then(FieldValue::as_arc_str);
+                let line = result.get("span_begin_line").and_then(FieldValue::as_usize);
+                match (filename, line) {
+                    (Some(filename), Some(line)) => (Arc::clone(filename), line),
+                    _ => anyhow::bail!(
+                        "lint `{}` must either output an explicit `ordering_key` string, \
+                        or output both `span_filename` and `span_begin_line`",
+                        semver_query.id
+                    ),
+                }
+            };
+            Ok((key, result))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(keyed.into_iter().map(|(_, result)| result).collect())
+}
+
+/// Renders the lint's templates for each result, checking that they materialize correctly.
+fn render_expectation(
+    config: &GlobalConfig,
+    semver_query: &SemverQuery,
+    results: Vec<BTreeMap<String, FieldValue>>,
+) -> anyhow::Result<LintTestExpectation> {
+    let mut messages = vec![];
+    let mut witness_hints = vec![];
+    let mut shims = vec![];
+    for result in &results {
+        let pretty_result: BTreeMap<&str, TransparentValue> = result
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.clone().into()))
+            .collect();
+
+        if let Some(template) = semver_query.per_result_error_template.as_deref() {
+            messages.push(
+                config
+                    .handlebars()
+                    .render_template(template, &pretty_result)
+                    .context("Error instantiating semver query template.")?,
+            );
+        }
+
+        if let Some(witness) = &semver_query.witness {
+            witness_hints.push(
+                config
+                    .handlebars()
+                    .render_template(&witness.hint_template, &pretty_result)
+                    .context("Error instantiating witness hint template.")?,
+            );
+        }
+
+        if let Some(template) = semver_query.shim_template.as_deref() {
+            shims.push(
+                config
+                    .handlebars()
+                    .render_template(template, &pretty_result)
+                    .context("Error instantiating shim template.")?,
+            );
+        }
+    }
+
+    Ok(LintTestExpectation {
+        results,
+        messages,
+        witness_hints,
+        shims,
+    })
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+fn serialize_expectation(path: &Path, expectation: &LintTestExpectation) -> anyhow::Result<String> {
+    let mut text = if is_json(path) {
+        serde_json::to_string_pretty(expectation)?
+    } else {
+        ron::ser::to_string_pretty(expectation, ron::ser::PrettyConfig::default())?
+    };
+    text.push('\n');
+    Ok(text)
+}
+
+fn deserialize_expectation(path: &Path, text: &str) -> anyhow::Result<LintTestExpectation> {
+    if is_json(path) {
+        serde_json::from_str(text)
+            .with_context(|| format!("failed to parse expectation file {}", path.display()))
+    } else {
+        ron::from_str(text)
+            .with_context(|| format!("failed to parse expectation file {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use trustfall::FieldValue;
+
+    use super::{deserialize_expectation, serialize_expectation, LintTestExpectation};
+
+    fn sample_expectation() -> LintTestExpectation {
+        LintTestExpectation {
+            results: vec![BTreeMap::from([
+                ("name".to_string(), FieldValue::from("foo")),
+                ("span_begin_line".to_string(), FieldValue::Uint64(4)),
+                (
+                    "path".to_string(),
+                    FieldValue::from(vec!["my_crate", "foo"]),
+                ),
+            ])],
+            messages: vec!["function foo, previously in file src/lib.rs:4".into()],
+            witness_hints: vec!["my_crate::foo(...);".into()],
+            shims: vec![],
+        }
+    }
+
+    #[test]
+    fn expectation_round_trips_as_ron() {
+        let path = std::path::Path::new("expected.ron");
+        let expectation = sample_expectation();
+        let text = serialize_expectation(path, &expectation).expect("failed to serialize");
+        let parsed = deserialize_expectation(path, &text).expect("failed to deserialize");
+        assert_eq!(expectation, parsed);
+    }
+
+    #[test]
+    fn expectation_round_trips_as_json() {
+        let path = std::path::Path::new("expected.json");
+        let expectation = sample_expectation();
+        let text = serialize_expectation(path, &expectation).expect("failed to serialize");
+        assert!(text.trim_start().starts_with('{'), "{text}");
+        let parsed = deserialize_expectation(path, &text).expect("failed to deserialize");
+        assert_eq!(expectation, parsed);
+    }
+
+    #[test]
+    fn empty_template_outputs_are_optional() {
+        let path = std::path::Path::new("expected.ron");
+        let parsed = deserialize_expectation(path, "(results: [])").expect("failed to parse");
+        assert_eq!(LintTestExpectation::default(), parsed);
+    }
+}
@@ -0,0 +1,520 @@
+//! Static validation of lint definitions.
+//!
+//! Checks that a [`SemverQuery`] is well-formed without running it:
+//! its queries must be valid against the rustdoc schema, its declared arguments must match
+//! the variables its queries use, and its templates must only refer to values that exist.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, OnceLock};
+
+use serde_json::Value;
+use trustfall::{FieldValue, Schema};
+use trustfall_core::ir::{IndexedQuery, Type};
+
+use crate::query::InheritedValue;
+use crate::templating::make_handlebars_registry;
+use crate::SemverQuery;
+
+/// A problem found in a lint definition.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintDefinitionError {
+    /// The id of the lint with the problem.
+    pub lint_id: String,
+    /// Description of the problem.
+    pub message: String,
+}
+
+impl std::fmt::Display for LintDefinitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "lint `{}`: {}", self.lint_id, self.message)
+    }
+}
+
+impl std::error::Error for LintDefinitionError {}
+
+/// The schemas of all the rustdoc formats we support, with their format version.
+///
+/// Lints run against every supported format, so they must be valid for each of them.
+fn rustdoc_schemas() -> &'static [(u32, Schema)] {
+    static SCHEMAS: OnceLock<Vec<(u32, Schema)>> = OnceLock::new();
+    SCHEMAS.get_or_init(|| {
+        vec![
+            (35, trustfall_rustdoc_adapter_v35::RustdocAdapter::schema()),
+            (36, trustfall_rustdoc_adapter_v36::RustdocAdapter::schema()),
+            (37, trustfall_rustdoc_adapter_v37::RustdocAdapter::schema()),
+            (39, trustfall_rustdoc_adapter_v39::RustdocAdapter::schema()),
+        ]
+    })
+}
+
+/// Checks the given lint definition for problems that would otherwise
+/// only be detected when its query runs, returning all the problems found.
+///
+/// The lint is checked against the schema of each supported rustdoc format.
+/// Problems that only affect some formats say which ones.
+pub fn validate_lint(semver_query: &SemverQuery) -> Vec<LintDefinitionError> {
+    let schemas = rustdoc_schemas();
+
+    // Problems in the order they were first found, with the formats they were found for.
+    let mut problems: Vec<(String, Vec<u32>)> = vec![];
+    for (version, schema) in schemas {
+        for message in validate_lint_for_schema(semver_query, schema) {
+            match problems
+                .iter_mut()
+                .find(|(existing, _)| *existing == message)
+            {
+                Some((_, versions)) => versions.push(*version),
+                None => problems.push((message, vec![*version])),
+            }
+        }
+    }
+
+    problems
+        .into_iter()
+        .map(|(message, versions)| {
+            let message = if versions.len() == schemas.len() {
+                message
+            } else {
+                let versions = versions
+                    .iter()
+                    .map(|version| format!("v{version}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{message} (with rustdoc {versions})")
+            };
+            LintDefinitionError {
+                lint_id: semver_query.id.clone(),
+                message,
+            }
+        })
+        .collect()
+}
+
+fn validate_lint_for_schema(semver_query: &SemverQuery, schema: &Schema) -> Vec<String> {
+    let mut errors = vec![];
+    let mut report = |message: String| errors.push(message);
+
+    let parsed_query = match trustfall_core::frontend::parse(schema, &semver_query.query) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            report(format!("query is not valid: {e}"));
+            return errors;
+        }
+    };
+
+    check_arguments(
+        "query",
+        &parsed_query,
+        semver_query
+            .arguments
+            .iter()
+            .map(|(name, value)| (name.as_str(), Some(FieldValue::from(value.clone())))),
+        &mut report,
+    );
+
+    let outputs = &parsed_query.outputs;
+    let has_ordering_key = outputs.contains_key("ordering_key");
+    let has_span = outputs.contains_key("span_filename") && outputs.contains_key("span_begin_line");
+    if !has_ordering_key && !has_span {
+        report(
+            "query must either output an explicit `ordering_key`, \
+            or output both `span_filename` and `span_begin_line`"
+                .to_string(),
+        );
+    }
+
+    let output_values = placeholder_values(outputs);
+    if let Some(template) = &semver_query.per_result_error_template {
+        check_template(
+            "per_result_error_template",
+            template,
+            &output_values,
+            &mut report,
+        );
+    }
+
+    if let Some(template) = &semver_query.shim_template {
+        // Shims may also refer to where a missing item went, how to forward to it,
+        // and the declaration of a newly-required trait method.
+        let mut shim_values = output_values.clone();
+        for name in [
+            "module",
+            "new_path",
+            "wrapper_signature",
+            "forwarding_call",
+            "method_signature",
+        ] {
+            shim_values.insert(Arc::from(name), Value::from(name));
+        }
+        check_template("shim_template", template, &shim_values, &mut report);
+    }
+
+    if let Some(witness) = &semver_query.witness {
+        check_template(
+            "witness.hint_template",
+            &witness.hint_template,
+            &output_values,
+            &mut report,
+        );
+
+        let mut witness_values = output_values.clone();
+        if let Some(witness_query) = &witness.witness_query {
+            match trustfall_core::frontend::parse(schema, &witness_query.query) {
+                Ok(parsed_witness_query) => {
+                    let mut arguments = vec![];
+                    for (name, value) in &witness_query.arguments {
+                        match value {
+                            InheritedValue::Inherited { inherit } => {
+                                if outputs.contains_key(inherit.as_str()) {
+                                    // The inherited value's type is checked by trustfall
+                                    // when the witness query runs.
+                                    arguments.push((name.as_str(), None));
+                                } else {
+                                    report(format!(
+                                        "witness query argument `{name}` inherits \
+                                        `{inherit}`, which is not an output of the query"
+                                    ));
+                                }
+                            }
+                            InheritedValue::Constant(value) => {
+                                arguments.push((name.as_str(), Some(value.clone().into())));
+                            }
+                        }
+                    }
+                    check_arguments(
+                        "witness query",
+                        &parsed_witness_query,
+                        arguments.into_iter(),
+                        &mut report,
+                    );
+
+                    // The witness query's outputs take precedence over the original query's.
+                    witness_values.extend(placeholder_values(&parsed_witness_query.outputs));
+                }
+                Err(e) => report(format!("witness query is not valid: {e}")),
+            }
+        }
+
+        if let Some(template) = &witness.witness_template {
+            check_template(
+                "witness.witness_template",
+                template,
+                &witness_values,
+                &mut report,
+            );
+        }
+    }
+
+    errors
+}
+
+/// Checks that the declared arguments match the variables used by the query,
+/// and that arguments with known values have the type the query expects.
+fn check_arguments<'a>(
+    query_kind: &str,
+    parsed_query: &IndexedQuery,
+    arguments: impl Iterator<Item = (&'a str, Option<FieldValue>)>,
+    report: &mut impl FnMut(String),
+) {
+    let variables = &parsed_query.ir_query.variables;
+    let mut declared = BTreeSet::new();
+    for (name, value) in arguments {
+        declared.insert(name);
+        match variables.get(name) {
+            None => report(format!(
+                "{query_kind} argument `{name}` is declared but not used by the {query_kind}"
+            )),
+            Some(variable_type) => {
+                if let Some(value) = value {
+                    if !variable_type.is_valid_value(&value) {
+                        report(format!(
+                            "{query_kind} argument `{name}` has value {value:?}, \
+                            which is not valid for its type `{variable_type}`"
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    for variable in variables.keys() {
+        if !declared.contains(variable.as_ref()) {
+            report(format!(
+                "{query_kind} uses variable `${variable}`, \
+                but no such argument is declared"
+            ));
+        }
+    }
+}
+
+/// Renders the template with placeholder values, to ensure it only refers to outputs that exist.
+fn check_template(
+    field: &str,
+    template: &str,
+    values: &BTreeMap<Arc<str>, Value>,
+    report: &mut impl FnMut(String),
+) {
+    let registry = make_handlebars_registry();
+    if let Err(e) = registry.render_template(template, values) {
+        report(format!("{field} failed to render: {e}"));
+    }
+}
+
+/// Constructs a placeholder value of the right type for each output.
+fn placeholder_values(
+    outputs: &BTreeMap<Arc<str>, trustfall_core::ir::Output>,
+) -> BTreeMap<Arc<str>, Value> {
+    outputs
+        .iter()
+        .map(|(name, output)| {
+            (
+                Arc::clone(name),
+                placeholder_value(name, &output.value_type),
+            )
+        })
+        .collect()
+}
+
+fn placeholder_value(name: &str, value_type: &Type) -> Value {
+    if let Some(inner) = value_type.as_list() {
+        Value::Array(vec![placeholder_value(name, &inner)])
+    } else {
+        match value_type.base_type() {
+            "Int" => Value::from(1),
+            "Float" => Value::from(1.0),
+            "Boolean" => Value::from(true),
+            _ => Value::from(name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_lint;
+    use crate::SemverQuery;
+
+    fn query_with(query: &str, extra_fields: &str) -> SemverQuery {
+        SemverQuery::from_ron_str(&format!(
+            r##"SemverQuery(
+                id: "test_lint",
+                human_readable_name: "test lint",
+                description: "test lint",
+                required_update: Major,
+                lint_level: Deny,
+                query: r#"{query}"#,
+                error_message: "test lint",
+                {extra_fields}
+            )"##
+        ))
+        .expect("failed to parse lint")
+    }
+
+    const FUNCTION_QUERY: &str = r#"
+    {
+        Crate {
+            item {
+                ... on Function {
+                    name @output
+                         @filter(op: "!=", value: ["$excluded"])
+
+                    span_: span @optional {
+                        filename @output
+                        begin_line @output
+                    }
+                }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn all_built_in_lints_are_valid() {
+        let errors: Vec<_> = SemverQuery::all_queries()
+            .values()
+            .flat_map(validate_lint)
+            .map(|e| e.to_string())
+            .collect();
+        assert!(errors.is_empty(), "{errors:#?}");
+    }
+
+    #[test]
+    fn valid_lint() {
+        let query = query_with(
+            FUNCTION_QUERY,
+            r#"arguments: { "excluded": "main" },
+            per_result_error_template: Some("{{name}} in {{span_filename}}:{{span_begin_line}}"),"#,
+        );
+        assert_eq!(validate_lint(&query), vec![]);
+    }
+
+    #[test]
+    fn invalid_query() {
+        let query = query_with("{ Crate { nonexistent_edge { name @output } } }", "");
+        let errors = validate_lint(&query);
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(
+            errors[0].message.starts_with("query is not valid"),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn argument_mismatches() {
+        let query = query_with(FUNCTION_QUERY, r#"arguments: { "unused": true },"#);
+        let errors: Vec<_> = validate_lint(&query)
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                "query argument `unused` is declared but not used by the query",
+                "query uses variable `$excluded`, but no such argument is declared",
+            ],
+        );
+
+        let query = query_with(FUNCTION_QUERY, r#"arguments: { "excluded": 1 },"#);
+        let errors = validate_lint(&query);
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(
+            errors[0].message.contains("is not valid for its type"),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn problem_in_some_rustdoc_formats() {
+        // `unsafe` on statics is nullable in the oldest supported rustdoc format only,
+        // so a null argument is invalid for all the other formats.
+        let query = query_with(
+            r#"
+            {
+                Crate {
+                    item {
+                        ... on Static {
+                            name @output
+                            unsafe @filter(op: "=", value: ["$unsafe"])
+
+                            span_: span @optional {
+                                filename @output
+                                begin_line @output
+                            }
+                        }
+                    }
+                }
+            }"#,
+            r#"arguments: { "unsafe": () },"#,
+        );
+        let errors = validate_lint(&query);
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(
+            errors[0]
+                .message
+                .ends_with("is not valid for its type `Boolean!` (with rustdoc v36, v37, v39)"),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn template_refers_to_missing_output() {
+        let query = query_with(
+            FUNCTION_QUERY,
+            r#"arguments: { "excluded": "main" },
+            per_result_error_template: Some("{{name}} in {{nonexistent}}"),
+            witness: (
+                hint_template: "{{also_nonexistent}}",
+            ),"#,
+        );
+        let errors = validate_lint(&query);
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(
+            errors[0]
+                .message
+                .starts_with("per_result_error_template failed to render"),
+            "{errors:?}"
+        );
+        assert!(
+            errors[1]
+                .message
+                .starts_with("witness.hint_template failed to render"),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn template_helper_type_mismatch() {
+        let query = query_with(
+            FUNCTION_QUERY,
+            r#"arguments: { "excluded": "main" },
+            per_result_error_template: Some("{{join \",\" name}}"),"#,
+        );
+        let errors = validate_lint(&query);
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(
+            errors[0]
+                .message
+                .starts_with("per_result_error_template failed to render"),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn witness_query_inherits_missing_output() {
+        let query = query_with(
+            FUNCTION_QUERY,
+            r##"arguments: { "excluded": "main" },
+            witness: (
+                hint_template: "{{name}}(...);",
+                witness_template: Some("{{name}} {{witness_name}}"),
+                witness_query: (
+                    query: r#"
+                    {
+                        Crate {
+                            item {
+                                ... on Function {
+                                    name @filter(op: "=", value: ["$name"])
+                                    witness_name: name @output
+                                }
+                            }
+                        }
+                    }"#,
+                    arguments: {
+                        "name": (inherit: "nonexistent"),
+                    },
+                ),
+            ),"##,
+        );
+        let errors: Vec<_> = validate_lint(&query)
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                "witness query argument `name` inherits `nonexistent`, \
+                which is not an output of the query",
+                "witness query uses variable `$name`, but no such argument is declared",
+            ],
+        );
+    }
+
+    #[test]
+    fn missing_ordering_outputs() {
+        let query = query_with(
+            r#"
+            {
+                Crate {
+                    item {
+                        ... on Function {
+                            name @output
+                        }
+                    }
+                }
+            }"#,
+            "",
+        );
+        let errors = validate_lint(&query);
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(errors[0].message.contains("ordering_key"), "{errors:?}");
+    }
+}
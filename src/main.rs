@@ -3,12 +3,13 @@
 use std::{collections::HashSet, env, path::PathBuf};
 
 use anstyle::{AnsiColor, Color, Reset, Style};
+use anyhow::Context;
 use cargo_config2::Config;
 use cargo_semver_checks::{
-    FeatureFlag, GlobalConfig, PackageSelection, ReleaseType, Rustdoc, ScopeSelection, SemverQuery,
-    WitnessGeneration,
+    ActualSemverUpdate, FeatureFlag, GlobalConfig, PackageSelection, ReleaseType, Rustdoc,
+    ScopeSelection, SemverQuery, WitnessGeneration,
 };
-use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum as _};
 use std::io::Write;
 
 #[cfg(test)]
@@ -153,13 +154,94 @@ fn main() {
 
     let check_release = match args.command {
         Some(SemverChecksCommands::CheckRelease(c)) => c,
+        Some(SemverChecksCommands::LintTest(lint_test)) => {
+            let lint_test: cargo_semver_checks::LintTest = lint_test.into();
+            let report = exit_on_error(config.is_error(), || lint_test.run(&mut config));
+            if let Some(diff) = report.diff() {
+                exit_on_error(config.is_error(), || {
+                    config.shell_error("lint output did not match the expectation file")?;
+                    writeln!(config.stderr(), "{diff}")?;
+                    config.shell_note("rerun with `--bless` to accept the new output")
+                });
+                std::process::exit(1);
+            }
+            std::process::exit(0);
+        }
+        Some(SemverChecksCommands::Bisect(bisect)) => {
+            let bisect: cargo_semver_checks::Bisect = bisect.into();
+            let report = exit_on_error(config.is_error(), || bisect.run(&mut config));
+            exit_on_error(config.is_error(), || {
+                for finding in report.findings() {
+                    write!(
+                        config.stdout(),
+                        "{} {} -> {} {}",
+                        finding.lint_id(),
+                        finding.item(),
+                        &finding.commit()[..12.min(finding.commit().len())],
+                        finding.summary(),
+                    )?;
+                    match finding.untested_before() {
+                        0 => {}
+                        1 => write!(
+                            config.stdout(),
+                            " (or the commit before it, which couldn't be checked)"
+                        )?,
+                        untested => write!(
+                            config.stdout(),
+                            " (or one of the {untested} commits before it, which couldn't be checked)"
+                        )?,
+                    }
+                    writeln!(config.stdout())?;
+                }
+                Ok(())
+            });
+            std::process::exit(if report.success() {
+                0
+            } else {
+                cargo_semver_checks::CheckOutcome::Error.code()
+            });
+        }
+        Some(SemverChecksCommands::Audit(audit)) => {
+            let audit: cargo_semver_checks::Audit = audit.into();
+            let report = exit_on_error(config.is_error(), || audit.run(&mut config));
+            exit_on_error(config.is_error(), || {
+                print_audit_table(&mut config, &report)
+            });
+            std::process::exit(report.exit_code().code());
+        }
+        Some(SemverChecksCommands::Batch(batch)) => {
+            let batch = exit_on_error(true, || {
+                cargo_semver_checks::Batch::from_path(&batch.batch_file)
+            });
+            let report = exit_on_error(config.is_error(), || batch.run(&mut config));
+            exit_on_error(config.is_error(), || {
+                print_batch_summary(&mut config, &report)
+            });
+            std::process::exit(report.exit_code().code());
+        }
+        Some(SemverChecksCommands::ValidateLints(validate_lints)) => {
+            let success = exit_on_error(true, || validate_lints.run(&mut config));
+            std::process::exit(if success { 0 } else { 1 });
+        }
         None => args.check_release,
     };
 
-    let check: cargo_semver_checks::Check = check_release.into();
+    let detailed_exit_codes = check_release.unstable_options.detailed_exit_codes;
+    let json_progress = check_release.unstable_options.message_format == MessageFormat::Json;
+    let mut check: cargo_semver_checks::Check = check_release.into();
+    if json_progress {
+        // Keep stdout for the JSON lines, so it can be parsed.
+        let use_color = config.err_color_choice();
+        config
+            .set_stdout(Box::new(std::io::stderr()))
+            .set_out_color_choice(use_color);
+        check.set_progress_callbacks(Box::new(JsonProgress));
+    }
 
     let report = exit_on_error(config.is_error(), || check.check_release(&mut config));
-    if report.success() {
+    if detailed_exit_codes {
+        std::process::exit(report.exit_code().code());
+    } else if report.success() {
         std::process::exit(0);
     } else {
         std::process::exit(1);
@@ -173,7 +255,7 @@ fn exit_on_error<T>(log_errors: bool, mut inner: impl FnMut() -> anyhow::Result<
             if log_errors {
                 eprintln!("error: {err:?}");
             }
-            std::process::exit(1)
+            std::process::exit(cargo_semver_checks::CheckOutcome::Error.code())
         }
     }
 }
@@ -339,6 +421,96 @@ struct UnstableOptions {
     /// Enable printing witness hints, examples of potentially-broken downstream code.
     #[arg(long, hide = true)]
     witness_hints: bool,
+
+    /// Show the source code of the affected items for each lint result, like rustc does.
+    #[arg(long, hide = true)]
+    source_snippets: bool,
+
+    /// Suggest where items reported as missing may have been renamed or moved to,
+    /// along with a `pub use` that would restore compatibility.
+    #[arg(long, hide = true)]
+    rename_hints: bool,
+
+    /// Suggest code that would restore compatibility for lint results where
+    /// the fix is mechanical, like re-exporting a moved item.
+    #[arg(long, hide = true)]
+    suggest_shims: bool,
+
+    /// Use the git tag of the most recent release at or below the current version
+    /// as the baseline. Tags are matched against the `baseline-tag-patterns` in
+    /// `[workspace.metadata.cargo-semver-checks]`, by default `{crate}-v{version}` and `v{version}`.
+    #[arg(long, hide = true, group = "baseline")]
+    baseline_from_tags: bool,
+
+    /// Use the commit where the current `HEAD` forked from the given branch
+    /// as the baseline, i.e. the merge base of `HEAD` and the branch.
+    #[arg(long, hide = true, value_name = "BRANCH", group = "baseline")]
+    baseline_merge_base: Option<String>,
+
+    /// Look up baseline versions in the registry with this name in cargo's configuration,
+    /// instead of crates.io. `file://` index URLs and local registries are supported.
+    #[arg(long, hide = true, value_name = "NAME")]
+    registry: Option<String>,
+
+    /// Use a `.crate` file as the baseline, such as one downloaded from a registry.
+    /// A directory of unpacked crate sources, like `cargo vendor` output, also works.
+    #[arg(long, hide = true, value_name = "PATH", group = "baseline")]
+    baseline_crate_file: Option<PathBuf>,
+
+    /// Check against the latest release of each semver-compatible release line
+    /// in the registry, e.g. both `2.8.1` and `3.1.0`, reporting on each separately.
+    #[arg(long, hide = true, group = "baseline")]
+    baseline_each_major: bool,
+
+    /// Build the crate with the dependency versions in its `Cargo.lock`, like `cargo build --locked`.
+    /// Baselines from git revisions and crate files use their own lockfile.
+    #[arg(long, hide = true)]
+    locked: bool,
+
+    /// Run cargo without accessing the network.
+    #[arg(long, hide = true)]
+    offline: bool,
+
+    /// Generate the current rustdoc with this rustup toolchain, as with `cargo +<TOOLCHAIN>`.
+    #[arg(long, hide = true, value_name = "TOOLCHAIN")]
+    current_toolchain: Option<String>,
+
+    /// Generate the baseline rustdoc with this rustup toolchain, as with `cargo +<TOOLCHAIN>`.
+    /// Both toolchains must emit the same rustdoc JSON format version.
+    #[arg(long, hide = true, value_name = "TOOLCHAIN")]
+    baseline_toolchain: Option<String>,
+
+    /// Also report the workspace crates that need a major version bump because they expose
+    /// types of a workspace crate with breaking changes in their own public API.
+    #[arg(long, hide = true)]
+    workspace_impact: bool,
+
+    /// Report which lint results affect the crates at this path that depend on the checked
+    /// crates, based on the paths through which their source uses them. Can be repeated.
+    #[arg(long, hide = true, value_name = "PATH")]
+    downstream: Vec<PathBuf>,
+
+    /// Also build the `--downstream` crates against the current version,
+    /// using a `[patch]` that points to its source.
+    #[arg(long, hide = true, requires = "downstream")]
+    downstream_build: bool,
+
+    /// Exit with a code that tells the outcome apart: 0 if no semver update is required,
+    /// 1 if the check failed to run, 3 if only warnings were produced,
+    /// 4 if a minor version is required, and 5 if a major version is required.
+    #[arg(long, hide = true)]
+    detailed_exit_codes: bool,
+
+    /// Also print progress events, such as each lint starting and finishing, as JSON lines
+    /// on stdout. The human-readable output then goes to stderr.
+    #[arg(
+        long,
+        hide = true,
+        value_enum,
+        value_name = "FMT",
+        default_value = "human"
+    )]
+    message_format: MessageFormat,
 }
 
 impl UnstableOptions {
@@ -360,21 +532,320 @@ impl UnstableOptions {
 
         // If this has a compilation error from adding or removing fields, see this function's
         // docstring for how to fix this function's implementation.
-        let Self { witness_hints } = self;
+        let Self {
+            witness_hints,
+            source_snippets,
+            rename_hints,
+            suggest_shims,
+            baseline_from_tags,
+            baseline_merge_base,
+            registry,
+            baseline_crate_file,
+            baseline_each_major,
+            locked,
+            offline,
+            current_toolchain,
+            baseline_toolchain,
+            workspace_impact,
+            downstream,
+            downstream_build,
+            detailed_exit_codes,
+            message_format,
+        } = self;
 
         if *witness_hints {
             list.push("--witness-hints".into());
         }
 
+        if *source_snippets {
+            list.push("--source-snippets".into());
+        }
+
+        if *rename_hints {
+            list.push("--rename-hints".into());
+        }
+
+        if *suggest_shims {
+            list.push("--suggest-shims".into());
+        }
+
+        if *baseline_from_tags {
+            list.push("--baseline-from-tags".into());
+        }
+
+        if baseline_merge_base.is_some() {
+            list.push("--baseline-merge-base".into());
+        }
+
+        if registry.is_some() {
+            list.push("--registry".into());
+        }
+
+        if baseline_crate_file.is_some() {
+            list.push("--baseline-crate-file".into());
+        }
+
+        if *baseline_each_major {
+            list.push("--baseline-each-major".into());
+        }
+
+        if *locked {
+            list.push("--locked".into());
+        }
+
+        if *offline {
+            list.push("--offline".into());
+        }
+
+        if current_toolchain.is_some() {
+            list.push("--current-toolchain".into());
+        }
+
+        if baseline_toolchain.is_some() {
+            list.push("--baseline-toolchain".into());
+        }
+
+        if *workspace_impact {
+            list.push("--workspace-impact".into());
+        }
+
+        if !downstream.is_empty() {
+            list.push("--downstream".into());
+        }
+
+        if *downstream_build {
+            list.push("--downstream-build".into());
+        }
+
+        if *detailed_exit_codes {
+            list.push("--detailed-exit-codes".into());
+        }
+
+        if *message_format != MessageFormat::Human {
+            list.push("--message-format".into());
+        }
+
         list
     }
 }
 
+/// How to print the progress of a check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+enum MessageFormat {
+    /// Human-readable output only.
+    #[default]
+    Human,
+    /// Progress events as JSON lines, in addition to the human-readable output.
+    Json,
+}
+
+/// Prints each progress event as a line of JSON on stdout.
+struct JsonProgress;
+
+impl cargo_semver_checks::ProgressCallbacks for JsonProgress {
+    fn event(&mut self, event: &cargo_semver_checks::ProgressEvent) {
+        // Ignore printing failures, like for the human-readable progress.
+        if let Ok(line) = serde_json::to_string(event) {
+            let _ = writeln!(std::io::stdout().lock(), "{line}");
+        }
+    }
+}
+
 /// Check your crate for semver violations.
 #[derive(Debug, Subcommand)]
+// The commands are parsed only once, so their size doesn't matter.
+#[allow(clippy::large_enum_variant)]
 enum SemverChecksCommands {
     #[command(alias = "diff-files")]
     CheckRelease(CheckRelease),
+
+    /// Test a lint definition against a baseline and current version of a crate.
+    /// Requires `-Z unstable-options`.
+    #[command(hide = true)]
+    LintTest(LintTestArgs),
+
+    /// Check lint definition files for problems, without running them.
+    /// Requires `-Z unstable-options`.
+    #[command(hide = true)]
+    ValidateLints(ValidateLints),
+
+    /// Find the commit that introduced each semver violation since a baseline git revision.
+    /// Commits that can't be checked are skipped, and make the command fail.
+    /// Requires `-Z unstable-options`.
+    #[command(hide = true)]
+    Bisect(BisectArgs),
+
+    /// Check every consecutive pair of a crate's published versions for semver violations.
+    /// Requires `-Z unstable-options`.
+    #[command(hide = true)]
+    Audit(AuditArgs),
+
+    /// Check many crates in one run, as listed in a TOML or JSON batch file.
+    /// Exits with the most severe of the crates' outcomes, using the same codes
+    /// as `--detailed-exit-codes`. Requires `-Z unstable-options`.
+    #[command(hide = true)]
+    Batch(BatchArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+struct LintTestArgs {
+    /// The lint definition file to test.
+    #[arg(value_name = "LINT_PATH")]
+    lint: PathBuf,
+
+    /// Directory containing the baseline version of the test crate.
+    #[arg(long, value_name = "MANIFEST_ROOT")]
+    baseline_root: PathBuf,
+
+    /// Directory containing the current version of the test crate.
+    #[arg(long, value_name = "MANIFEST_ROOT")]
+    current_root: PathBuf,
+
+    /// File with the expected lint output.
+    /// It is RON-encoded, unless it has a `.json` extension.
+    #[arg(long, value_name = "PATH")]
+    expected: PathBuf,
+
+    /// Overwrite the expectation file with the actual lint output.
+    #[arg(long)]
+    bless: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+struct BisectArgs {
+    /// Git revision without the semver violations, e.g. the tag of the previous release.
+    #[arg(long, value_name = "REV")]
+    baseline_rev: String,
+
+    /// Git revision with the semver violations to investigate.
+    #[arg(long, value_name = "REV", default_value = "HEAD")]
+    current_rev: String,
+
+    /// Directory containing the crate's source.
+    #[arg(long, value_name = "MANIFEST_ROOT", default_value = ".")]
+    project_root: PathBuf,
+
+    /// Package to bisect, if not the one at the project root.
+    #[arg(long, short, value_name = "SPEC")]
+    package: Option<String>,
+}
+
+impl From<BisectArgs> for cargo_semver_checks::Bisect {
+    fn from(value: BisectArgs) -> Self {
+        let mut bisect = Self::new(value.project_root, value.baseline_rev);
+        bisect.set_current_rev(value.current_rev);
+        if let Some(package) = value.package {
+            bisect.set_package(package);
+        }
+        bisect
+    }
+}
+
+#[derive(Debug, Args, Clone)]
+struct AuditArgs {
+    /// The crate to audit.
+    #[arg(value_name = "CRATE")]
+    crate_name: String,
+
+    /// Look up the crate in the registry with this name in cargo's configuration,
+    /// instead of crates.io.
+    #[arg(long, value_name = "NAME")]
+    registry: Option<String>,
+
+    /// Also audit yanked versions.
+    #[arg(long)]
+    include_yanked: bool,
+
+    /// Also audit pre-release versions.
+    #[arg(long)]
+    include_prereleases: bool,
+
+    /// Only audit versions at or after this one.
+    #[arg(long, value_name = "X.Y.Z")]
+    since: Option<semver::Version>,
+}
+
+impl From<AuditArgs> for cargo_semver_checks::Audit {
+    fn from(value: AuditArgs) -> Self {
+        let mut audit = Self::new(value.crate_name);
+        if let Some(registry) = value.registry {
+            audit.set_registry(registry);
+        }
+        audit.set_include_yanked(value.include_yanked);
+        audit.set_include_prereleases(value.include_prereleases);
+        if let Some(since) = value.since {
+            audit.set_since(since);
+        }
+        audit
+    }
+}
+
+#[derive(Debug, Args, Clone)]
+struct BatchArgs {
+    /// The batch file listing the crates to check.
+    #[arg(value_name = "PATH")]
+    batch_file: PathBuf,
+}
+
+#[derive(Debug, Args, Clone)]
+struct ValidateLints {
+    /// The lint definition files to check.
+    /// If none are given, checks the built-in lints.
+    #[arg(value_name = "LINT_PATH")]
+    lints: Vec<PathBuf>,
+}
+
+impl ValidateLints {
+    /// Returns `true` if all lints are valid.
+    fn run(&self, config: &mut GlobalConfig) -> anyhow::Result<bool> {
+        let queries = if self.lints.is_empty() {
+            SemverQuery::all_queries().into_values().collect()
+        } else {
+            let mut queries = Vec::with_capacity(self.lints.len());
+            for path in &self.lints {
+                let text = fs_err::read_to_string(path)?;
+                let query = SemverQuery::from_ron_str(&text)
+                    .with_context(|| format!("failed to parse lint file {}", path.display()))?;
+                queries.push(query);
+            }
+            queries
+        };
+
+        let mut num_invalid = 0;
+        for query in &queries {
+            let errors = cargo_semver_checks::validate_lint(query);
+            if !errors.is_empty() {
+                num_invalid += 1;
+            }
+            for error in errors {
+                config.shell_error(error)?;
+            }
+        }
+
+        if num_invalid == 0 {
+            config.shell_status("Validated", format_args!("{} lints", queries.len()))?;
+            Ok(true)
+        } else {
+            config.shell_status(
+                "Validated",
+                format_args!("{} lints, {num_invalid} invalid", queries.len()),
+            )?;
+            Ok(false)
+        }
+    }
+}
+
+impl From<LintTestArgs> for cargo_semver_checks::LintTest {
+    fn from(value: LintTestArgs) -> Self {
+        let mut lint_test = Self::new(
+            value.lint,
+            value.baseline_root,
+            value.current_root,
+            value.expected,
+        );
+        lint_test.set_bless(value.bless);
+        lint_test
+    }
 }
 
 #[derive(Debug, Args, Clone)]
@@ -411,7 +882,7 @@ struct CheckRelease {
         help_heading = "Baseline",
         group = "baseline"
     )]
-    baseline_version: Option<String>,
+    baseline_version: Vec<String>,
 
     /// Git revision to lookup for a baseline
     #[arg(
@@ -536,6 +1007,21 @@ struct CheckRelease {
     unstable_options: UnstableOptions,
 }
 
+impl CheckRelease {
+    /// Like [`UnstableOptions::non_default()`], but also covering the unstable uses
+    /// of stable options, such as passing `--baseline-version` more than once.
+    #[must_use]
+    fn non_default_unstable(&self) -> Vec<String> {
+        let mut list = self.unstable_options.non_default();
+
+        if self.baseline_version.len() > 1 {
+            list.push("--baseline-version (more than once)".into());
+        }
+
+        list
+    }
+}
+
 impl From<CheckRelease> for cargo_semver_checks::Check {
     fn from(value: CheckRelease) -> Self {
         let (current, current_project_root) = if let Some(current_rustdoc) = value.current_rustdoc {
@@ -571,8 +1057,10 @@ impl From<CheckRelease> for cargo_semver_checks::Check {
             selection.set_excluded_packages(value.workspace.exclude);
             check.set_package_selection(selection);
         }
+        // Further `--baseline-version`s are checked as additional baselines.
+        let mut baseline_versions = value.baseline_version.into_iter();
         let custom_baseline = {
-            if let Some(baseline_version) = value.baseline_version {
+            if let Some(baseline_version) = baseline_versions.next() {
                 Some(Rustdoc::from_registry(baseline_version))
             } else if let Some(baseline_rev) = value.baseline_rev {
                 let root = if let Some(baseline_root) = value.baseline_root {
@@ -583,6 +1071,20 @@ impl From<CheckRelease> for cargo_semver_checks::Check {
                     std::env::current_dir().expect("can't determine current directory")
                 };
                 Some(Rustdoc::from_git_revision(root, baseline_rev))
+            } else if let Some(branch) = value.unstable_options.baseline_merge_base {
+                let root = current_project_root.unwrap_or_else(|| {
+                    std::env::current_dir().expect("can't determine current directory")
+                });
+                Some(Rustdoc::from_git_merge_base(root, branch))
+            } else if value.unstable_options.baseline_from_tags {
+                let root = current_project_root.unwrap_or_else(|| {
+                    std::env::current_dir().expect("can't determine current directory")
+                });
+                Some(Rustdoc::from_git_tags(root))
+            } else if value.unstable_options.baseline_each_major {
+                Some(Rustdoc::from_registry_latest_in_each_major())
+            } else if let Some(crate_file) = value.unstable_options.baseline_crate_file {
+                Some(Rustdoc::from_crate_file(crate_file))
             } else if let Some(baseline_rustdoc) = value.baseline_rustdoc {
                 Some(Rustdoc::from_path(baseline_rustdoc))
             } else {
@@ -594,6 +1096,9 @@ impl From<CheckRelease> for cargo_semver_checks::Check {
         if let Some(baseline) = custom_baseline {
             check.set_baseline(baseline);
         }
+        for baseline_version in baseline_versions {
+            check.add_baseline(Rustdoc::from_registry(baseline_version));
+        }
 
         if let Some(release_type) = value.release_type {
             check.set_release_type(release_type);
@@ -631,10 +1136,138 @@ impl From<CheckRelease> for cargo_semver_checks::Check {
         witness_generation.show_hints = value.unstable_options.witness_hints;
         check.set_witness_generation(witness_generation);
 
+        check.set_source_snippets(value.unstable_options.source_snippets);
+        check.set_rename_hints(value.unstable_options.rename_hints);
+        check.set_shim_suggestions(value.unstable_options.suggest_shims);
+        if let Some(registry) = value.unstable_options.registry {
+            check.set_registry(registry);
+        }
+        check.set_locked(value.unstable_options.locked);
+        check.set_offline(value.unstable_options.offline);
+        if let Some(toolchain) = value.unstable_options.current_toolchain {
+            check.set_current_toolchain(toolchain);
+        }
+        if let Some(toolchain) = value.unstable_options.baseline_toolchain {
+            check.set_baseline_toolchain(toolchain);
+        }
+        check.set_workspace_impact(value.unstable_options.workspace_impact);
+        for downstream in value.unstable_options.downstream {
+            check.add_downstream(downstream);
+        }
+        check.set_downstream_build(value.unstable_options.downstream_build);
+
         check
     }
 }
 
+/// Prints whether each crate in the batch passed, failed and with which required bump,
+/// or couldn't be checked.
+fn print_batch_summary(
+    config: &mut GlobalConfig,
+    report: &cargo_semver_checks::BatchReport,
+) -> anyhow::Result<()> {
+    let (mut passed, mut failed, mut errored) = (0, 0, 0);
+    let mut rows = vec![];
+    for (name, outcome) in report.outcomes() {
+        let status = match outcome {
+            Ok(report) if report.success() => {
+                passed += 1;
+                "passed".to_string()
+            }
+            Ok(report) => {
+                failed += 1;
+                let required_bump = match report.exit_code() {
+                    cargo_semver_checks::CheckOutcome::MajorRequired => "major",
+                    _ => "minor",
+                };
+                format!("failed, requires new {required_bump} version")
+            }
+            Err(_) => {
+                errored += 1;
+                "error".to_string()
+            }
+        };
+        rows.push((name, status));
+    }
+
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, status) in rows {
+        writeln!(config.stdout(), "{name:<width$} {status}")?;
+    }
+    config.shell_status(
+        "Summary",
+        format_args!("{passed} passed, {failed} failed, {errored} errors"),
+    )
+}
+
+/// Prints a table row for each audited version pair.
+fn print_audit_table(
+    config: &mut GlobalConfig,
+    report: &cargo_semver_checks::AuditReport,
+) -> anyhow::Result<()> {
+    let mut rows = vec![[
+        "versions".to_string(),
+        "declared".into(),
+        "required".into(),
+        "status".into(),
+        "lints".into(),
+    ]];
+    for pair in report.pairs() {
+        let declared = match pair.declared_bump() {
+            ActualSemverUpdate::Major => "major",
+            ActualSemverUpdate::Minor => "minor",
+            ActualSemverUpdate::Patch => "patch",
+            ActualSemverUpdate::NotChanged => "none",
+        };
+        let (required, status, lints) = match pair.error() {
+            Some(error) => ("-".to_string(), "error", error.to_string()),
+            None => (
+                pair.required_bump()
+                    .to_possible_value()
+                    .expect("release types are all named")
+                    .get_name()
+                    .to_string(),
+                if pair.respects_semver() {
+                    "ok"
+                } else {
+                    "violation"
+                },
+                pair.lints().join(", "),
+            ),
+        };
+        rows.push([
+            format!("{} -> {}", pair.baseline_version(), pair.current_version()),
+            declared.into(),
+            required,
+            status.into(),
+            lints,
+        ]);
+    }
+
+    let mut widths = [0; 4];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    for row in rows {
+        let line = format!(
+            "{:<w0$} {:<w1$} {:<w2$} {:<w3$} {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+        );
+        writeln!(config.stdout(), "{}", line.trim_end())?;
+    }
+    Ok(())
+}
+
 /// Helper function to encapsulate the logic of validating that unstable options
 /// were not used without `-Z unstable-options` and issuing deprecation warnings
 /// for any stable feature flags that were explicitly specified.
@@ -658,13 +1291,16 @@ fn validate_feature_flags(config: &mut GlobalConfig, args: &SemverChecks) -> any
     }
 
     if !config.feature_flag_enabled(FeatureFlag::UNSTABLE_OPTIONS) {
-        let unstable_options = match &args.command {
-            Some(SemverChecksCommands::CheckRelease(cr)) => &cr.unstable_options,
-            None => &args.check_release.unstable_options,
+        let non_default_options = match &args.command {
+            Some(SemverChecksCommands::CheckRelease(cr)) => cr.non_default_unstable(),
+            Some(SemverChecksCommands::LintTest(_)) => vec!["lint-test".into()],
+            Some(SemverChecksCommands::ValidateLints(_)) => vec!["validate-lints".into()],
+            Some(SemverChecksCommands::Bisect(_)) => vec!["bisect".into()],
+            Some(SemverChecksCommands::Audit(_)) => vec!["audit".into()],
+            Some(SemverChecksCommands::Batch(_)) => vec!["batch".into()],
+            None => args.check_release.non_default_unstable(),
         };
 
-        let non_default_options = unstable_options.non_default();
-
         if !non_default_options.is_empty() {
             let mut message = String::from(
                 "the following options are not supported without `-Z unstable-options`:\n",
@@ -1,9 +1,13 @@
 use std::collections::BTreeMap;
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use serde::Deserialize;
 
-use crate::{LintLevel, OverrideMap, QueryOverride, RequiredSemverUpdate};
+use crate::rustdoc_gen::{FeatureConfig, FeaturesGroup};
+use crate::{
+    LintLevel, OverrideMap, QueryOverride, ReleaseType, RequiredSemverUpdate, VersionPolicy,
+    VersionPolicyPreset,
+};
 
 #[derive(Debug, Clone)]
 pub(crate) struct Manifest {
@@ -79,6 +83,110 @@ pub(crate) struct MetadataTable {
 pub(crate) struct SemverChecksTable {
     /// Holds the `lints` table, if it is declared.
     pub(crate) lints: Option<LintTable>,
+    /// Patterns matching the git tags of released versions, like `"{crate}-v{version}"`,
+    /// used to find the baseline when checking against git tags.
+    #[serde(default, rename = "baseline-tag-patterns")]
+    pub(crate) baseline_tag_patterns: Option<Vec<String>>,
+    /// How version changes map to the kind of release they declare.
+    #[serde(default, rename = "version-policy")]
+    pub(crate) version_policy: Option<VersionPolicyConfig>,
+    /// Features renamed since the baseline, from their baseline name to their current one.
+    /// Renamed features enabled by name on one side are enabled on the other side too,
+    /// but ones enabled only by `all-features`, `default-features` or the heuristic aren't.
+    #[serde(default, rename = "renamed-features")]
+    pub(crate) renamed_features: Option<BTreeMap<String, String>>,
+    /// Features to build both the current and baseline versions with.
+    #[serde(flatten)]
+    pub(crate) features: FeatureTable,
+    /// Features to build only the current version with, on top of the ones above.
+    #[serde(default)]
+    pub(crate) current: Option<FeatureTable>,
+    /// Features to build only the baseline version with, on top of the ones above.
+    #[serde(default)]
+    pub(crate) baseline: Option<FeatureTable>,
+}
+
+/// Which features to enable when generating rustdoc, like the matching CLI flags.
+/// When neither `all-features` nor `default-features` is set,
+/// features are chosen heuristically.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct FeatureTable {
+    features: Option<Vec<String>>,
+    default_features: Option<bool>,
+    all_features: Option<bool>,
+    /// Features never to enable, e.g. ones that only build on nightly.
+    exclude_features: Option<Vec<String>>,
+}
+
+impl FeatureTable {
+    fn features_group(&self) -> anyhow::Result<Option<FeaturesGroup>> {
+        Ok(match (self.all_features, self.default_features) {
+            (Some(true), Some(_)) => {
+                bail!("`all-features` and `default-features` can't be set together")
+            }
+            (Some(true), None) => Some(FeaturesGroup::All),
+            (_, Some(true)) => Some(FeaturesGroup::Default),
+            (_, Some(false)) => Some(FeaturesGroup::None),
+            (_, None) => None,
+        })
+    }
+
+    fn apply_to(&self, feature_config: &mut FeatureConfig) -> anyhow::Result<()> {
+        feature_config.layer(
+            self.features_group()?,
+            self.features.as_deref().unwrap_or_default(),
+            self.exclude_features.as_deref().unwrap_or_default(),
+        );
+        Ok(())
+    }
+}
+
+/// Different valid representations of a [`VersionPolicy`] in the Cargo.toml configuration table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum VersionPolicyConfig {
+    /// Just a preset by name, e.g. `version-policy = "strict-zero"`
+    Preset(VersionPolicyPreset),
+    /// A preset with overrides, e.g.
+    /// `version-policy = { preset = "cargo", zero-patch = "patch" }`
+    Custom(CustomVersionPolicy),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct CustomVersionPolicy {
+    /// The preset to start from. Defaults to `cargo`.
+    preset: Option<VersionPolicyPreset>,
+    zero_minor: Option<ReleaseType>,
+    zero_patch: Option<ReleaseType>,
+    zero_zero_patch: Option<ReleaseType>,
+    prerelease: Option<ReleaseType>,
+}
+
+impl VersionPolicyConfig {
+    pub(crate) fn into_policy(self) -> VersionPolicy {
+        match self {
+            Self::Preset(preset) => VersionPolicy::preset(preset),
+            Self::Custom(custom) => {
+                let mut policy =
+                    VersionPolicy::preset(custom.preset.unwrap_or(VersionPolicyPreset::Cargo));
+                if let Some(release_type) = custom.zero_minor {
+                    policy.set_zero_minor(release_type);
+                }
+                if let Some(release_type) = custom.zero_patch {
+                    policy.set_zero_patch(release_type);
+                }
+                if let Some(release_type) = custom.zero_zero_patch {
+                    policy.set_zero_zero_patch(release_type);
+                }
+                if let Some(release_type) = custom.prerelease {
+                    policy.set_prerelease(release_type);
+                }
+                policy
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -232,6 +340,73 @@ pub(crate) fn deserialize_lint_table(
     Ok(table.and_then(|table| table.config.and_then(|config| config.lints)))
 }
 
+/// Helper function to deserialize the optional `baseline-tag-patterns` list from a
+/// [`serde_json::Value`] holding a `[workspace.metadata]` table.
+///
+/// Returns an `Err` if the `cargo-semver-checks` table is present
+/// but invalid.  Returns `Ok(None)` if the list is not present.
+pub(crate) fn deserialize_baseline_tag_patterns(
+    metadata: &serde_json::Value,
+) -> anyhow::Result<Option<Vec<String>>> {
+    let table = Option::<MetadataTable>::deserialize(metadata)?;
+    Ok(table.and_then(|table| table.config.and_then(|config| config.baseline_tag_patterns)))
+}
+
+/// Helper function to deserialize the optional `version-policy` entry from a
+/// [`serde_json::Value`] holding a `[package/workspace.metadata]` table.
+///
+/// Returns an `Err` if the `cargo-semver-checks` table is present
+/// but invalid.  Returns `Ok(None)` if the entry is not present.
+pub(crate) fn deserialize_version_policy(
+    metadata: &serde_json::Value,
+) -> anyhow::Result<Option<VersionPolicy>> {
+    let table = Option::<MetadataTable>::deserialize(metadata)?;
+    Ok(table
+        .and_then(|table| table.config.and_then(|config| config.version_policy))
+        .map(VersionPolicyConfig::into_policy))
+}
+
+/// Helper function to deserialize the optional `renamed-features` table from a
+/// [`serde_json::Value`] holding a `[package/workspace.metadata]` table.
+///
+/// Returns an `Err` if the `cargo-semver-checks` table is present
+/// but invalid.  Returns `Ok(None)` if the table is not present.
+pub(crate) fn deserialize_renamed_features(
+    metadata: &serde_json::Value,
+) -> anyhow::Result<Option<BTreeMap<String, String>>> {
+    let table = Option::<MetadataTable>::deserialize(metadata)?;
+    Ok(table.and_then(|table| table.config.and_then(|config| config.renamed_features)))
+}
+
+/// Helper function to apply the feature settings from a [`serde_json::Value`]
+/// holding a `[package.metadata]` table on top of the current and baseline feature configs.
+///
+/// Returns an `Err` if the `cargo-semver-checks` table is present
+/// but invalid.
+pub(crate) fn apply_feature_tables(
+    metadata: &serde_json::Value,
+    current: &mut FeatureConfig,
+    baseline: &mut FeatureConfig,
+) -> anyhow::Result<()> {
+    let table = Option::<MetadataTable>::deserialize(metadata)?;
+    let Some(config) = table.and_then(|table| table.config) else {
+        return Ok(());
+    };
+    config.features.apply_to(current)?;
+    config.features.apply_to(baseline)?;
+    if let Some(table) = &config.current {
+        table
+            .apply_to(current)
+            .context("invalid `current` feature settings")?;
+    }
+    if let Some(table) = &config.baseline {
+        table
+            .apply_to(baseline)
+            .context("invalid `baseline` feature settings")?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -358,6 +533,86 @@ mod tests {
         .expect_err("`workspace = false` should not be accepted");
     }
 
+    #[test]
+    fn version_policy_from_preset_or_table() {
+        use super::deserialize_version_policy;
+        use crate::{ActualSemverUpdate, VersionPolicy, VersionPolicyPreset};
+
+        let policy = |metadata| {
+            deserialize_version_policy(&metadata)
+                .expect("valid metadata")
+                .expect("version policy is set")
+        };
+        assert_eq!(
+            policy(serde_json::json!({
+                "cargo-semver-checks": { "version-policy": "strict-zero" }
+            })),
+            VersionPolicy::preset(VersionPolicyPreset::StrictZero)
+        );
+
+        let custom = policy(serde_json::json!({
+            "cargo-semver-checks": {
+                "version-policy": { "preset": "prerelease-trains", "zero-patch": "patch" }
+            }
+        }));
+        let version = |version| semver::Version::parse(version).unwrap();
+        assert_eq!(
+            custom.classify(&version("0.3.1"), &version("0.3.2")),
+            ActualSemverUpdate::Patch
+        );
+        assert_eq!(
+            custom.classify(&version("1.0.0"), &version("1.0.1-rc.1")),
+            ActualSemverUpdate::Major
+        );
+
+        deserialize_version_policy(&serde_json::json!({
+            "cargo-semver-checks": { "version-policy": { "zero-pach": "patch" } }
+        }))
+        .expect_err("unknown keys are invalid");
+        assert!(deserialize_version_policy(&serde_json::json!({}))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn feature_tables_layer_per_side() {
+        use super::apply_feature_tables;
+        use crate::rustdoc_gen::{FeatureConfig, FeaturesGroup};
+
+        let mut current = FeatureConfig::default_for_current();
+        let mut baseline = FeatureConfig::default_for_baseline();
+        apply_feature_tables(
+            &serde_json::json!({
+                "cargo-semver-checks": {
+                    "default-features": true,
+                    "features": ["serde", "std"],
+                    "exclude-features": ["nightly"],
+                    "current": { "features": ["nightly", "rayon"] },
+                    "baseline": { "all-features": true, "exclude-features": ["std"] },
+                }
+            }),
+            &mut current,
+            &mut baseline,
+        )
+        .expect("valid metadata");
+
+        assert_eq!(current.features_group, FeaturesGroup::Default);
+        assert_eq!(current.extra_features, ["serde", "std", "nightly", "rayon"]);
+        assert!(current.excluded_features.is_empty());
+        assert_eq!(baseline.features_group, FeaturesGroup::All);
+        assert_eq!(baseline.extra_features, ["serde"]);
+        assert_eq!(baseline.excluded_features, ["nightly", "std"]);
+
+        apply_feature_tables(
+            &serde_json::json!({
+                "cargo-semver-checks": { "all-features": true, "default-features": false }
+            }),
+            &mut current,
+            &mut baseline,
+        )
+        .expect_err("conflicting feature groups are invalid");
+    }
+
     #[test]
     fn workspace_key_omitted_is_false() {
         let table = serde_json::from_value::<LintTable>(serde_json::json! {{
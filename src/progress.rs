@@ -0,0 +1,169 @@
+//! Structured progress events, for tools that show the progress of a check as it runs,
+//! such as IDE and CI integrations.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Serialize, Serializer};
+
+/// Receives progress events while crates are checked.
+///
+/// Set with [`Check::set_progress_callbacks()`](crate::Check::set_progress_callbacks),
+/// or for every check run with a config with
+/// [`GlobalConfig::set_progress_callbacks()`](crate::GlobalConfig::set_progress_callbacks).
+/// Lints run in parallel, so their events may arrive from several threads.
+pub trait ProgressCallbacks: Send {
+    /// Called for each event as it happens.
+    fn event(&mut self, event: &ProgressEvent);
+}
+
+/// An event in the progress of a check.
+///
+/// Events about a crate's rustdoc say whether they're about the baseline or the current version.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    /// Started generating the placeholder project the crate's rustdoc is generated in.
+    GeneratePlaceholderProjectStart {
+        crate_name: String,
+        version: String,
+        is_baseline: bool,
+    },
+    /// Finished generating the placeholder project.
+    GeneratePlaceholderProjectSuccess {
+        crate_name: String,
+        version: String,
+        is_baseline: bool,
+    },
+    /// The crate's rustdoc was found in the cache, so it won't be generated.
+    RustdocCacheHit {
+        crate_name: String,
+        version: String,
+        is_baseline: bool,
+    },
+    /// Started generating the crate's rustdoc.
+    GenerateRustdocStart {
+        crate_name: String,
+        version: String,
+        is_baseline: bool,
+    },
+    /// Finished generating the crate's rustdoc.
+    GenerateRustdocSuccess {
+        crate_name: String,
+        version: String,
+        is_baseline: bool,
+        #[serde(rename = "duration_secs", serialize_with = "serialize_secs")]
+        duration: Duration,
+    },
+    /// Saved the generated rustdoc in the cache.
+    RustdocCachePopulated {
+        crate_name: String,
+        version: String,
+        is_baseline: bool,
+    },
+    /// Started parsing the crate's rustdoc.
+    ParseRustdocStart {
+        crate_name: String,
+        version: String,
+        is_baseline: bool,
+        cached: bool,
+    },
+    /// Finished parsing the crate's rustdoc.
+    ParseRustdocSuccess {
+        crate_name: String,
+        version: String,
+        is_baseline: bool,
+        cached: bool,
+        #[serde(rename = "duration_secs", serialize_with = "serialize_secs")]
+        duration: Duration,
+    },
+    /// Something went wrong, but the check continues.
+    NonFatalError {
+        crate_name: String,
+        version: String,
+        is_baseline: bool,
+        message: String,
+    },
+    /// Started running a lint against the crate.
+    QueryStart {
+        crate_name: String,
+        query_id: String,
+    },
+    /// Finished running a lint against the crate.
+    QueryFinish {
+        crate_name: String,
+        query_id: String,
+        #[serde(rename = "duration_secs", serialize_with = "serialize_secs")]
+        duration: Duration,
+        /// How many results the lint found. Zero means it passed.
+        results: usize,
+    },
+    /// Finished checking the crate against all of its baselines.
+    CrateFinish {
+        crate_name: String,
+        #[serde(rename = "duration_secs", serialize_with = "serialize_secs")]
+        duration: Duration,
+        /// Whether the crate respects semver against all of its baselines.
+        success: bool,
+    },
+}
+
+fn serialize_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+/// Shared access to the progress callbacks, so events can be sent from the threads lints run on.
+#[derive(Clone)]
+pub(crate) struct ProgressHandle(Arc<Mutex<Box<dyn ProgressCallbacks>>>);
+
+impl std::fmt::Debug for ProgressHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressHandle")
+    }
+}
+
+/// Handles are equal if they send events to the same callbacks.
+impl PartialEq for ProgressHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ProgressHandle {}
+
+impl ProgressHandle {
+    pub(crate) fn new(callbacks: Box<dyn ProgressCallbacks>) -> Self {
+        Self(Arc::new(Mutex::new(callbacks)))
+    }
+
+    pub(crate) fn emit(&self, event: ProgressEvent) {
+        // A panic in another thread's callback shouldn't hide later events.
+        let mut callbacks = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        callbacks.event(&event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::ProgressEvent;
+
+    #[test]
+    fn events_serialize_as_tagged_json() {
+        let event = ProgressEvent::QueryFinish {
+            crate_name: "my_crate".into(),
+            query_id: "function_missing".into(),
+            duration: Duration::from_millis(1500),
+            results: 2,
+        };
+        assert_eq!(
+            serde_json::to_string(&event).expect("serialization failed"),
+            r#"{"event":"query_finish","crate_name":"my_crate","query_id":"function_missing","duration_secs":1.5,"results":2}"#
+        );
+    }
+}
@@ -1,8 +1,10 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
 
 use ron::extensions::Extensions;
 use serde::{Deserialize, Serialize};
-use trustfall::TransparentValue;
+use trustfall::{FieldValue, TransparentValue};
+use trustfall_rustdoc::VersionedRustdocAdapter;
 
 use crate::ReleaseType;
 
@@ -128,6 +130,26 @@ pub struct SemverQuery {
     /// more information.
     #[serde(default)]
     pub witness: Option<Witness>,
+
+    /// Optional [`handlebars`] template that renders a suggested compatibility shim:
+    /// Rust code the crate's maintainers could add to undo the breakage, such as a `pub use`
+    /// re-exporting a moved item. It will be provided all `@output` data from the query.
+    ///
+    /// For lints about missing items, it is also provided `new_path`, the path of the item
+    /// the missing one was likely renamed or moved to, and `module`, the module the missing
+    /// item was in (or for methods, their type). For functions and methods, it is also
+    /// provided `wrapper_signature` and `forwarding_call`, for a wrapper under the old name
+    /// that forwards to the new item, or null if its parameters can't be forwarded.
+    /// The shim is omitted for results where no such item was found.
+    ///
+    /// For `trait_method_added`, it is also provided `method_signature`, the declaration of
+    /// the newly-required method, so a default body can be suggested.
+    ///
+    /// Lints about changed parameters, like `function_parameter_count_changed`, get no shim:
+    /// there's no mechanical way to map the old parameters onto the new ones. Neither do
+    /// removed trait methods, since implementations of the trait would still break.
+    #[serde(default)]
+    pub(crate) shim_template: Option<String>,
 }
 
 impl SemverQuery {
@@ -143,6 +165,115 @@ impl SemverQuery {
         Self::deserialize(&mut deserializer)
     }
 
+    /// Runs this query against the given adapter, collecting its distinct results.
+    ///
+    /// Queries that `@recurse` can reach the same vertex along several paths,
+    /// producing the same result more than once, so duplicates are removed.
+    /// Field values can't be hashed, so results are compared by their JSON form.
+    pub(crate) fn run(
+        &self,
+        adapter: &VersionedRustdocAdapter<'_>,
+    ) -> anyhow::Result<Vec<BTreeMap<Arc<str>, FieldValue>>> {
+        let mut seen = HashSet::new();
+        let mut results = vec![];
+        for result in adapter.run_query(&self.query, self.arguments.clone())? {
+            let key = serde_json::to_string(
+                &result
+                    .iter()
+                    .map(|(name, value)| (name, TransparentValue::from(value.clone())))
+                    .collect::<BTreeMap<_, _>>(),
+            )?;
+            if seen.insert(key) {
+                results.push(result);
+            }
+        }
+        Ok(results)
+    }
+
+    /// Runs this query against the given adapter, collecting its distinct results
+    /// like [`SemverQuery::run()`].
+    ///
+    /// Span data inside `@fold` blocks is reordered in increasing `begin_line` order,
+    /// since the underlying adapter is non-deterministic due to its iteration over hashtables.
+    /// Our heuristic for detecting spans inside `@fold` is to look for:
+    /// - list-typed outputs
+    /// - with names ending in `_begin_line`
+    /// - located inside *one* `@fold` level (i.e. their component is directly under the root).
+    pub(crate) fn run_with_ordered_folds(
+        &self,
+        adapter: &VersionedRustdocAdapter<'_>,
+    ) -> anyhow::Result<Vec<BTreeMap<String, FieldValue>>> {
+        let parsed_query = trustfall_core::frontend::parse(adapter.schema(), &self.query)
+            .map_err(|e| anyhow::anyhow!("query of lint `{}` is not valid: {e}", self.id))?;
+        let fold_keys_and_targets: BTreeMap<&str, Vec<Arc<str>>> = parsed_query
+            .outputs
+            .iter()
+            .filter_map(|(name, output)| {
+                if name.as_ref().ends_with("_begin_line") && output.value_type.is_list() {
+                    if let Some(fold) = parsed_query
+                        .ir_query
+                        .root_component
+                        .folds
+                        .values()
+                        .find(|fold| fold.component.root == parsed_query.vids[&output.vid].root)
+                    {
+                        let targets = parsed_query
+                            .outputs
+                            .values()
+                            .filter_map(|o| {
+                                fold.component
+                                    .vertices
+                                    .contains_key(&o.vid)
+                                    .then_some(Arc::clone(&o.name))
+                            })
+                            .collect();
+                        Some((name.as_ref(), targets))
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let results = self
+            .run(adapter)?
+            .into_iter()
+            .map(move |mut res| {
+                // Reorder `@fold`-ed span data in increasing `begin_line` order.
+                for (fold_key, targets) in &fold_keys_and_targets {
+                    let mut data: Vec<(u64, usize)> = res[*fold_key]
+                        .as_vec_with(FieldValue::as_u64)
+                        .expect("fold key was not a list of u64")
+                        .into_iter()
+                        .enumerate()
+                        .map(|(idx, val)| (val, idx))
+                        .collect();
+                    data.sort_unstable();
+                    for target in targets {
+                        res.entry(Arc::clone(target)).and_modify(|value| {
+                            // The output of a `@fold @transform(op: "count")` might not be a list here,
+                            // so ignore such outputs. They don't need reordering anyway.
+                            if let Some(slice) = value.as_slice() {
+                                let new_order = data
+                                    .iter()
+                                    .map(|(_, idx)| slice[*idx].clone())
+                                    .collect::<Vec<_>>()
+                                    .into();
+                                *value = new_order;
+                            }
+                        });
+                    }
+                }
+
+                // Turn the output keys into regular strings.
+                res.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+            })
+            .collect();
+        Ok(results)
+    }
+
     pub fn all_queries() -> BTreeMap<String, SemverQuery> {
         let mut queries = BTreeMap::default();
         for (id, query_text) in get_queries() {
@@ -215,11 +346,7 @@ impl OverrideStack {
     /// lint level if not overridden.
     #[must_use]
     pub fn effective_lint_level(&self, query: &SemverQuery) -> LintLevel {
-        self.0
-            .iter()
-            .rev()
-            .find_map(|x| x.get(&query.id).and_then(|y| y.lint_level))
-            .unwrap_or(query.lint_level)
+        self.effective_lint_level_of(&query.id, query.lint_level)
     }
 
     /// Calculates the *effective* required version bump of this query, by searching for an override
@@ -227,11 +354,31 @@ impl OverrideStack {
     /// required version bump if not overridden.
     #[must_use]
     pub fn effective_required_update(&self, query: &SemverQuery) -> RequiredSemverUpdate {
+        self.effective_required_update_of(&query.id, query.required_update)
+    }
+
+    /// Like [`OverrideStack::effective_lint_level`], for checks that aren't queries,
+    /// given their id and default lint level.
+    pub(crate) fn effective_lint_level_of(&self, id: &str, default: LintLevel) -> LintLevel {
+        self.0
+            .iter()
+            .rev()
+            .find_map(|x| x.get(id).and_then(|y| y.lint_level))
+            .unwrap_or(default)
+    }
+
+    /// Like [`OverrideStack::effective_required_update`], for checks that aren't queries,
+    /// given their id and default required version bump.
+    pub(crate) fn effective_required_update_of(
+        &self,
+        id: &str,
+        default: RequiredSemverUpdate,
+    ) -> RequiredSemverUpdate {
         self.0
             .iter()
             .rev()
-            .find_map(|x| x.get(&query.id).and_then(|y| y.required_update))
-            .unwrap_or(query.required_update)
+            .find_map(|x| x.get(id).and_then(|y| y.required_update))
+            .unwrap_or(default)
     }
 }
 
@@ -585,82 +732,9 @@ mod tests {
         let adapter = VersionedRustdocAdapter::new(indexed_crate_new, Some(indexed_crate_old))
             .expect("could not create adapter");
 
-        let results_iter = adapter
-            .run_query(&semver_query.query, semver_query.arguments.clone())
-            .unwrap();
-
-        // Ensure span data inside `@fold` blocks is deterministically ordered,
-        // since the underlying adapter is non-deterministic due to its iteration over hashtables.
-        // Our heuristic for detecting spans inside `@fold` is to look for:
-        // - list-typed outputs
-        // - with names ending in `_begin_line`
-        // - located inside *one* `@fold` level (i.e. their component is directly under the root).
-        let parsed_query = trustfall_core::frontend::parse(adapter.schema(), &semver_query.query)
-            .expect("not a valid query");
-        let fold_keys_and_targets: BTreeMap<&str, Vec<Arc<str>>> = parsed_query
-            .outputs
-            .iter()
-            .filter_map(|(name, output)| {
-                if name.as_ref().ends_with("_begin_line") && output.value_type.is_list() {
-                    if let Some(fold) = parsed_query
-                        .ir_query
-                        .root_component
-                        .folds
-                        .values()
-                        .find(|fold| fold.component.root == parsed_query.vids[&output.vid].root)
-                    {
-                        let targets = parsed_query
-                            .outputs
-                            .values()
-                            .filter_map(|o| {
-                                fold.component
-                                    .vertices
-                                    .contains_key(&o.vid)
-                                    .then_some(Arc::clone(&o.name))
-                            })
-                            .collect();
-                        Some((name.as_ref(), targets))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        let results = results_iter
-            .map(move |mut res| {
-                // Reorder `@fold`-ed span data in increasing `begin_line` order.
-                for (fold_key, targets) in &fold_keys_and_targets {
-                    let mut data: Vec<(u64, usize)> = res[*fold_key]
-                        .as_vec_with(FieldValue::as_u64)
-                        .expect("fold key was not a list of u64")
-                        .into_iter()
-                        .enumerate()
-                        .map(|(idx, val)| (val, idx))
-                        .collect();
-                    data.sort_unstable();
-                    for target in targets {
-                        res.entry(Arc::clone(target)).and_modify(|value| {
-                            // The output of a `@fold @transform(op: "count")` might not be a list here,
-                            // so ignore such outputs. They don't need reordering anyway.
-                            if let Some(slice) = value.as_slice() {
-                                let new_order = data
-                                    .iter()
-                                    .map(|(_, idx)| slice[*idx].clone())
-                                    .collect::<Vec<_>>()
-                                    .into();
-                                *value = new_order;
-                            }
-                        });
-                    }
-                }
-
-                // Turn the output keys into regular strings.
-                res.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
-            })
-            .collect::<Vec<BTreeMap<_, _>>>();
+        let results = semver_query
+            .run_with_ordered_folds(&adapter)
+            .expect("failed to run query");
         (format!("./test_crates/{crate_pair_name}/"), results)
     }
 
@@ -751,8 +825,13 @@ mod tests {
                 }
             }
         };
+        // Results with the same key, e.g. ones about the same item, are ordered by their outputs.
         for value in query_execution_results.values_mut() {
-            value.sort_unstable_by_key(key_func);
+            value.sort_unstable_by(|a, b| {
+                key_func(a)
+                    .cmp(&key_func(b))
+                    .then_with(|| format!("{a:?}").cmp(&format!("{b:?}")))
+            });
         }
 
         // TODO: Remove this once Rust 1.85 is the oldest Rust supported by cargo-semver-checks.
@@ -790,10 +869,7 @@ mod tests {
         if let Some(template) = semver_query.per_result_error_template {
             assert!(!transparent_results.is_empty());
 
-            let flattened_actual_results: Vec<_> = transparent_results
-                .iter()
-                .flat_map(|(_key, value)| value)
-                .collect();
+            let flattened_actual_results: Vec<_> = transparent_results.values().flatten().collect();
             for semver_violation_result in flattened_actual_results {
                 registry
                     .render_template(&template, semver_violation_result)
@@ -871,6 +947,7 @@ mod tests {
             error_message: String::new(),
             per_result_error_template: None,
             witness: None,
+            shim_template: None,
         }
     }
 
@@ -1251,6 +1328,7 @@ add_lints!(
     enum_variant_missing,
     exported_function_changed_abi,
     feature_missing,
+    feature_no_longer_enables_feature,
     feature_not_enabled_by_default,
     function_abi_no_longer_unwind,
     function_abi_now_unwind,
@@ -0,0 +1,577 @@
+//! Suggestions for where items reported as missing may have gone.
+//!
+//! Lints like `function_missing` can't tell whether an item was removed, renamed, or moved.
+//! Here we look through the current crate for a new item of the same kind that looks like
+//! the missing one: either one with the same name under a different path, or one with
+//! a similar name in the same module. Missing methods are only matched to similarly-named
+//! new methods of the same type, since a method moved to another type can't be forwarded to.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use trustfall::{FieldValue, TransparentValue};
+use trustfall_rustdoc::VersionedRustdocAdapter;
+
+use crate::check_release::result_path;
+use crate::shims::forwarding_values;
+use crate::SemverQuery;
+
+/// How similar two names must be, according to `strsim::jaro_winkler()`,
+/// for one to be suggested as a possible rename of the other.
+const RENAME_SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// The kind of item reported as missing by each supported lint,
+/// and how to compute a signature that similar items of that kind must share.
+/// Functions also output their declaration, so shims can forward to them.
+fn item_kind(lint_id: &str) -> Option<(&'static str, &'static str)> {
+    Some(match lint_id {
+        "function_missing" => (
+            "Function",
+            r#"parameter @fold @transform(op: "count") @output(name: "signature")
+                        declaration: signature @output"#,
+        ),
+        "struct_missing" => ("Struct", r#"struct_type @output(name: "signature")"#),
+        "enum_missing" => ("Enum", ""),
+        "trait_missing" => ("Trait", ""),
+        "union_missing" => ("Union", ""),
+        "pub_static_missing" => ("Static", ""),
+        "pub_module_level_const_missing" => ("Constant", ""),
+        "module_missing" => ("Module", ""),
+        _ => return None,
+    })
+}
+
+fn items_query(side: &str, kind: &str, signature: &str) -> String {
+    format!(
+        r#"
+    {{
+        CrateDiff {{
+            {side} {{
+                item {{
+                    ... on {kind} {{
+                        visibility_limit @filter(op: "=", value: ["$public"])
+                        {signature}
+
+                        importable_path {{
+                            path @output
+                            public_api @filter(op: "=", value: ["$true"])
+                        }}
+                    }}
+                }}
+            }}
+        }}
+    }}"#
+    )
+}
+
+/// The public inherent methods of publicly-importable types, with the same outputs as
+/// [`items_query()`] for functions, plus the `method_name` to append to the type's path.
+fn methods_query(side: &str) -> String {
+    format!(
+        r#"
+    {{
+        CrateDiff {{
+            {side} {{
+                item {{
+                    ... on ImplOwner {{
+                        visibility_limit @filter(op: "=", value: ["$public"])
+
+                        importable_path {{
+                            path @output
+                            public_api @filter(op: "=", value: ["$true"])
+                        }}
+
+                        inherent_impl {{
+                            public_api_eligible @filter(op: "=", value: ["$true"])
+
+                            method {{
+                                visibility_limit @filter(op: "=", value: ["$public"])
+                                public_api_eligible @filter(op: "=", value: ["$true"])
+                                method_name: name @output
+                                parameter @fold @transform(op: "count") @output(name: "signature")
+                                declaration: signature @output
+                            }}
+                        }}
+                    }}
+                }}
+            }}
+        }}
+    }}"#
+    )
+}
+
+/// A publicly-importable item, under one of its importable paths.
+/// Methods are identified by their type's path followed by their name.
+#[derive(Debug, Clone, PartialEq)]
+struct ImportableItem {
+    path: Vec<String>,
+    signature: Option<FieldValue>,
+    /// The item's declaration, for functions and methods.
+    declaration: Option<String>,
+}
+
+impl ImportableItem {
+    fn name(&self) -> &str {
+        self.path.last().map(String::as_str).unwrap_or_default()
+    }
+
+    fn module(&self) -> &[String] {
+        &self.path[..self.path.len().saturating_sub(1)]
+    }
+}
+
+/// Where a missing item may have gone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Relocation {
+    /// An item with the same name now exists at this path.
+    Moved(Vec<String>),
+    /// An item with a similar name now exists at this path, in the same module.
+    Renamed(Vec<String>),
+    /// A method with a similar name now exists at this path, on the same type.
+    MethodRenamed(Vec<String>),
+}
+
+impl Relocation {
+    fn new_path(&self) -> &[String] {
+        match self {
+            Self::Moved(path) | Self::Renamed(path) | Self::MethodRenamed(path) => path,
+        }
+    }
+
+    /// Describes where the item may have gone, e.g. "possibly renamed to `bar`".
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Self::Moved(path) => format!("possibly moved to `{}`", crate_relative(path)),
+            Self::Renamed(path) | Self::MethodRenamed(path) => format!(
+                "possibly renamed to `{}`",
+                path.last().map(String::as_str).unwrap_or_default()
+            ),
+        }
+    }
+
+    /// Returns the module where a re-export would restore the missing item's path,
+    /// and the `pub use` item that does so. Methods can't be re-exported.
+    pub(crate) fn shim(&self, missing_path: &[String]) -> Option<(String, String)> {
+        if let Self::MethodRenamed(_) = self {
+            return None;
+        }
+        let module = missing_module(missing_path);
+        let new_path = self.new_path();
+        let missing_name = missing_path.last().map(String::as_str).unwrap_or_default();
+        let shim = if new_path.last().map(String::as_str) == Some(missing_name) {
+            format!("pub use {};", crate_relative(new_path))
+        } else {
+            format!("pub use {} as {missing_name};", crate_relative(new_path))
+        };
+        Some((module, shim))
+    }
+
+    /// The values a lint's shim template can use to refer to where the missing item went.
+    /// For methods, `module` is the path of their type.
+    pub(crate) fn template_values(
+        &self,
+        missing_path: &[String],
+    ) -> [(Arc<str>, TransparentValue); 2] {
+        [
+            (
+                Arc::from("module"),
+                TransparentValue::String(missing_module(missing_path).into()),
+            ),
+            (
+                Arc::from("new_path"),
+                TransparentValue::String(crate_relative(self.new_path()).into()),
+            ),
+        ]
+    }
+}
+
+/// The module containing the missing item, as it would be written inside the crate itself.
+fn missing_module(missing_path: &[String]) -> String {
+    crate_relative(&missing_path[..missing_path.len().saturating_sub(1)])
+}
+
+/// Formats an importable path as it would be written inside the crate itself.
+fn crate_relative(path: &[String]) -> String {
+    std::iter::once("crate")
+        .chain(path.iter().skip(1).map(String::as_str))
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// The items that a lint's missing items could have been renamed or moved to.
+pub(crate) struct RenameHints {
+    baseline: Vec<ImportableItem>,
+    current: Vec<ImportableItem>,
+    /// Whether the missing items are methods, identified by the `method_name` output
+    /// in addition to their type's `path`.
+    methods: bool,
+}
+
+impl RenameHints {
+    /// Looks up the items relevant to the given lint,
+    /// or returns `None` if the lint isn't about a missing item.
+    pub(crate) fn for_lint(
+        adapter: &VersionedRustdocAdapter<'_>,
+        semver_query: &SemverQuery,
+    ) -> anyhow::Result<Option<Self>> {
+        let methods = semver_query.id == "inherent_method_missing";
+        let query = |side: &str| match item_kind(&semver_query.id) {
+            Some((kind, signature)) => Some(items_query(side, kind, signature)),
+            None => methods.then(|| methods_query(side)),
+        };
+        if query("baseline").is_none() {
+            return Ok(None);
+        }
+
+        let load_items = |side: &str| -> anyhow::Result<Vec<ImportableItem>> {
+            let arguments: BTreeMap<&str, FieldValue> = [
+                ("public", FieldValue::from("public")),
+                ("true", FieldValue::from(true)),
+            ]
+            .into_iter()
+            .collect();
+            let query = query(side).expect("lint is supported");
+            Ok(adapter
+                .run_query(&query, arguments)?
+                .filter_map(|mut result| {
+                    let mut path: Vec<String> = result
+                        .remove("path")?
+                        .as_vec_with(FieldValue::as_str)?
+                        .into_iter()
+                        .map(str::to_string)
+                        .collect();
+                    if let Some(method_name) = result.remove("method_name") {
+                        path.push(method_name.as_str()?.to_string());
+                    }
+                    Some(ImportableItem {
+                        path,
+                        signature: result.remove("signature"),
+                        declaration: result
+                            .remove("declaration")
+                            .and_then(|declaration| Some(declaration.as_str()?.to_string())),
+                    })
+                })
+                .collect())
+        };
+
+        Ok(Some(Self {
+            baseline: load_items("baseline")?,
+            current: load_items("current")?,
+            methods,
+        }))
+    }
+
+    /// Suggests where the item at the `path` output of a lint result may have gone,
+    /// or for methods, the method named by its `method_name` output.
+    pub(crate) fn find_for_result(
+        &self,
+        result: &BTreeMap<Arc<str>, TransparentValue>,
+    ) -> Option<(Vec<String>, Relocation)> {
+        let mut path = result_path(result)?;
+        if !self.methods {
+            let relocation = find_relocation(&path, &self.baseline, &self.current, true)?;
+            return Some((path, relocation));
+        }
+
+        let Some(TransparentValue::String(method_name)) = result.get("method_name") else {
+            return None;
+        };
+        path.push(method_name.to_string());
+        match find_relocation(&path, &self.baseline, &self.current, false)? {
+            Relocation::Renamed(new_path) => Some((path, Relocation::MethodRenamed(new_path))),
+            _ => None,
+        }
+    }
+
+    /// The values a lint's shim template can use to refer to where the missing item went,
+    /// plus `wrapper_signature` and `forwarding_call` for a wrapper that forwards to it.
+    /// The latter are null unless the item is a function or method with forwardable parameters.
+    pub(crate) fn template_values(
+        &self,
+        missing_path: &[String],
+        relocation: &Relocation,
+    ) -> Vec<(Arc<str>, TransparentValue)> {
+        let new_path = relocation.new_path();
+        let old_name = missing_path.last().map(String::as_str).unwrap_or_default();
+        let new_name = new_path.last().map(String::as_str).unwrap_or_default();
+        let callee = match relocation {
+            Relocation::MethodRenamed(_) => format!("Self::{new_name}"),
+            Relocation::Moved(_) | Relocation::Renamed(_) => crate_relative(new_path),
+        };
+        let forwarding = self
+            .current
+            .iter()
+            .find(|item| item.path == new_path)
+            .and_then(|item| item.declaration.as_deref())
+            .and_then(|declaration| forwarding_values(declaration, old_name, new_name, &callee));
+
+        let mut values = relocation.template_values(missing_path).to_vec();
+        match forwarding {
+            Some(forwarding) => values.extend(forwarding),
+            None => values.extend(
+                ["wrapper_signature", "forwarding_call"]
+                    .map(|name| (Arc::from(name), TransparentValue::Null)),
+            ),
+        }
+        values
+    }
+}
+
+/// Finds where the missing item may have gone. Items with the same name elsewhere
+/// are only considered if `moves_allowed`; otherwise only renames are.
+fn find_relocation(
+    missing_path: &[String],
+    baseline: &[ImportableItem],
+    current: &[ImportableItem],
+    moves_allowed: bool,
+) -> Option<Relocation> {
+    let missing = baseline.iter().find(|item| item.path == missing_path)?;
+
+    // Only consider items at paths that are new in the current version:
+    // items that were already there can't be where the missing item went.
+    let baseline_paths: BTreeSet<&[String]> =
+        baseline.iter().map(|item| item.path.as_slice()).collect();
+    let candidates = current.iter().filter(|item| {
+        !baseline_paths.contains(item.path.as_slice()) && item.signature == missing.signature
+    });
+
+    let mut moved: Option<&ImportableItem> = None;
+    let mut renamed: Option<(f64, &ImportableItem)> = None;
+    for candidate in candidates {
+        if candidate.name() == missing.name() {
+            if !moves_allowed {
+                continue;
+            }
+            // Prefer the shortest path, since it's most likely to be the item's intended path.
+            if moved.is_none_or(|best| {
+                (candidate.path.len(), &candidate.path) < (best.path.len(), &best.path)
+            }) {
+                moved = Some(candidate);
+            }
+        } else if candidate.module() == missing.module() {
+            let similarity = strsim::jaro_winkler(candidate.name(), missing.name());
+            if similarity >= RENAME_SIMILARITY_THRESHOLD
+                && renamed.is_none_or(|(best_similarity, best)| {
+                    similarity > best_similarity
+                        || (similarity == best_similarity && candidate.path < best.path)
+                })
+            {
+                renamed = Some((similarity, candidate));
+            }
+        }
+    }
+
+    moved
+        .map(|item| Relocation::Moved(item.path.clone()))
+        .or_else(|| renamed.map(|(_, item)| Relocation::Renamed(item.path.clone())))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use trustfall::{FieldValue, TransparentValue};
+
+    use super::{
+        find_relocation, item_kind, items_query, methods_query, ImportableItem, Relocation,
+        RenameHints,
+    };
+    use crate::templating::make_handlebars_registry;
+    use crate::SemverQuery;
+
+    fn item(path: &str, signature: Option<u64>) -> ImportableItem {
+        ImportableItem {
+            path: path.split("::").map(str::to_string).collect(),
+            signature: signature.map(FieldValue::from),
+            declaration: None,
+        }
+    }
+
+    fn path(path: &str) -> Vec<String> {
+        path.split("::").map(str::to_string).collect()
+    }
+
+    #[test]
+    fn items_queries_are_valid() {
+        let schema = trustfall_rustdoc_adapter_v39::RustdocAdapter::schema();
+        for lint_id in ["function_missing", "struct_missing", "module_missing"] {
+            let (kind, signature) = item_kind(lint_id).expect("lint not supported");
+            for side in ["baseline", "current"] {
+                let query = items_query(side, kind, signature);
+                if let Err(e) = trustfall_core::frontend::parse(&schema, &query) {
+                    panic!("invalid query for {lint_id}: {e}\n{query}");
+                }
+            }
+        }
+        for side in ["baseline", "current"] {
+            let query = methods_query(side);
+            if let Err(e) = trustfall_core::frontend::parse(&schema, &query) {
+                panic!("invalid methods query: {e}\n{query}");
+            }
+        }
+    }
+
+    #[test]
+    fn moved_item() {
+        let baseline = [item("lib::a::foo", Some(1)), item("lib::a::other", Some(1))];
+        let current = [
+            item("lib::a::other", Some(1)),
+            item("lib::b::c::foo", Some(1)),
+            item("lib::b::foo", Some(1)),
+        ];
+        let relocation = find_relocation(&path("lib::a::foo"), &baseline, &current, true);
+        assert_eq!(relocation, Some(Relocation::Moved(path("lib::b::foo"))));
+
+        let relocation = relocation.expect("no relocation");
+        assert_eq!(relocation.describe(), "possibly moved to `crate::b::foo`");
+        assert_eq!(
+            relocation.shim(&path("lib::a::foo")),
+            Some(("crate::a".to_string(), "pub use crate::b::foo;".to_string()))
+        );
+    }
+
+    #[test]
+    fn renamed_item() {
+        let baseline = [item("lib::parse_config", Some(1))];
+        let current = [
+            item("lib::parse", Some(1)),
+            item("lib::parse_configuration", Some(1)),
+            item("lib::unrelated", Some(1)),
+        ];
+        let relocation = find_relocation(&path("lib::parse_config"), &baseline, &current, true);
+        assert_eq!(
+            relocation,
+            Some(Relocation::Renamed(path("lib::parse_configuration")))
+        );
+
+        let relocation = relocation.expect("no relocation");
+        assert_eq!(
+            relocation.describe(),
+            "possibly renamed to `parse_configuration`"
+        );
+        assert_eq!(
+            relocation.shim(&path("lib::parse_config")),
+            Some((
+                "crate".to_string(),
+                "pub use crate::parse_configuration as parse_config;".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn no_relocation() {
+        let baseline = [item("lib::a::foo", Some(1)), item("lib::b::foo", Some(1))];
+        let current = [
+            // Already existed, so it can't be where `lib::a::foo` went.
+            item("lib::b::foo", Some(1)),
+            // Different signature.
+            item("lib::c::foo", Some(2)),
+            // Similar name, but in a different module.
+            item("lib::d::fooo", Some(1)),
+            // Same module, but the name isn't similar.
+            item("lib::a::bar", Some(1)),
+        ];
+        assert_eq!(
+            find_relocation(&path("lib::a::foo"), &baseline, &current, true),
+            None
+        );
+    }
+
+    #[test]
+    fn renamed_method() {
+        let baseline = [item("lib::Parser::finish", Some(1))];
+        let current = [
+            // Same name on another type: not something a wrapper could forward to.
+            item("lib::Builder::finish", Some(1)),
+            item("lib::Parser::finished", Some(1)),
+        ];
+        assert_eq!(
+            find_relocation(&path("lib::Parser::finish"), &baseline, &current, false),
+            Some(Relocation::Renamed(path("lib::Parser::finished")))
+        );
+
+        let relocation = Relocation::MethodRenamed(path("lib::Parser::finished"));
+        assert_eq!(relocation.describe(), "possibly renamed to `finished`");
+        assert_eq!(relocation.shim(&path("lib::Parser::finish")), None);
+    }
+
+    fn render_shim(
+        lint_id: &str,
+        hints: &RenameHints,
+        name: &str,
+        relocation: &Relocation,
+    ) -> String {
+        let query = &SemverQuery::all_queries()[lint_id];
+        let template = query.shim_template.as_deref().expect("no shim template");
+        let missing_path = hints.baseline[0].path.clone();
+
+        let mut values: BTreeMap<Arc<str>, TransparentValue> = [
+            (Arc::from("name"), TransparentValue::String(name.into())),
+            (
+                Arc::from("method_name"),
+                TransparentValue::String(missing_path.last().expect("empty path").as_str().into()),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        values.extend(hints.template_values(&missing_path, relocation));
+
+        make_handlebars_registry()
+            .render_template(template, &values)
+            .expect("failed to render shim")
+    }
+
+    #[test]
+    fn shim_template_uses_relocation() {
+        let hints = RenameHints {
+            baseline: vec![item("lib::parse_config", Some(1))],
+            current: vec![item("lib::parse_configuration", Some(1))],
+            methods: false,
+        };
+        let relocation = Relocation::Renamed(path("lib::parse_configuration"));
+
+        // Without the new function's declaration, it can only be re-exported.
+        assert_eq!(
+            render_shim("function_missing", &hints, "parse_config", &relocation),
+            "// in `crate`:\npub use crate::parse_configuration as parse_config;"
+        );
+    }
+
+    #[test]
+    fn shim_template_forwards_to_renamed_items() {
+        let mut new_function = item("lib::parse_configuration", Some(1));
+        new_function.declaration = Some("fn parse_configuration(text: &str) -> Config".into());
+        let hints = RenameHints {
+            baseline: vec![item("lib::parse_config", Some(1))],
+            current: vec![new_function],
+            methods: false,
+        };
+        let relocation = Relocation::Renamed(path("lib::parse_configuration"));
+        assert_eq!(
+            render_shim("function_missing", &hints, "parse_config", &relocation),
+            "// in `crate`:\n\
+            #[deprecated(note = \"use `crate::parse_configuration` instead\")]\n\
+            pub fn parse_config(text: &str) -> Config {\n    \
+                crate::parse_configuration(text)\n\
+            }"
+        );
+
+        let mut new_method = item("lib::Parser::finished", Some(2));
+        new_method.declaration = Some("fn finished(self: &mut Self, strict: bool) -> usize".into());
+        let hints = RenameHints {
+            baseline: vec![item("lib::Parser::finish", Some(2))],
+            current: vec![new_method],
+            methods: true,
+        };
+        let relocation = Relocation::MethodRenamed(path("lib::Parser::finished"));
+        assert_eq!(
+            render_shim("inherent_method_missing", &hints, "Parser", &relocation),
+            "// in `impl Parser`:\n\
+            #[deprecated(note = \"use `crate::Parser::finished` instead\")]\n\
+            pub fn finish(self: &mut Self, strict: bool) -> usize {\n    \
+                self.finished(strict)\n\
+            }"
+        );
+    }
+}
@@ -1,14 +1,17 @@
 use std::borrow::Cow;
-use std::collections::{BTreeSet, HashMap};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::PathBuf;
+use std::rc::Rc;
 
 use anyhow::{bail, Context as _};
 use itertools::Itertools;
 use serde::Serialize;
 use tame_index::IndexKrate;
-use trustfall_rustdoc::VersionedStorage;
 
-use crate::data_generation::{CrateDataRequest, IntoTerminalResult as _, TerminalError};
+use crate::data_generation::{
+    CrateDataRequest, IntoTerminalResult as _, LoadedCrate, TerminalError,
+};
 use crate::manifest::Manifest;
 use crate::GlobalConfig;
 
@@ -16,9 +19,12 @@ use crate::GlobalConfig;
 pub(crate) enum CrateSource<'a> {
     Registry {
         crate_: &'a tame_index::IndexVersion,
+        registry: Option<&'a str>,
     },
     ManifestPath {
         manifest: &'a Manifest,
+        /// The id of the git tree the manifest's project was extracted from, if any.
+        tree_id: Option<&'a str>,
     },
 }
 
@@ -28,7 +34,9 @@ impl CrateSource<'_> {
     pub(crate) fn regular_features(&self) -> Vec<String> {
         match self {
             Self::Registry { crate_, .. } => crate_.features().map(|(k, _v)| k).cloned().collect(),
-            Self::ManifestPath { manifest } => manifest.parsed.features.keys().cloned().collect(),
+            Self::ManifestPath { manifest, .. } => {
+                manifest.parsed.features.keys().cloned().collect()
+            }
         }
     }
 
@@ -42,7 +50,7 @@ impl CrateSource<'_> {
                 .filter(|dep| dep.is_optional())
                 .map(|dep| dep.name.to_string())
                 .collect(),
-            Self::ManifestPath { manifest } => {
+            Self::ManifestPath { manifest, .. } => {
                 let mut dependencies = manifest.parsed.dependencies.clone();
                 for target in manifest.parsed.target.values() {
                     // Fixes https://github.com/obi1kenobi/cargo-semver-checks/issues/369
@@ -66,7 +74,7 @@ impl CrateSource<'_> {
 
         let feature_defns: Vec<&String> = match self {
             Self::Registry { crate_, .. } => crate_.features().flat_map(|(_k, v)| v).collect(),
-            Self::ManifestPath { manifest } => {
+            Self::ManifestPath { manifest, .. } => {
                 manifest.parsed.features.values().flatten().collect()
             }
         };
@@ -173,6 +181,7 @@ impl CrateSource<'_> {
 
         result
             .into_iter()
+            .filter(|feature_name| !feature_config.excluded_features.contains(feature_name))
             .filter(|feature_name| {
                 if !all_features.contains(feature_name) && feature_config.is_baseline {
                     global_config
@@ -208,6 +217,9 @@ pub(crate) struct FeatureConfig {
     pub(crate) features_group: FeaturesGroup,
     /// Explicitly enabled features.
     pub(crate) extra_features: Vec<String>,
+    /// Features never to enable, even if they are in the chosen feature set.
+    /// Features enabled by default are only excluded if default features aren't used.
+    pub(crate) excluded_features: Vec<String>,
     pub(crate) is_baseline: bool,
 }
 
@@ -225,6 +237,7 @@ impl FeatureConfig {
         Self {
             features_group: FeaturesGroup::Heuristic,
             extra_features: Vec::new(),
+            excluded_features: Vec::new(),
             is_baseline: false,
         }
     }
@@ -233,9 +246,69 @@ impl FeatureConfig {
         Self {
             features_group: FeaturesGroup::Heuristic,
             extra_features: Vec::new(),
+            excluded_features: Vec::new(),
             is_baseline: true,
         }
     }
+
+    /// Layers more settings on top of this config. The feature group, if any, replaces
+    /// the current one. The newly enabled and excluded features take precedence over
+    /// the ones excluded and enabled so far.
+    pub(crate) fn layer(
+        &mut self,
+        features_group: Option<FeaturesGroup>,
+        features: &[String],
+        excluded_features: &[String],
+    ) {
+        if let Some(features_group) = features_group {
+            self.features_group = features_group;
+        }
+        self.excluded_features
+            .retain(|feature| !features.contains(feature));
+        self.extra_features
+            .retain(|feature| !excluded_features.contains(feature));
+        for feature in features {
+            if !self.extra_features.contains(feature) {
+                self.extra_features.push(feature.clone());
+            }
+        }
+        for feature in excluded_features {
+            if !self.excluded_features.contains(feature) {
+                self.excluded_features.push(feature.clone());
+            }
+        }
+    }
+
+    /// Enables each renamed feature on both sides whenever it's enabled on one of them,
+    /// so the API behind it is compared. `renamed_features` maps baseline feature names
+    /// to the names of the same features in the current version.
+    ///
+    /// Only features enabled by name are considered, not the ones a features group enables,
+    /// since groups are resolved against each side's own manifest when generating rustdoc.
+    /// So a feature the heuristic treats differently under its two names, like `unstable-tls`
+    /// renamed to `tls`, must be enabled by name for the API behind it to be compared.
+    pub(crate) fn apply_renames(
+        renamed_features: &BTreeMap<String, String>,
+        current: &mut Self,
+        baseline: &mut Self,
+    ) {
+        let mut current_features = vec![];
+        let mut baseline_features = vec![];
+        for (baseline_name, current_name) in renamed_features {
+            if baseline.extra_features.contains(baseline_name)
+                && !current.excluded_features.contains(current_name)
+            {
+                current_features.push(current_name.clone());
+            }
+            if current.extra_features.contains(current_name)
+                && !baseline.excluded_features.contains(baseline_name)
+            {
+                baseline_features.push(baseline_name.clone());
+            }
+        }
+        current.layer(None, &current_features, &[]);
+        baseline.layer(None, &baseline_features, &[]);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -244,6 +317,8 @@ pub(crate) struct CrateDataForRustdoc<'a> {
     pub(crate) name: &'a str,
     pub(crate) feature_config: &'a FeatureConfig,
     pub(crate) build_target: Option<&'a str>,
+    /// The rustup toolchain to generate the rustdoc with, if not the active one.
+    pub(crate) toolchain: Option<&'a str>,
 }
 
 fn generate_rustdoc(
@@ -253,7 +328,7 @@ fn generate_rustdoc(
     target_root: PathBuf,
     crate_source: CrateSource,
     crate_data: CrateDataForRustdoc,
-) -> Result<VersionedStorage, TerminalError> {
+) -> Result<LoadedCrate, TerminalError> {
     let extra_features: BTreeSet<Cow<'_, str>> = crate_source
         .feature_list_from_config(config, crate_data.feature_config)
         .into_iter()
@@ -265,21 +340,25 @@ fn generate_rustdoc(
     );
 
     let request = match crate_source {
-        CrateSource::Registry { crate_, .. } => CrateDataRequest::from_index(
+        CrateSource::Registry { crate_, registry } => CrateDataRequest::from_index(
             crate_,
+            registry,
             default_features,
             extra_features,
             crate_data.build_target,
+            crate_data.toolchain,
             matches!(
                 crate_data.crate_type,
                 crate::rustdoc_gen::CrateType::Baseline { .. }
             ),
         ),
-        CrateSource::ManifestPath { manifest } => CrateDataRequest::from_local_project(
+        CrateSource::ManifestPath { manifest, tree_id } => CrateDataRequest::from_local_project(
             manifest,
+            tree_id,
             default_features,
             extra_features,
             crate_data.build_target,
+            crate_data.toolchain,
             matches!(
                 crate_data.crate_type,
                 crate::rustdoc_gen::CrateType::Baseline { .. }
@@ -306,7 +385,36 @@ pub(crate) trait RustdocGenerator {
         generation_settings: super::data_generation::GenerationSettings,
         cache_settings: super::data_generation::CacheSettings<()>,
         crate_data: CrateDataForRustdoc,
-    ) -> Result<VersionedStorage, TerminalError>;
+    ) -> Result<LoadedCrate, TerminalError>;
+
+    /// The directory containing the given package's source code, if it is available locally.
+    ///
+    /// Used to resolve relative file paths in lint spans.
+    fn package_source_dir(&self, _crate_name: &str) -> Option<PathBuf> {
+        None
+    }
+
+    /// Whether the given package has a library target, if that's known without generating
+    /// its rustdoc, e.g. from a local manifest.
+    fn package_has_lib_target(&self, _crate_name: &str) -> Option<bool> {
+        None
+    }
+
+    /// Whether there may be a baseline of the given package at all, e.g. because
+    /// it was published to the registry. Used to avoid generating baselines that can't exist.
+    fn package_has_history(
+        &self,
+        _config: &mut GlobalConfig,
+        _crate_name: &str,
+    ) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+
+    /// A short description of where the crate's source comes from, if it's not obvious
+    /// from its version alone, such as the git commit that was automatically chosen.
+    fn source_label(&self) -> Option<String> {
+        None
+    }
 }
 
 #[derive(Debug)]
@@ -327,10 +435,15 @@ impl RustdocGenerator for RustdocFromFile {
         _generation_settings: super::data_generation::GenerationSettings,
         _cache_settings: super::data_generation::CacheSettings<()>,
         _crate_data: CrateDataForRustdoc,
-    ) -> Result<VersionedStorage, TerminalError> {
-        trustfall_rustdoc::load_rustdoc(&self.path, None)
+    ) -> Result<LoadedCrate, TerminalError> {
+        let data = trustfall_rustdoc::load_rustdoc(&self.path, None)
             .map_err(anyhow::Error::from)
-            .into_terminal_result()
+            .into_terminal_result()?;
+        Ok(LoadedCrate {
+            data,
+            json_path: Some(self.path.clone()),
+            package: None,
+        })
     }
 }
 
@@ -341,6 +454,9 @@ pub(crate) struct RustdocFromProjectRoot {
     manifest_errors: HashMap<PathBuf, anyhow::Error>,
     duplicate_packages: HashMap<String, Vec<PathBuf>>,
     target_root: PathBuf,
+    /// The id of the git tree the project was extracted from, if any.
+    /// Since trees never change, the project's rustdoc can be cached.
+    tree_id: Option<String>,
 }
 
 impl RustdocFromProjectRoot {
@@ -415,6 +531,7 @@ impl RustdocFromProjectRoot {
             manifest_errors,
             duplicate_packages,
             target_root: target_root.to_owned(),
+            tree_id: None,
         })
     }
 }
@@ -426,7 +543,7 @@ impl RustdocGenerator for RustdocFromProjectRoot {
         generation_settings: super::data_generation::GenerationSettings,
         cache_settings: super::data_generation::CacheSettings<()>,
         crate_data: CrateDataForRustdoc,
-    ) -> Result<VersionedStorage, TerminalError> {
+    ) -> Result<LoadedCrate, TerminalError> {
         let manifest: &Manifest = self.manifests.get(crate_data.name).ok_or_else(|| {
             if let Some(duplicates) = self.duplicate_packages.get(crate_data.name) {
                 let duplicates = duplicates.iter().map(|p| p.display()).join("\n  ");
@@ -460,15 +577,33 @@ impl RustdocGenerator for RustdocFromProjectRoot {
             generation_settings,
             cache_settings,
             self.target_root.clone(),
-            CrateSource::ManifestPath { manifest },
+            CrateSource::ManifestPath {
+                manifest,
+                tree_id: self.tree_id.as_deref(),
+            },
             crate_data,
         )
     }
+
+    fn package_source_dir(&self, crate_name: &str) -> Option<PathBuf> {
+        let manifest = self.manifests.get(crate_name)?;
+        manifest.path.parent().map(ToOwned::to_owned)
+    }
+
+    fn package_has_lib_target(&self, crate_name: &str) -> Option<bool> {
+        Some(
+            self.manifests
+                .get(crate_name)
+                .and_then(|manifest| manifest.parsed.lib.as_ref())
+                .is_some_and(|lib| !lib.proc_macro),
+        )
+    }
 }
 
 #[derive(Debug)]
 pub(crate) struct RustdocFromGitRevision {
     path: RustdocFromProjectRoot,
+    label: Option<String>,
 }
 
 impl RustdocFromGitRevision {
@@ -487,11 +622,60 @@ impl RustdocFromGitRevision {
         std::fs::create_dir_all(&tree_dir)?;
         extract_tree(tree_id, &tree_dir)?;
 
-        let path = RustdocFromProjectRoot::new(&tree_dir, target)?;
-        Ok(Self { path })
+        let mut path = RustdocFromProjectRoot::new(&tree_dir, target)?;
+        path.tree_id = Some(tree_id.to_string());
+        Ok(Self { path, label: None })
+    }
+
+    /// Uses the commit where the current `HEAD` forked from the given branch,
+    /// i.e. the merge base of `HEAD` and the branch.
+    pub fn with_merge_base(
+        source: &std::path::Path,
+        target: &std::path::Path,
+        branch: &str,
+        config: &mut GlobalConfig,
+    ) -> anyhow::Result<Self> {
+        let repo = gix::discover(source)?;
+        let merge_base = find_merge_base(&repo, branch)?;
+
+        let mut generator = Self::with_rev(source, target, &merge_base.to_string(), config)?;
+        generator.label = Some(format!(
+            "merge base {} with {branch}",
+            merge_base.shorten_or_id()
+        ));
+        Ok(generator)
     }
 }
 
+fn find_merge_base<'repo>(
+    repo: &'repo gix::Repository,
+    branch: &str,
+) -> anyhow::Result<gix::Id<'repo>> {
+    let shallow_advice = || {
+        if repo.is_shallow() {
+            "\nnote: this repository is a shallow clone, so the history needed to find \
+            the merge base may be missing\n\
+            help: fetch the full history with `git fetch --unshallow`, \
+            or use `fetch-depth: 0` with `actions/checkout` in GitHub Actions"
+        } else {
+            ""
+        }
+    };
+
+    let head = repo
+        .head_id()
+        .context("failed to resolve HEAD to a commit")?;
+    let branch_commit = repo
+        .rev_parse_single(&*format!("{branch}^{{commit}}"))
+        .with_context(|| format!("failed to resolve branch `{branch}`{}", shallow_advice()))?;
+    repo.merge_base(head, branch_commit).with_context(|| {
+        format!(
+            "failed to find the merge base of HEAD and `{branch}`{}",
+            shallow_advice()
+        )
+    })
+}
+
 fn extract_tree(tree: gix::Id<'_>, target: &std::path::Path) -> anyhow::Result<()> {
     for entry in tree.object()?.try_into_tree()?.iter() {
         let entry = entry?;
@@ -524,10 +708,232 @@ impl RustdocGenerator for RustdocFromGitRevision {
         generation_settings: super::data_generation::GenerationSettings,
         cache_settings: super::data_generation::CacheSettings<()>,
         crate_data: CrateDataForRustdoc,
-    ) -> Result<VersionedStorage, TerminalError> {
+    ) -> Result<LoadedCrate, TerminalError> {
+        self.path
+            .load_rustdoc(config, generation_settings, cache_settings, crate_data)
+    }
+
+    fn package_source_dir(&self, crate_name: &str) -> Option<PathBuf> {
+        self.path.package_source_dir(crate_name)
+    }
+
+    fn package_has_lib_target(&self, crate_name: &str) -> Option<bool> {
+        self.path.package_has_lib_target(crate_name)
+    }
+
+    fn source_label(&self) -> Option<String> {
+        self.label.clone()
+    }
+}
+
+/// Generates rustdoc from a `.crate` file, as created by `cargo package`
+/// and served by registries, without needing access to any registry.
+#[derive(Debug)]
+pub(crate) struct RustdocFromCrateFile {
+    path: RustdocFromProjectRoot,
+    label: String,
+}
+
+impl RustdocFromCrateFile {
+    /// # Arguments
+    /// * `crate_file` - Path to a `.crate` file, or to a directory with the contents of one,
+    ///   such as the output of `cargo vendor`.
+    /// * `target_root` - Path to a directory where the crate can be unpacked,
+    ///   and where the placeholder manifest / rustdoc can be created.
+    pub(crate) fn new(
+        crate_file: &std::path::Path,
+        target_root: &std::path::Path,
+    ) -> anyhow::Result<Self> {
+        let label = crate_file
+            .file_name()
+            .with_context(|| format!("`{}` is not a crate file", crate_file.display()))?
+            .to_string_lossy()
+            .into_owned();
+
+        let source_dir = if crate_file.is_dir() {
+            crate_file.to_owned()
+        } else {
+            let source_dir = target_root.join("source");
+            extract_crate_file(crate_file, &source_dir)
+                .with_context(|| format!("failed to unpack `{}`", crate_file.display()))?;
+            source_dir
+        };
+
+        let path = RustdocFromProjectRoot::new(&source_dir, target_root)?;
+        Ok(Self { path, label })
+    }
+}
+
+/// Unpacks the gzipped tarball of a `.crate` file, replacing any previously unpacked contents.
+///
+/// The tarball's file modification times are preserved, so unpacking the same file again
+/// doesn't cause cargo to rebuild the crate.
+fn extract_crate_file(
+    crate_file: &std::path::Path,
+    target: &std::path::Path,
+) -> anyhow::Result<()> {
+    if target.exists() {
+        fs_err::remove_dir_all(target)?;
+    }
+    fs_err::create_dir_all(target)?;
+
+    let file = fs_err::File::open(crate_file)?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    archive.unpack(target)?;
+    Ok(())
+}
+
+impl RustdocGenerator for RustdocFromCrateFile {
+    fn load_rustdoc(
+        &self,
+        config: &mut GlobalConfig,
+        generation_settings: super::data_generation::GenerationSettings,
+        cache_settings: super::data_generation::CacheSettings<()>,
+        crate_data: CrateDataForRustdoc,
+    ) -> Result<LoadedCrate, TerminalError> {
         self.path
             .load_rustdoc(config, generation_settings, cache_settings, crate_data)
     }
+
+    fn package_source_dir(&self, crate_name: &str) -> Option<PathBuf> {
+        self.path.package_source_dir(crate_name)
+    }
+
+    fn package_has_lib_target(&self, crate_name: &str) -> Option<bool> {
+        self.path.package_has_lib_target(crate_name)
+    }
+
+    fn source_label(&self) -> Option<String> {
+        Some(self.label.clone())
+    }
+}
+
+/// The tag patterns used when the workspace doesn't configure any.
+pub(crate) const DEFAULT_BASELINE_TAG_PATTERNS: &[&str] = &["{crate}-v{version}", "v{version}"];
+
+/// Generates the baseline rustdoc from the git tag of the crate's most recent release.
+///
+/// Tags are matched against patterns like `{crate}-v{version}`, and the baseline version
+/// is chosen among the tagged versions the same way as for versions in the registry.
+#[derive(Debug)]
+pub(crate) struct RustdocFromGitTags {
+    source: PathBuf,
+    target_root: PathBuf,
+    tag_patterns: Vec<String>,
+    tags: Vec<String>,
+    /// The revision each crate's baseline was loaded from, so its source can be found later.
+    revisions: RefCell<BTreeMap<String, RustdocFromGitRevision>>,
+}
+
+impl RustdocFromGitTags {
+    pub fn new(
+        source: &std::path::Path,
+        target_root: &std::path::Path,
+        tag_patterns: Vec<String>,
+    ) -> anyhow::Result<Self> {
+        for pattern in &tag_patterns {
+            if pattern.matches("{version}").count() != 1 {
+                bail!("baseline tag pattern `{pattern}` must contain `{{version}}` exactly once");
+            }
+        }
+
+        let repo = gix::discover(source)?;
+        let tags = repo
+            .references()?
+            .tags()?
+            .map(|reference| {
+                reference
+                    .map(|reference| reference.name().shorten().to_string())
+                    .map_err(|e| anyhow::anyhow!(e))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self {
+            source: source.to_path_buf(),
+            target_root: target_root.to_path_buf(),
+            tag_patterns,
+            tags,
+            revisions: RefCell::default(),
+        })
+    }
+
+    /// Finds the tag of the crate version that should be used as the baseline.
+    fn choose_tag(
+        &self,
+        crate_name: &str,
+        version_current: Option<&semver::Version>,
+    ) -> anyhow::Result<&str> {
+        // Earlier patterns take precedence when multiple tags name the same version.
+        let mut tagged_versions: BTreeMap<semver::Version, &str> = BTreeMap::new();
+        for pattern in self.tag_patterns.iter().rev() {
+            for tag in &self.tags {
+                if let Some(version) = match_tag(pattern, crate_name, tag) {
+                    tagged_versions.insert(version, tag);
+                }
+            }
+        }
+
+        let version = select_baseline_version(
+            tagged_versions.keys().map(|v| (v.clone(), false)),
+            version_current,
+        )
+        .with_context(|| {
+            let patterns = self.tag_patterns.iter().map(|p| format!("`{p}`")).join(", ");
+            match version_current {
+                Some(current) => anyhow::format_err!(
+                    "no git tags for {crate_name} at or below v{current} match the baseline tag patterns {patterns}"
+                ),
+                None => anyhow::format_err!(
+                    "no git tags for {crate_name} match the baseline tag patterns {patterns}"
+                ),
+            }
+        })?;
+        Ok(tagged_versions[&version])
+    }
+}
+
+/// Returns the version named by the tag, if the tag matches the pattern for the given crate.
+fn match_tag(pattern: &str, crate_name: &str, tag: &str) -> Option<semver::Version> {
+    let pattern = pattern.replace("{crate}", crate_name);
+    let (prefix, suffix) = pattern.split_once("{version}")?;
+    let version = tag.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    semver::Version::parse(version).ok()
+}
+
+impl RustdocGenerator for RustdocFromGitTags {
+    fn load_rustdoc(
+        &self,
+        config: &mut GlobalConfig,
+        generation_settings: super::data_generation::GenerationSettings,
+        cache_settings: super::data_generation::CacheSettings<()>,
+        crate_data: CrateDataForRustdoc,
+    ) -> Result<LoadedCrate, TerminalError> {
+        let version_current = match crate_data.crate_type {
+            CrateType::Current => None,
+            CrateType::Baseline {
+                highest_allowed_version,
+            } => highest_allowed_version,
+        };
+        let tag = self
+            .choose_tag(crate_data.name, version_current)
+            .into_terminal_result()?;
+        let target = self
+            .target_root
+            .join(format!("git-{}", crate::util::slugify(tag)));
+        let revision = RustdocFromGitRevision::with_rev(&self.source, &target, tag, config)
+            .into_terminal_result()?;
+        let crate_name = crate_data.name.to_string();
+        let result = revision.load_rustdoc(config, generation_settings, cache_settings, crate_data);
+        self.revisions.borrow_mut().insert(crate_name, revision);
+        result
+    }
+
+    fn package_source_dir(&self, crate_name: &str) -> Option<PathBuf> {
+        self.revisions
+            .borrow()
+            .get(crate_name)?
+            .package_source_dir(crate_name)
+    }
 }
 
 // From git2 crate
@@ -547,7 +953,12 @@ fn bytes2str(b: &[u8]) -> &std::ffi::OsStr {
 pub(crate) struct RustdocFromRegistry {
     target_root: PathBuf,
     version: Option<semver::Version>,
-    index: tame_index::index::ComboIndex,
+    /// The name of the registry to use instead of crates.io, if any.
+    registry: Option<String>,
+    /// Shared with other checks run with the same [`GlobalConfig`].
+    index: Rc<tame_index::index::ComboIndex>,
+    /// Where cargo unpacked each loaded crate's source, so it can be found later.
+    source_dirs: RefCell<BTreeMap<String, PathBuf>>,
 }
 
 impl core::fmt::Debug for RustdocFromRegistry {
@@ -555,110 +966,218 @@ impl core::fmt::Debug for RustdocFromRegistry {
         f.debug_struct("RustdocFromRegistry")
             .field("target_root", &self.target_root)
             .field("version", &self.version)
+            .field("registry", &self.registry)
             .field("index", &"<elided>")
+            .field("source_dirs", &self.source_dirs)
             .finish()
     }
 }
 
 impl RustdocFromRegistry {
-    pub fn new(target_root: &std::path::Path, config: &mut GlobalConfig) -> anyhow::Result<Self> {
-        let index_url = tame_index::IndexUrl::crates_io(
-            // This is the config root, where .cargo/config.toml configuration files
-            // are crawled to determine if crates.io has been source replaced
-            // <https://doc.rust-lang.org/cargo/reference/source-replacement.html>
-            // if not specified it defaults to the current working directory,
-            // which is the same default that cargo uses, though note this can be
-            // extremely confusing if one can specify the manifest path of the
-            // crate from a different current working directory, though AFAICT
-            // this is not how this binary works
-            None,
-            // If set this overrides the CARGO_HOME that is used for both finding
-            // the "global" default config if not overriden during directory
-            // traversal to the root, as well as where the various registry
-            // indices/git sources are rooted. This is generally only useful
-            // for testing
-            None,
-            // If set, overrides the version of the cargo binary used, this is used
-            // as a fallback to determine if the version is 1.70.0+, which means
-            // the default crates.io registry to use is the sparse registry, else
-            // it is the old git registry
-            None,
-        )
-        .context("failed to obtain crates.io url")?;
-
-        use tame_index::index::{self, ComboIndexCache};
-
-        let index_cache = ComboIndexCache::new(tame_index::IndexLocation::new(index_url))
-            .context("failed to open crates.io index cache")?;
-
-        let index: index::ComboIndex = match index_cache {
-            ComboIndexCache::Git(git) => {
-                let lock = acquire_cargo_global_package_lock(config)?;
-                let mut rgi = index::RemoteGitIndex::new(git, &lock)
-                    .context("failed to open crates.io git index")?;
-
-                config.shell_status("Updating", "index")?;
-                while need_retry(rgi.fetch(&lock))? {
-                    config.shell_status("Blocking", "waiting for lock on registry index")?;
-                    std::thread::sleep(REGISTRY_BACKOFF);
-                }
-                drop(lock);
-
-                rgi.into()
+    /// Looks up crates in the registry with the given name, or in crates.io if `None`.
+    ///
+    /// Registry names are resolved the way cargo resolves them: from `CARGO_REGISTRIES_<NAME>_INDEX`,
+    /// source replacement, or the `[registries]` table of cargo's configuration.
+    pub fn new(
+        target_root: &std::path::Path,
+        registry_name: Option<&str>,
+        config: &mut GlobalConfig,
+    ) -> anyhow::Result<Self> {
+        let (index_url, registry) = match registry_name {
+            None | Some("crates-io") => {
+                let index_url = tame_index::IndexUrl::crates_io(
+                    // This is the config root, where .cargo/config.toml configuration files
+                    // are crawled to determine if crates.io has been source replaced
+                    // <https://doc.rust-lang.org/cargo/reference/source-replacement.html>
+                    // if not specified it defaults to the current working directory,
+                    // which is the same default that cargo uses, though note this can be
+                    // extremely confusing if one can specify the manifest path of the
+                    // crate from a different current working directory, though AFAICT
+                    // this is not how this binary works
+                    None,
+                    // If set this overrides the CARGO_HOME that is used for both finding
+                    // the "global" default config if not overriden during directory
+                    // traversal to the root, as well as where the various registry
+                    // indices/git sources are rooted. This is generally only useful
+                    // for testing
+                    None,
+                    // If set, overrides the version of the cargo binary used, this is used
+                    // as a fallback to determine if the version is 1.70.0+, which means
+                    // the default crates.io registry to use is the sparse registry, else
+                    // it is the old git registry
+                    None,
+                )
+                .context("failed to obtain crates.io url")?;
+                (index_url, None)
             }
-            ComboIndexCache::Sparse(sparse) => {
-                let client = tame_index::external::reqwest::blocking::Client::builder()
-                    .http2_prior_knowledge()
-                    .build()
-                    .context("failed to build HTTP client")?;
-                index::RemoteSparseIndex::new(sparse, client).into()
+            Some(name) => {
+                let index_url = tame_index::IndexUrl::for_registry_name(None, None, name)
+                    .with_context(|| format!("failed to find the index of registry `{name}`"))?;
+                (index_url, Some(name.to_string()))
             }
-            _ => bail!("encountered unknown cache type"),
         };
 
+        let index = shared_index(index_url, config)?;
+
         Ok(Self {
             target_root: target_root.to_owned(),
             version: None,
+            registry,
             index,
+            source_dirs: RefCell::default(),
         })
     }
 
     pub fn set_version(&mut self, version: semver::Version) {
         self.version = Some(version);
     }
+
+    /// The crate's entry in the registry index, or `None` if it was never published there.
+    fn find_index_entry(
+        &self,
+        config: &mut GlobalConfig,
+        crate_name: &str,
+    ) -> anyhow::Result<Option<IndexKrate>> {
+        let lock = acquire_cargo_global_package_lock(config)?;
+        let crate_ = self
+            .index
+            .krate(
+                crate_name.try_into().expect("this should be impossible"),
+                false,
+                &lock,
+            )
+            .with_context(|| format!("failed to read index metadata for crate '{crate_name}'"))?;
+        drop(lock);
+        Ok(crate_)
+    }
+
+    fn index_entry(
+        &self,
+        config: &mut GlobalConfig,
+        crate_name: &str,
+    ) -> anyhow::Result<IndexKrate> {
+        self.find_index_entry(config, crate_name)?
+            .with_context(|| {
+                anyhow::format_err!(
+                    "{crate_name} not found in registry ({}). \
+                    For workarounds check \
+                    https://github.com/obi1kenobi/cargo-semver-checks#does-the-crate-im-checking-have-to-be-published-on-cratesio",
+                    self.registry.as_deref().unwrap_or("crates.io"),
+                )
+            })
+    }
+
+    /// Every version of the crate published to the registry, with whether it's yanked.
+    pub(crate) fn published_versions(
+        &self,
+        config: &mut GlobalConfig,
+        crate_name: &str,
+    ) -> anyhow::Result<Vec<(semver::Version, bool)>> {
+        let crate_ = self.index_entry(config, crate_name)?;
+        Ok(crate_
+            .versions
+            .iter()
+            .filter_map(|iv| {
+                semver::Version::parse(iv.version.as_str())
+                    .ok()
+                    .map(|v| (v, iv.is_yanked()))
+            })
+            .collect())
+    }
+
+    /// The latest release of each semver-compatible release line of the crate, oldest first,
+    /// considering only versions at or below `highest_allowed_version` if it's set.
+    pub(crate) fn latest_in_each_major(
+        &self,
+        config: &mut GlobalConfig,
+        crate_name: &str,
+        highest_allowed_version: Option<&semver::Version>,
+    ) -> anyhow::Result<Vec<semver::Version>> {
+        let versions = self.published_versions(config, crate_name)?;
+        let latest = select_latest_in_each_major(versions, highest_allowed_version);
+        if latest.is_empty() {
+            bail!("No available baseline versions for {crate_name}");
+        }
+        Ok(latest)
+    }
+}
+
+/// Opens the registry index at the given URL, fetching it first if it's a git index.
+/// Returns the index at the given URL, opening it only if it wasn't already opened
+/// with this config.
+fn shared_index(
+    index_url: tame_index::IndexUrl<'_>,
+    config: &mut GlobalConfig,
+) -> anyhow::Result<Rc<tame_index::index::ComboIndex>> {
+    if let Some(index) = config.registry_index(index_url.as_str()) {
+        return Ok(index);
+    }
+    let url = index_url.as_str().to_string();
+    let index = Rc::new(open_index(index_url, config)?);
+    config.add_registry_index(url, Rc::clone(&index));
+    Ok(index)
+}
+
+fn open_index(
+    index_url: tame_index::IndexUrl<'_>,
+    config: &mut GlobalConfig,
+) -> anyhow::Result<tame_index::index::ComboIndex> {
+    use tame_index::index::{self, ComboIndexCache};
+
+    let index_name = match &index_url {
+        tame_index::IndexUrl::CratesIoSparse | tame_index::IndexUrl::CratesIoGit => {
+            "crates.io".to_string()
+        }
+        index_url => format!("`{}`", index_url.as_str()),
+    };
+
+    let index_cache = ComboIndexCache::new(tame_index::IndexLocation::new(index_url))
+        .with_context(|| format!("failed to open {index_name} index cache"))?;
+
+    Ok(match index_cache {
+        ComboIndexCache::Git(git) => {
+            let lock = acquire_cargo_global_package_lock(config)?;
+            let mut rgi = index::RemoteGitIndex::new(git, &lock)
+                .with_context(|| format!("failed to open {index_name} git index"))?;
+
+            config.shell_status("Updating", "index")?;
+            while need_retry(rgi.fetch(&lock))? {
+                config.shell_status("Blocking", "waiting for lock on registry index")?;
+                std::thread::sleep(REGISTRY_BACKOFF);
+            }
+            drop(lock);
+
+            rgi.into()
+        }
+        ComboIndexCache::Sparse(sparse) => {
+            let client = tame_index::external::reqwest::blocking::Client::builder()
+                .http2_prior_knowledge()
+                .build()
+                .context("failed to build HTTP client")?;
+            index::RemoteSparseIndex::new(sparse, client).into()
+        }
+        ComboIndexCache::Local(local) => local.into(),
+        _ => bail!("encountered unknown cache type"),
+    })
 }
 
 fn choose_baseline_version(
     crate_: &IndexKrate,
     version_current: Option<&semver::Version>,
 ) -> anyhow::Result<semver::Version> {
-    // Try to avoid pre-releases
-    // - Breaking changes are allowed between them
-    // - Most likely the user cares about the last official release
     if let Some(current) = version_current {
-        let mut instances = crate_
-            .versions
-            .iter()
-            .map(|iv| (iv.version.clone(), iv.is_yanked()))
-            // For unpublished changes when the user doesn't increment the version
-            // post-release, allow using the current version as a baseline.
-            .filter_map(|(v, yanked)| semver::Version::parse(v.as_str()).ok().map(|v| (v, yanked)))
-            .filter(|(v, _)| v <= current)
-            .collect::<Vec<_>>();
-        instances.sort();
-        instances
-            .iter()
-            .rev()
-            .find(|(v, yanked)| v.pre.is_empty() && !yanked)
-            .or_else(|| instances.last())
-            .map(|(v, _)| v.clone())
-            .with_context(|| {
-                anyhow::format_err!(
-                    "No available baseline versions for {}@{}",
-                    crate_.name(),
-                    current
-                )
-            })
+        let versions = crate_.versions.iter().filter_map(|iv| {
+            semver::Version::parse(iv.version.as_str())
+                .ok()
+                .map(|v| (v, iv.is_yanked()))
+        });
+        select_baseline_version(versions, Some(current)).with_context(|| {
+            anyhow::format_err!(
+                "No available baseline versions for {}@{}",
+                crate_.name(),
+                current
+            )
+        })
     } else {
         let instance = semver::Version::parse(
             crate_
@@ -676,6 +1195,63 @@ fn choose_baseline_version(
     }
 }
 
+/// Chooses the baseline among the given `(version, is_yanked)` pairs,
+/// considering only versions at or below `version_current` if it's set.
+fn select_baseline_version(
+    versions: impl IntoIterator<Item = (semver::Version, bool)>,
+    version_current: Option<&semver::Version>,
+) -> Option<semver::Version> {
+    // Try to avoid pre-releases
+    // - Breaking changes are allowed between them
+    // - Most likely the user cares about the last official release
+    let mut instances = versions
+        .into_iter()
+        // For unpublished changes when the user doesn't increment the version
+        // post-release, allow using the current version as a baseline.
+        .filter(|(v, _)| version_current.is_none_or(|current| v <= current))
+        .collect::<Vec<_>>();
+    instances.sort();
+    instances
+        .iter()
+        .rev()
+        .find(|(v, yanked)| v.pre.is_empty() && !yanked)
+        .or_else(|| instances.last())
+        .map(|(v, _)| v.clone())
+}
+
+/// Chooses the newest version of each semver-compatible release line
+/// among the given `(version, is_yanked)` pairs, oldest release line first.
+///
+/// Pre-releases and yanked versions are skipped, and like [`select_baseline_version`],
+/// only versions at or below `version_current` are considered if it's set.
+fn select_latest_in_each_major(
+    versions: impl IntoIterator<Item = (semver::Version, bool)>,
+    version_current: Option<&semver::Version>,
+) -> Vec<semver::Version> {
+    // Releases are semver-compatible if they share their leftmost non-zero version component.
+    let release_line = |v: &semver::Version| match (v.major, v.minor) {
+        (0, 0) => (0, 0, v.patch),
+        (0, minor) => (0, minor, 0),
+        (major, _) => (major, 0, 0),
+    };
+    let mut latest: BTreeMap<(u64, u64, u64), semver::Version> = BTreeMap::new();
+    for (version, yanked) in versions {
+        if yanked
+            || !version.pre.is_empty()
+            || version_current.is_some_and(|current| &version > current)
+        {
+            continue;
+        }
+        let entry = latest
+            .entry(release_line(&version))
+            .or_insert_with(|| version.clone());
+        if *entry < version {
+            *entry = version;
+        }
+    }
+    latest.into_values().collect()
+}
+
 impl RustdocGenerator for RustdocFromRegistry {
     fn load_rustdoc(
         &self,
@@ -683,21 +1259,10 @@ impl RustdocGenerator for RustdocFromRegistry {
         generation_settings: super::data_generation::GenerationSettings,
         cache_settings: super::data_generation::CacheSettings<()>,
         crate_data: CrateDataForRustdoc,
-    ) -> Result<VersionedStorage, TerminalError> {
-        let lock = acquire_cargo_global_package_lock(config).into_terminal_result()?;
-        let crate_ = self.index.krate(crate_data.name.try_into().expect("this should be impossible"), false, &lock)
-            .with_context(|| {
-                format!("failed to read index metadata for crate '{}'", crate_data.name)
-            }).into_terminal_result()?
-            .with_context(|| {
-            anyhow::format_err!(
-                "{} not found in registry (crates.io). \
-        For workarounds check \
-        https://github.com/obi1kenobi/cargo-semver-checks#does-the-crate-im-checking-have-to-be-published-on-cratesio",
-                crate_data.name
-            )
-        }).into_terminal_result()?;
-        drop(lock);
+    ) -> Result<LoadedCrate, TerminalError> {
+        let crate_ = self
+            .index_entry(config, crate_data.name)
+            .into_terminal_result()?;
 
         let base_version = if let Some(base) = &self.version {
             base.clone()
@@ -729,14 +1294,42 @@ impl RustdocGenerator for RustdocFromRegistry {
             })
             .into_terminal_result()?;
 
-        generate_rustdoc(
+        let crate_name = crate_data.name.to_string();
+        let loaded = generate_rustdoc(
             config,
             generation_settings,
             cache_settings,
             self.target_root.clone(),
-            CrateSource::Registry { crate_ },
+            CrateSource::Registry {
+                crate_,
+                registry: self.registry.as_deref(),
+            },
             crate_data,
-        )
+        )?;
+        if let Some(source_dir) = loaded
+            .package
+            .as_ref()
+            .and_then(|package| package.manifest_path.parent())
+        {
+            self.source_dirs
+                .borrow_mut()
+                .insert(crate_name, source_dir.as_std_path().to_owned());
+        }
+        Ok(loaded)
+    }
+
+    fn package_source_dir(&self, crate_name: &str) -> Option<PathBuf> {
+        self.source_dirs.borrow().get(crate_name).cloned()
+    }
+
+    fn package_has_history(
+        &self,
+        config: &mut GlobalConfig,
+        crate_name: &str,
+    ) -> anyhow::Result<bool> {
+        Ok(self
+            .find_index_entry(config, crate_name)?
+            .is_some_and(|crate_| !crate_.versions.is_empty()))
     }
 }
 
@@ -773,9 +1366,15 @@ fn acquire_cargo_global_package_lock(
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+    use std::rc::Rc;
+
     use tame_index::{IndexKrate, IndexVersion};
 
-    use super::choose_baseline_version;
+    use super::{
+        choose_baseline_version, extract_crate_file, match_tag, open_index,
+        select_latest_in_each_major, shared_index, FeatureConfig, RustdocFromGitTags,
+    };
 
     fn new_mock_version(version: semver::Version, yanked: bool) -> IndexVersion {
         let mut iv = IndexVersion::fake("test-crate", version.to_string());
@@ -856,4 +1455,223 @@ mod tests {
             "1.2.1",
         );
     }
+
+    #[test]
+    fn latest_in_each_major_choosing_logic() {
+        let versions = [
+            ("0.1.0", false),
+            ("0.1.3", false),
+            ("0.2.0", false),
+            ("1.0.0", false),
+            ("1.4.2", false),
+            ("1.5.0", true),
+            ("2.0.0-rc.1", false),
+            ("2.0.0", false),
+            ("2.1.0", false),
+            ("3.0.0", false),
+        ]
+        .map(|(version, yanked)| (semver::Version::parse(version).unwrap(), yanked));
+        let chosen = |current: Option<&str>| {
+            select_latest_in_each_major(
+                versions.clone(),
+                current.map(|v| semver::Version::parse(v).unwrap()).as_ref(),
+            )
+            .into_iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(chosen(None), ["0.1.3", "0.2.0", "1.4.2", "2.1.0", "3.0.0"]);
+        assert_eq!(chosen(Some("2.0.5")), ["0.1.3", "0.2.0", "1.4.2", "2.0.0"]);
+        assert!(chosen(Some("0.0.1")).is_empty());
+    }
+
+    #[test]
+    fn tag_matching() {
+        let version = |v: &str| Some(semver::Version::parse(v).unwrap());
+        assert_eq!(
+            match_tag("{crate}-v{version}", "my-crate", "my-crate-v1.2.3"),
+            version("1.2.3")
+        );
+        assert_eq!(
+            match_tag("v{version}", "my-crate", "v1.0.0-rc.1"),
+            version("1.0.0-rc.1")
+        );
+        assert_eq!(
+            match_tag("{crate}-v{version}", "my-crate", "other-crate-v1.2.3"),
+            None
+        );
+        assert_eq!(match_tag("v{version}", "my-crate", "v1.2"), None);
+    }
+
+    #[test]
+    fn tag_choosing_logic() {
+        let tags = RustdocFromGitTags {
+            source: "".into(),
+            target_root: "".into(),
+            tag_patterns: vec!["{crate}-v{version}".into(), "v{version}".into()],
+            revisions: Default::default(),
+            tags: [
+                "v1.0.0",
+                "my-crate-v1.0.0",
+                "v1.1.0",
+                "v1.2.0-rc.1",
+                "v2.0.0",
+                "other-v1.5.0",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        };
+        let current = semver::Version::parse("1.2.0").unwrap();
+        assert_eq!(
+            tags.choose_tag("my-crate", Some(&current)).unwrap(),
+            "v1.1.0"
+        );
+        assert_eq!(tags.choose_tag("my-crate", None).unwrap(), "v2.0.0");
+
+        // Crate-specific tags take precedence over generic ones for the same version.
+        let current = semver::Version::parse("1.0.5").unwrap();
+        assert_eq!(
+            tags.choose_tag("my-crate", Some(&current)).unwrap(),
+            "my-crate-v1.0.0"
+        );
+
+        let current = semver::Version::parse("0.9.0").unwrap();
+        assert!(tags.choose_tag("my-crate", Some(&current)).is_err());
+    }
+
+    /// Writes a local registry with versions `1.0.0` and yanked `1.1.0` of crate `abc`.
+    fn write_local_registry(registry: &Path) {
+        use sha2::Digest as _;
+
+        let index_dir = registry.join("index/3/a");
+        std::fs::create_dir_all(&index_dir).unwrap();
+
+        // Local registries are validated when opened, so the `.crate` file's checksum must match.
+        let crate_file = b"not really a tarball";
+        std::fs::write(registry.join("abc-1.0.0.crate"), crate_file).unwrap();
+        let checksum = format!("{:x}", sha2::Sha256::digest(crate_file));
+        let index_entries = [("1.0.0", false), ("1.1.0", true)]
+            .map(|(version, yanked)| {
+                format!(
+                    r#"{{"name":"abc","vers":"{version}","deps":[],"cksum":"{checksum}","features":{{}},"yanked":{yanked}}}"#
+                )
+            })
+            .join("\n");
+        std::fs::write(index_dir.join("abc"), index_entries).unwrap();
+    }
+
+    fn local_index_url(registry: &Path) -> tame_index::IndexUrl<'static> {
+        tame_index::IndexUrl::Local(
+            tame_index::PathBuf::from_path_buf(registry.to_owned())
+                .unwrap()
+                .into(),
+        )
+    }
+
+    #[test]
+    fn local_registry_index() {
+        let registry = std::env::temp_dir().join(format!(
+            "semver-checks-local-registry-{}",
+            std::process::id()
+        ));
+        write_local_registry(&registry);
+        let index =
+            open_index(local_index_url(&registry), &mut crate::GlobalConfig::new()).unwrap();
+        let lock = tame_index::index::FileLock::unlocked();
+        let krate = index
+            .krate("abc".try_into().unwrap(), false, &lock)
+            .unwrap()
+            .expect("crate not found in local registry");
+        assert_eq!(
+            choose_baseline_version(&krate, None).unwrap(),
+            semver::Version::parse("1.0.0").unwrap()
+        );
+
+        std::fs::remove_dir_all(&registry).unwrap();
+    }
+
+    #[test]
+    fn registry_index_is_opened_once_per_config() {
+        let registry = std::env::temp_dir().join(format!(
+            "semver-checks-shared-registry-{}",
+            std::process::id()
+        ));
+        write_local_registry(&registry);
+
+        // Like two crates of a batch checked against the same registry.
+        let mut config = crate::GlobalConfig::new();
+        let first = shared_index(local_index_url(&registry), &mut config).unwrap();
+        let second = shared_index(local_index_url(&registry), &mut config).unwrap();
+        assert!(Rc::ptr_eq(&first, &second));
+
+        let other =
+            shared_index(local_index_url(&registry), &mut crate::GlobalConfig::new()).unwrap();
+        assert!(!Rc::ptr_eq(&first, &other));
+
+        std::fs::remove_dir_all(&registry).unwrap();
+    }
+
+    #[test]
+    fn crate_file_extraction() {
+        let dir =
+            std::env::temp_dir().join(format!("semver-checks-crate-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let crate_file = dir.join("abc-1.0.0.crate");
+        {
+            let encoder = flate2::write::GzEncoder::new(
+                std::fs::File::create(&crate_file).unwrap(),
+                flate2::Compression::default(),
+            );
+            let mut builder = tar::Builder::new(encoder);
+            for (path, contents) in [
+                ("abc-1.0.0/Cargo.toml", "[package]\nname = \"abc\"\n"),
+                ("abc-1.0.0/src/lib.rs", "pub fn f() {}\n"),
+            ] {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(contents.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder
+                    .append_data(&mut header, path, contents.as_bytes())
+                    .unwrap();
+            }
+            builder.into_inner().unwrap().finish().unwrap();
+        }
+
+        let target = dir.join("source");
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::write(target.join("stale.rs"), "").unwrap();
+
+        extract_crate_file(&crate_file, &target).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(target.join("abc-1.0.0/src/lib.rs")).unwrap(),
+            "pub fn f() {}\n"
+        );
+        assert!(!target.join("stale.rs").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn renamed_features_are_enabled_on_both_sides() {
+        let renamed_features = [
+            ("tokio-rt".to_string(), "rt-tokio".to_string()),
+            ("old-tls".to_string(), "tls".to_string()),
+            ("gone".to_string(), "excluded".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let mut current = FeatureConfig::default_for_current();
+        let mut baseline = FeatureConfig::default_for_baseline();
+        current.layer(None, &["tls".to_string()], &["excluded".to_string()]);
+        baseline.layer(None, &["tokio-rt".to_string(), "gone".to_string()], &[]);
+
+        FeatureConfig::apply_renames(&renamed_features, &mut current, &mut baseline);
+        assert_eq!(current.extra_features, ["tls", "rt-tokio"]);
+        assert_eq!(current.excluded_features, ["excluded"]);
+        assert_eq!(baseline.extra_features, ["tokio-rt", "gone", "old-tls"]);
+    }
 }
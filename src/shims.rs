@@ -0,0 +1,290 @@
+//! Values for shim templates that can't be computed from a lint's query outputs.
+//!
+//! Shims that forward to a renamed function, or that give a newly-required trait method
+//! a default body, need the full declaration of the item involved. Lints don't output
+//! declarations, so we look them up here and parse what the shim needs out of them.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use trustfall::{FieldValue, TransparentValue};
+use trustfall_rustdoc::VersionedRustdocAdapter;
+
+use crate::check_release::result_path;
+use crate::SemverQuery;
+
+/// Template values for a `#[deprecated]` wrapper named `old_name`, forwarding to
+/// the function or method `new_name` declared as `declaration`:
+/// `wrapper_signature`, the declaration under the old name, and `forwarding_call`,
+/// the call to make in the wrapper's body.
+///
+/// Free functions are called as `callee`. Methods that take `self` are called on it instead.
+/// Returns `None` if some parameter isn't a plain identifier, since it can't be forwarded.
+pub(crate) fn forwarding_values(
+    declaration: &str,
+    old_name: &str,
+    new_name: &str,
+    callee: &str,
+) -> Option<[(Arc<str>, TransparentValue); 2]> {
+    let (header, _) = declaration.split_once(&format!("fn {new_name}"))?;
+    let parameters = parameter_names(declaration)?;
+    let mut call = match parameters.split_first() {
+        Some((receiver, arguments)) if receiver == "self" => {
+            format!("self.{new_name}({})", arguments.join(", "))
+        }
+        _ => format!("{callee}({})", parameters.join(", ")),
+    };
+    if header.contains("async ") {
+        call.push_str(".await");
+    }
+
+    Some([
+        (
+            Arc::from("wrapper_signature"),
+            TransparentValue::String(
+                declaration
+                    .replacen(&format!("fn {new_name}"), &format!("fn {old_name}"), 1)
+                    .into(),
+            ),
+        ),
+        (
+            Arc::from("forwarding_call"),
+            TransparentValue::String(call.into()),
+        ),
+    ])
+}
+
+/// The names of the parameters of a function declaration, like `fn f<T>(a: T, b: u8)`,
+/// or `None` if they aren't all plain identifiers or the declaration can't be parsed.
+fn parameter_names(declaration: &str) -> Option<Vec<String>> {
+    let mut depth = 0usize;
+    let mut previous = None;
+    let mut parameters: Option<Vec<String>> = None;
+    let mut current = String::new();
+    for c in declaration.chars() {
+        match c {
+            // The `>` of `->` in bounds like `F: Fn() -> u8` doesn't close anything.
+            '>' if previous == Some('-') => {}
+            '(' if depth == 0 && parameters.is_none() => {
+                parameters = Some(vec![]);
+                depth += 1;
+                previous = Some(c);
+                continue;
+            }
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    if let Some(parameters) = parameters.as_mut() {
+                        if !current.trim().is_empty() {
+                            parameters.push(std::mem::take(&mut current));
+                        }
+                        break;
+                    }
+                }
+            }
+            ',' if depth == 1 && parameters.is_some() => {
+                parameters.as_mut()?.push(std::mem::take(&mut current));
+                previous = Some(c);
+                continue;
+            }
+            _ => {}
+        }
+        if parameters.is_some() {
+            current.push(c);
+        }
+        previous = Some(c);
+    }
+
+    parameters?
+        .iter()
+        .map(|parameter| {
+            let (name, _) = parameter.split_once(':')?;
+            let name = name.trim();
+            let name = name.strip_prefix("mut ").unwrap_or(name).trim();
+            let is_identifier = name != "_"
+                && name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+            is_identifier.then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// The declaration of each method of the current version's public traits.
+const TRAIT_METHODS_QUERY: &str = r#"
+{
+    CrateDiff {
+        current {
+            item {
+                ... on Trait {
+                    visibility_limit @filter(op: "=", value: ["$public"])
+
+                    importable_path {
+                        path @output
+                        public_api @filter(op: "=", value: ["$true"])
+                    }
+
+                    method {
+                        method_name: name @output
+                        declaration: signature @output
+                    }
+                }
+            }
+        }
+    }
+}"#;
+
+/// The declarations of trait methods reported by lints about newly-required methods,
+/// so their shims can suggest a default body.
+pub(crate) struct TraitMethodDeclarations {
+    /// Each method's declaration, by the trait's importable path and the method's name.
+    declarations: BTreeMap<(Vec<String>, String), String>,
+}
+
+impl TraitMethodDeclarations {
+    /// Looks up the declarations of the current version's trait methods,
+    /// or returns `None` if the lint isn't about newly-required trait methods.
+    pub(crate) fn for_lint(
+        adapter: &VersionedRustdocAdapter<'_>,
+        semver_query: &SemverQuery,
+    ) -> anyhow::Result<Option<Self>> {
+        if semver_query.id != "trait_method_added" {
+            return Ok(None);
+        }
+
+        let arguments: BTreeMap<&str, FieldValue> = [
+            ("public", FieldValue::from("public")),
+            ("true", FieldValue::from(true)),
+        ]
+        .into_iter()
+        .collect();
+        let declarations = adapter
+            .run_query(TRAIT_METHODS_QUERY, arguments)?
+            .filter_map(|mut result| {
+                let path = result
+                    .remove("path")?
+                    .as_vec_with(FieldValue::as_str)?
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect();
+                let method_name = result.remove("method_name")?.as_str()?.to_string();
+                let declaration = result.remove("declaration")?.as_str()?.to_string();
+                Some(((path, method_name), declaration))
+            })
+            .collect();
+
+        Ok(Some(Self { declarations }))
+    }
+
+    /// The `method_signature` template value for the method a lint result reports.
+    pub(crate) fn template_values(
+        &self,
+        result: &BTreeMap<Arc<str>, TransparentValue>,
+    ) -> Option<[(Arc<str>, TransparentValue); 1]> {
+        let path = result_path(result)?;
+        let Some(TransparentValue::String(method_name)) = result.get("method_name") else {
+            return None;
+        };
+        let declaration = self.declarations.get(&(path, method_name.to_string()))?;
+        Some([(
+            Arc::from("method_signature"),
+            TransparentValue::String(declaration.as_str().into()),
+        )])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use trustfall::TransparentValue;
+
+    use super::{forwarding_values, parameter_names, TRAIT_METHODS_QUERY};
+    use crate::templating::make_handlebars_registry;
+    use crate::SemverQuery;
+
+    #[test]
+    fn trait_methods_query_is_valid() {
+        let schema = trustfall_rustdoc_adapter_v39::RustdocAdapter::schema();
+        if let Err(e) = trustfall_core::frontend::parse(&schema, TRAIT_METHODS_QUERY) {
+            panic!("invalid query: {e}");
+        }
+    }
+
+    fn names(names: &[&str]) -> Option<Vec<String>> {
+        Some(names.iter().map(|name| name.to_string()).collect())
+    }
+
+    #[test]
+    fn parameter_names_of_declarations() {
+        assert_eq!(parameter_names("fn f()"), names(&[]));
+        assert_eq!(
+            parameter_names("fn f(a: u8, b: &[u8; 4])"),
+            names(&["a", "b"])
+        );
+        assert_eq!(
+            parameter_names(
+                "fn f<'a, F: Fn(u8, u8) -> u8, const N: usize>(f: F, x: &'a Vec<(u8, u8)>) -> u8"
+            ),
+            names(&["f", "x"])
+        );
+        assert_eq!(
+            parameter_names("fn f<T>(self: &Self, mut value: T) where T: Clone"),
+            names(&["self", "value"])
+        );
+        // Parameters without names, or bound by patterns, can't be forwarded.
+        assert_eq!(parameter_names("fn f(_: u8)"), None);
+        assert_eq!(parameter_names("fn f((a, b): (u8, u8))"), None);
+    }
+
+    #[test]
+    fn forwarding_calls() {
+        let call = |declaration: &str, new_name: &str, callee: &str| {
+            let [_, (_, call)] = forwarding_values(declaration, "old", new_name, callee)?;
+            match call {
+                TransparentValue::String(call) => Some(call.to_string()),
+                _ => None,
+            }
+        };
+        assert_eq!(
+            call("fn new(a: u8, b: u8) -> u8", "new", "crate::new").as_deref(),
+            Some("crate::new(a, b)")
+        );
+        assert_eq!(
+            call("fn new(self: &Self, a: u8)", "new", "Self::new").as_deref(),
+            Some("self.new(a)")
+        );
+        assert_eq!(
+            call("async fn new()", "new", "crate::new").as_deref(),
+            Some("crate::new().await")
+        );
+        assert_eq!(call("fn new(_: u8)", "new", "crate::new"), None);
+    }
+
+    #[test]
+    fn trait_method_shim_adds_default_body() {
+        let query = &SemverQuery::all_queries()["trait_method_added"];
+        let template = query.shim_template.as_deref().expect("no shim template");
+
+        let values: BTreeMap<Arc<str>, TransparentValue> = [
+            ("name", "Parser"),
+            ("method_name", "finish"),
+            ("method_signature", "fn finish(self: &mut Self) -> usize"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (Arc::from(name), TransparentValue::String(value.into())))
+        .collect();
+
+        let shim = make_handlebars_registry()
+            .render_template(template, &values)
+            .expect("failed to render shim");
+        assert_eq!(
+            shim,
+            "// in `trait Parser`, a default body keeps existing implementations compiling:\n\
+            fn finish(self: &mut Self) -> usize {\n    \
+                unimplemented!(\"`finish` is not implemented\")\n\
+            }"
+        );
+    }
+}
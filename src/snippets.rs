@@ -0,0 +1,435 @@
+//! Rendering of rustc-style source snippets for lint results.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use itertools::Itertools as _;
+use trustfall::{Schema, TransparentValue};
+use trustfall_core::ir::{IRQueryComponent, Vid};
+
+/// When a span covers more lines than this, the middle of the snippet is elided.
+const MAX_SNIPPET_LINES: usize = 6;
+
+/// Where to find the source code of each version of the crate being checked.
+///
+/// Lint spans may contain file paths relative to the package's directory,
+/// which are resolved against these directories.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SnippetSources {
+    pub(crate) current: Option<PathBuf>,
+    pub(crate) baseline: Option<PathBuf>,
+}
+
+/// Which version of the crate a lint's span refers to.
+///
+/// Baseline spans sort first, so snippets read from the old code to the new.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum SpanSide {
+    Baseline,
+    Current,
+}
+
+/// A span in a lint's outputs: the prefix shared by its `filename`, `begin_line`
+/// and `end_line` outputs, e.g. `span_`, and the version of the crate it comes from.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct SpanOutput {
+    pub(crate) side: SpanSide,
+    pub(crate) prefix: String,
+}
+
+impl SpanSide {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Current => "current",
+            Self::Baseline => "baseline",
+        }
+    }
+}
+
+impl SnippetSources {
+    fn source_dir(&self, side: SpanSide) -> Option<&Path> {
+        match side {
+            SpanSide::Current => self.current.as_deref(),
+            SpanSide::Baseline => self.baseline.as_deref(),
+        }
+    }
+
+    /// Renders the source code covered by each of the result's spans that can be found.
+    pub(crate) fn render_result(
+        &self,
+        spans: &[SpanOutput],
+        result: &BTreeMap<Arc<str>, TransparentValue>,
+    ) -> Vec<String> {
+        spans
+            .iter()
+            .filter_map(|span| {
+                let field = |name: &str| result.get(format!("{}{name}", span.prefix).as_str());
+                let Some(TransparentValue::String(filename)) = field("filename") else {
+                    return None;
+                };
+                let begin_line = field("begin_line").and_then(as_line_number)?;
+                let end_line = field("end_line").and_then(as_line_number);
+                self.render(span.side, filename, begin_line, end_line)
+            })
+            .collect()
+    }
+
+    /// Renders the source code covered by a lint result's span, if it can be found.
+    fn render(
+        &self,
+        side: SpanSide,
+        filename: &str,
+        begin_line: usize,
+        end_line: Option<usize>,
+    ) -> Option<String> {
+        let path = Path::new(filename);
+        let path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.source_dir(side)?.join(path)
+        };
+        let source = std::fs::read_to_string(path).ok()?;
+        render_snippet(&source, side, filename, begin_line, end_line)
+    }
+}
+
+fn as_line_number(value: &TransparentValue) -> Option<usize> {
+    match value {
+        TransparentValue::Int64(n) => usize::try_from(*n).ok(),
+        TransparentValue::Uint64(n) => usize::try_from(*n).ok(),
+        _ => None,
+    }
+}
+
+/// Finds the lint's span outputs, named like `span_begin_line`, and whether each comes from
+/// the current or baseline version of the crate. Baseline spans are listed first.
+///
+/// Only spans with a single value per result that are reachable through
+/// the `current` or `baseline` edge of the `CrateDiff` root are returned.
+pub(crate) fn span_outputs(schema: &Schema, query: &str) -> Vec<SpanOutput> {
+    let Ok(parsed_query) = trustfall_core::frontend::parse(schema, query) else {
+        return vec![];
+    };
+    if parsed_query.ir_query.root_name.as_ref() != "CrateDiff" {
+        return vec![];
+    }
+
+    let root_component = &parsed_query.ir_query.root_component;
+    let mut parents: BTreeMap<Vid, (Vid, Arc<str>)> = BTreeMap::new();
+    collect_parents(root_component, &mut parents);
+
+    let side_of = |mut vid: Vid| {
+        while let Some((from_vid, edge_name)) = parents.get(&vid) {
+            if *from_vid == root_component.root {
+                return match edge_name.as_ref() {
+                    "current" => Some(SpanSide::Current),
+                    "baseline" => Some(SpanSide::Baseline),
+                    _ => None,
+                };
+            }
+            vid = *from_vid;
+        }
+        None
+    };
+    parsed_query
+        .outputs
+        .iter()
+        .filter(|(_, output)| !output.value_type.is_list())
+        .filter_map(|(name, output)| {
+            let prefix = name
+                .strip_suffix("begin_line")
+                .filter(|prefix| prefix.ends_with("span_"))?;
+            Some(SpanOutput {
+                side: side_of(output.vid)?,
+                prefix: prefix.to_string(),
+            })
+        })
+        .sorted()
+        .collect()
+}
+
+/// Maps each vertex to the vertex and edge name it was reached from.
+fn collect_parents(component: &IRQueryComponent, parents: &mut BTreeMap<Vid, (Vid, Arc<str>)>) {
+    for edge in component.edges.values() {
+        parents.insert(edge.to_vid, (edge.from_vid, Arc::clone(&edge.edge_name)));
+    }
+    for fold in component.folds.values() {
+        parents.insert(fold.to_vid, (fold.from_vid, Arc::clone(&fold.edge_name)));
+        collect_parents(&fold.component, parents);
+    }
+}
+
+fn render_snippet(
+    source: &str,
+    side: SpanSide,
+    filename: &str,
+    begin_line: usize,
+    end_line: Option<usize>,
+) -> Option<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    if begin_line == 0 || begin_line > lines.len() {
+        return None;
+    }
+    let end_line = end_line
+        .unwrap_or(begin_line)
+        .clamp(begin_line, lines.len());
+
+    let gutter_width = end_line.to_string().len();
+    let empty_gutter = " ".repeat(gutter_width);
+    let mut snippet = String::new();
+    writeln!(
+        snippet,
+        "{empty_gutter}--> {filename}:{begin_line} ({} version)",
+        side.as_str()
+    )
+    .expect("writes to strings are infallible");
+    writeln!(snippet, "{empty_gutter} |").expect("writes to strings are infallible");
+
+    if begin_line == end_line {
+        let line = lines[begin_line - 1];
+        let content = line.trim_start();
+        let indent = line.len() - content.len();
+        writeln!(snippet, "{begin_line:>gutter_width$} | {line}")
+            .expect("writes to strings are infallible");
+        write!(
+            snippet,
+            "{empty_gutter} | {}{}",
+            " ".repeat(indent),
+            "^".repeat(content.trim_end().len().max(1))
+        )
+        .expect("writes to strings are infallible");
+        return Some(snippet);
+    }
+
+    // Multi-line spans are marked in the margin, the way rustc does it.
+    let line_numbers: Vec<Option<usize>> = if end_line - begin_line < MAX_SNIPPET_LINES {
+        (begin_line..=end_line).map(Some).collect()
+    } else {
+        (begin_line..begin_line + MAX_SNIPPET_LINES / 2)
+            .map(Some)
+            .chain([None])
+            .chain((end_line + 1 - MAX_SNIPPET_LINES / 2..=end_line).map(Some))
+            .collect()
+    };
+    for line_number in line_numbers {
+        match line_number {
+            Some(n) if n == begin_line => {
+                writeln!(snippet, "{n:>gutter_width$} | / {}", lines[n - 1])
+            }
+            Some(n) => writeln!(snippet, "{n:>gutter_width$} | | {}", lines[n - 1]),
+            None => writeln!(snippet, "{empty_gutter} | ..."),
+        }
+        .expect("writes to strings are infallible");
+    }
+    write!(snippet, "{empty_gutter} | |_^").expect("writes to strings are infallible");
+    Some(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use trustfall::TransparentValue;
+
+    use super::{render_snippet, span_outputs, SnippetSources, SpanOutput, SpanSide};
+
+    const SOURCE: &str = "\
+pub fn moved() {}
+
+pub struct Foo {
+    pub x: i64,
+    pub y: i64,
+}
+";
+
+    #[test]
+    fn single_line_snippet() {
+        let snippet = render_snippet(SOURCE, SpanSide::Baseline, "src/lib.rs", 1, Some(1))
+            .expect("no snippet");
+        let expected = [
+            " --> src/lib.rs:1 (baseline version)",
+            "  |",
+            "1 | pub fn moved() {}",
+            "  | ^^^^^^^^^^^^^^^^^",
+        ];
+        assert_eq!(snippet, expected.join("\n"));
+    }
+
+    #[test]
+    fn multi_line_snippet() {
+        let snippet = render_snippet(SOURCE, SpanSide::Current, "src/lib.rs", 3, Some(6))
+            .expect("no snippet");
+        let expected = [
+            " --> src/lib.rs:3 (current version)",
+            "  |",
+            "3 | / pub struct Foo {",
+            "4 | |     pub x: i64,",
+            "5 | |     pub y: i64,",
+            "6 | | }",
+            "  | |_^",
+        ];
+        assert_eq!(snippet, expected.join("\n"));
+    }
+
+    #[test]
+    fn long_snippet_is_elided() {
+        let source = (1..=10).map(|n| format!("line {n}\n")).collect::<String>();
+        let snippet = render_snippet(&source, SpanSide::Current, "src/lib.rs", 1, Some(10))
+            .expect("no snippet");
+        let expected = [
+            "  --> src/lib.rs:1 (current version)",
+            "   |",
+            " 1 | / line 1",
+            " 2 | | line 2",
+            " 3 | | line 3",
+            "   | ...",
+            " 8 | | line 8",
+            " 9 | | line 9",
+            "10 | | line 10",
+            "   | |_^",
+        ];
+        assert_eq!(snippet, expected.join("\n"));
+    }
+
+    #[test]
+    fn out_of_range_span() {
+        assert_eq!(
+            render_snippet(SOURCE, SpanSide::Current, "src/lib.rs", 42, None),
+            None
+        );
+    }
+
+    fn span(side: SpanSide, prefix: &str) -> SpanOutput {
+        SpanOutput {
+            side,
+            prefix: prefix.to_string(),
+        }
+    }
+
+    #[test]
+    fn span_outputs_follow_crate_diff_edges() {
+        let schema = trustfall_rustdoc_adapter_v39::RustdocAdapter::schema();
+        let query = |edge: &str| {
+            format!(
+                r#"
+                {{
+                    CrateDiff {{
+                        {edge} {{
+                            item {{
+                                ... on Function {{
+                                    name @output
+                                    span_: span @optional {{
+                                        filename @output
+                                        begin_line @output
+                                    }}
+                                }}
+                            }}
+                        }}
+                    }}
+                }}"#
+            )
+        };
+        assert_eq!(
+            span_outputs(&schema, &query("baseline")),
+            [span(SpanSide::Baseline, "span_")]
+        );
+        assert_eq!(
+            span_outputs(&schema, &query("current")),
+            [span(SpanSide::Current, "span_")]
+        );
+    }
+
+    #[test]
+    fn span_outputs_from_both_versions() {
+        let schema = trustfall_rustdoc_adapter_v39::RustdocAdapter::schema();
+        let query = r#"
+        {
+            CrateDiff {
+                current {
+                    item {
+                        ... on Function {
+                            name @output @tag
+                            span_: span @optional {
+                                filename @output
+                                begin_line @output
+                            }
+                        }
+                    }
+                }
+                baseline {
+                    item {
+                        ... on Function {
+                            name @filter(op: "=", value: ["%name"])
+                            baseline_span_: span @optional {
+                                filename @output
+                                begin_line @output
+                            }
+                        }
+                    }
+                }
+            }
+        }"#;
+        assert_eq!(
+            span_outputs(&schema, query),
+            [
+                span(SpanSide::Baseline, "baseline_span_"),
+                span(SpanSide::Current, "span_"),
+            ]
+        );
+    }
+
+    #[test]
+    fn both_versions_are_rendered() {
+        let root = std::env::temp_dir().join(format!(
+            "semver-checks-snippet-sources-{}",
+            std::process::id()
+        ));
+        let sources = SnippetSources {
+            current: Some(root.join("new")),
+            baseline: Some(root.join("old")),
+        };
+        for (dir, source) in [
+            ("old", "pub fn moved() {}\n"),
+            ("new", "\npub fn moved() {}\n"),
+        ] {
+            std::fs::create_dir_all(root.join(dir).join("src")).unwrap();
+            std::fs::write(root.join(dir).join("src/lib.rs"), source).unwrap();
+        }
+
+        let result = BTreeMap::from([
+            (
+                Arc::from("span_filename"),
+                TransparentValue::String("src/lib.rs".into()),
+            ),
+            (Arc::from("span_begin_line"), TransparentValue::Uint64(2)),
+            (
+                Arc::from("baseline_span_filename"),
+                TransparentValue::String("src/lib.rs".into()),
+            ),
+            (
+                Arc::from("baseline_span_begin_line"),
+                TransparentValue::Uint64(1),
+            ),
+        ]);
+        let spans = [
+            span(SpanSide::Baseline, "baseline_span_"),
+            span(SpanSide::Current, "span_"),
+        ];
+        let snippets = sources.render_result(&spans, &result);
+        assert_eq!(snippets.len(), 2, "{snippets:?}");
+        assert!(snippets[0].contains("src/lib.rs:1 (baseline version)"));
+        assert!(snippets[1].contains("src/lib.rs:2 (current version)"));
+
+        // Spans whose source can't be found are left out.
+        let sources = SnippetSources {
+            baseline: None,
+            ..sources
+        };
+        assert_eq!(sources.render_result(&spans, &result).len(), 1);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,333 @@
+//! Comparing the library targets of the baseline and current packages.
+//!
+//! Some changes break dependents before any of the library's API is compared:
+//! renaming the library changes the crate name that every path to its items starts with,
+//! and dropping a crate type like `rlib` stops dependents from linking to it the way they did.
+//! Both are read from the `cargo_metadata::Target`s of each package.
+//!
+//! The `links` key of `-sys` crates is compared too, since build scripts of dependents
+//! read the `DEP_<LINKS>_*` variables named after it.
+
+use std::collections::BTreeSet;
+use std::io::Write as _;
+
+use anstyle::{Reset, Style};
+
+use crate::{GlobalConfig, LintLevel, OverrideStack, RequiredSemverUpdate};
+
+/// The library target of a package, as far as its dependents are concerned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LibTarget {
+    name: String,
+    /// The crate types the library is built as. `lib` is recorded as `rlib`,
+    /// which is what cargo builds for it.
+    crate_types: BTreeSet<String>,
+    /// The native library the package links to, from the `links` manifest key.
+    links: Option<String>,
+}
+
+impl LibTarget {
+    /// The name the library is imported by, with dashes replaced by underscores.
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The library target of the package, if it has one that can be semver-checked.
+    pub(crate) fn of_package(package: &cargo_metadata::Package) -> Option<Self> {
+        let target = package
+            .targets
+            .iter()
+            .find(|target| crate::is_lib_like_checkable_target(target))?;
+        Some(Self {
+            name: target.name.replace('-', "_"),
+            crate_types: target
+                .crate_types
+                .iter()
+                .map(|crate_type| match crate_type.to_string().as_str() {
+                    "lib" => "rlib".to_string(),
+                    crate_type => crate_type.to_string(),
+                })
+                .collect(),
+            links: package.links.clone(),
+        })
+    }
+}
+
+/// A change to a package's library target that breaks its dependents.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetChange {
+    /// The package no longer has a library target.
+    LibRemoved { name: String },
+    /// The library target was renamed.
+    LibRenamed { baseline: String, current: String },
+    /// The library target is no longer built as the given crate type.
+    CrateTypeRemoved { crate_type: String },
+    /// The package no longer has a `links` key.
+    LinksRemoved { links: String },
+    /// The package's `links` key has a different value.
+    LinksChanged { baseline: String, current: String },
+    /// The package has a `links` key it didn't have before.
+    LinksAdded { links: String },
+}
+
+impl TargetChange {
+    /// An identifier for the kind of change, in the style of lint ids.
+    ///
+    /// Like lints, their level and required version bump can be overridden by this id,
+    /// e.g. in the `[lints]` table of the package's manifest.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::LibRemoved { .. } => "lib_target_removed",
+            Self::LibRenamed { .. } => "lib_target_renamed",
+            Self::CrateTypeRemoved { .. } => "lib_crate_type_removed",
+            Self::LinksRemoved { .. } => "links_removed",
+            Self::LinksChanged { .. } => "links_changed",
+            Self::LinksAdded { .. } => "links_added",
+        }
+    }
+
+    /// The lint level of the change, `deny` unless overridden.
+    pub(crate) fn lint_level(&self, overrides: &OverrideStack) -> LintLevel {
+        overrides.effective_lint_level_of(self.id(), LintLevel::Deny)
+    }
+
+    /// The version bump the change requires, `major` unless overridden.
+    pub(crate) fn required_update(&self, overrides: &OverrideStack) -> RequiredSemverUpdate {
+        overrides.effective_required_update_of(self.id(), RequiredSemverUpdate::Major)
+    }
+
+    fn human_readable_name(&self) -> &'static str {
+        match self {
+            Self::LibRemoved { .. } => "library target removed",
+            Self::LibRenamed { .. } => "library target renamed",
+            Self::CrateTypeRemoved { .. } => "library crate type removed",
+            Self::LinksRemoved { .. } => "package links key removed",
+            Self::LinksChanged { .. } => "package links key changed",
+            Self::LinksAdded { .. } => "package links key added",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Self::LibRemoved { .. } => {
+                "The package no longer has a library target, so dependents can no longer use it \
+                as a library."
+            }
+            Self::LibRenamed { .. } => {
+                "The package's library target was renamed. Every path dependents use to refer to \
+                its items starts with the library's name, so all of them break."
+            }
+            Self::CrateTypeRemoved { .. } => {
+                "The package's library is no longer built as one of the crate types it used to be. \
+                Dependents that link to it as that crate type break, e.g. Rust crates need `rlib` \
+                and C programs need `cdylib` or `staticlib`."
+            }
+            Self::LinksRemoved { .. } | Self::LinksChanged { .. } => {
+                "The package's `links` key was removed or changed. Build scripts of dependents \
+                that read the `DEP_<LINKS>_*` variables set by this package's build script \
+                no longer find them."
+            }
+            Self::LinksAdded { .. } => {
+                "The package now has a `links` key. Only one package in a dependency graph \
+                may link to a given native library, so dependents that also depend on another \
+                package linking to it, such as an older major version of this one, fail to build."
+            }
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Self::LibRemoved { name } => format!("library {name} removed from the package"),
+            Self::LibRenamed { baseline, current } => {
+                format!("library {baseline} renamed to {current}")
+            }
+            Self::CrateTypeRemoved { crate_type } => {
+                format!("crate type {crate_type} removed from the library")
+            }
+            Self::LinksRemoved { links } => format!("links = \"{links}\" removed"),
+            Self::LinksChanged { baseline, current } => {
+                format!("links = \"{baseline}\" changed to links = \"{current}\"")
+            }
+            Self::LinksAdded { links } => format!("links = \"{links}\" added"),
+        }
+    }
+}
+
+/// The changes between the baseline library target and the current one, if any.
+pub(crate) fn target_changes(
+    baseline: &LibTarget,
+    current: Option<&LibTarget>,
+) -> Vec<TargetChange> {
+    let Some(current) = current else {
+        return vec![TargetChange::LibRemoved {
+            name: baseline.name.clone(),
+        }];
+    };
+
+    let mut changes = vec![];
+    if baseline.name != current.name {
+        changes.push(TargetChange::LibRenamed {
+            baseline: baseline.name.clone(),
+            current: current.name.clone(),
+        });
+    }
+    changes.extend(
+        baseline
+            .crate_types
+            .difference(&current.crate_types)
+            .map(|crate_type| TargetChange::CrateTypeRemoved {
+                crate_type: crate_type.clone(),
+            }),
+    );
+    match (&baseline.links, &current.links) {
+        (Some(links), None) => changes.push(TargetChange::LinksRemoved {
+            links: links.clone(),
+        }),
+        (Some(baseline), Some(current)) if baseline != current => {
+            changes.push(TargetChange::LinksChanged {
+                baseline: baseline.clone(),
+                current: current.clone(),
+            })
+        }
+        (None, Some(links)) => changes.push(TargetChange::LinksAdded {
+            links: links.clone(),
+        }),
+        _ => {}
+    }
+    changes
+}
+
+/// Prints each change like a lint result of the given kind, `failure` or `warning`.
+pub(crate) fn print_target_changes<'a>(
+    config: &mut GlobalConfig,
+    kind: &str,
+    changes: impl IntoIterator<Item = &'a TargetChange>,
+) -> anyhow::Result<()> {
+    for change in changes {
+        config.log_info(|config| {
+            writeln!(
+                config.stdout(),
+                "\n--- {kind} {}: {} ---\n\n{}Description:{}\n{}\n\n{}Failed in:{}\n  {}",
+                change.id(),
+                change.human_readable_name(),
+                Style::new().bold(),
+                Reset,
+                change.description(),
+                Style::new().bold(),
+                Reset,
+                change.message(),
+            )?;
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{target_changes, LibTarget, TargetChange};
+    use crate::{LintLevel, OverrideMap, OverrideStack, QueryOverride, RequiredSemverUpdate};
+
+    fn lib(name: &str, crate_types: &[&str]) -> LibTarget {
+        LibTarget {
+            name: name.to_string(),
+            crate_types: crate_types.iter().map(ToString::to_string).collect(),
+            links: None,
+        }
+    }
+
+    fn sys_lib(links: &str) -> LibTarget {
+        LibTarget {
+            links: Some(links.to_string()),
+            ..lib("foo_sys", &["rlib"])
+        }
+    }
+
+    #[test]
+    fn library_target_changes() {
+        let baseline = lib("my_crate", &["rlib", "cdylib"]);
+        assert!(target_changes(&baseline, Some(&baseline)).is_empty());
+        assert!(target_changes(
+            &baseline,
+            Some(&lib("my_crate", &["rlib", "cdylib", "staticlib"]))
+        )
+        .is_empty());
+
+        assert_eq!(
+            target_changes(&baseline, Some(&lib("renamed", &["cdylib"]))),
+            [
+                TargetChange::LibRenamed {
+                    baseline: "my_crate".into(),
+                    current: "renamed".into(),
+                },
+                TargetChange::CrateTypeRemoved {
+                    crate_type: "rlib".into(),
+                },
+            ]
+        );
+        assert_eq!(
+            target_changes(&baseline, None),
+            [TargetChange::LibRemoved {
+                name: "my_crate".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn links_changes() {
+        let plain = lib("foo_sys", &["rlib"]);
+        assert!(target_changes(&sys_lib("foo"), Some(&sys_lib("foo"))).is_empty());
+
+        assert_eq!(
+            target_changes(&sys_lib("foo"), Some(&plain)),
+            [TargetChange::LinksRemoved {
+                links: "foo".into(),
+            }]
+        );
+        assert_eq!(
+            target_changes(&sys_lib("foo"), Some(&sys_lib("foo2"))),
+            [TargetChange::LinksChanged {
+                baseline: "foo".into(),
+                current: "foo2".into(),
+            }]
+        );
+        assert_eq!(
+            target_changes(&plain, Some(&sys_lib("foo"))),
+            [TargetChange::LinksAdded {
+                links: "foo".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn lint_level_can_be_overridden() {
+        let renamed = TargetChange::LibRenamed {
+            baseline: "my_crate".into(),
+            current: "renamed".into(),
+        };
+        let removed = TargetChange::CrateTypeRemoved {
+            crate_type: "rlib".into(),
+        };
+        let mut overrides = OverrideStack::new();
+        assert_eq!(renamed.lint_level(&overrides), LintLevel::Deny);
+        assert_eq!(
+            renamed.required_update(&overrides),
+            RequiredSemverUpdate::Major
+        );
+
+        overrides.push(&OverrideMap::from([(
+            "lib_target_renamed".to_string(),
+            QueryOverride {
+                required_update: Some(RequiredSemverUpdate::Minor),
+                lint_level: Some(LintLevel::Warn),
+            },
+        )]));
+        assert_eq!(renamed.lint_level(&overrides), LintLevel::Warn);
+        assert_eq!(
+            renamed.required_update(&overrides),
+            RequiredSemverUpdate::Minor
+        );
+        assert_eq!(removed.lint_level(&overrides), LintLevel::Deny);
+    }
+}
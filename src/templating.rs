@@ -1,4 +1,4 @@
-use handlebars::{handlebars_helper, Handlebars};
+use handlebars::{handlebars_helper, Handlebars, RenderErrorReason};
 use serde_json::Value;
 
 // a helper to lowercase a string
@@ -6,16 +6,19 @@ handlebars_helper!(lowercase: |arg: Value| {
     if let Value::String(arg) = arg {
         arg.to_ascii_lowercase()
     } else {
-        unreachable!("non-string value provided: {arg:?}")
+        return Err(RenderErrorReason::Other(format!("lowercase: non-string value provided: {arg:?}")).into());
     }
 });
 
 // a helper to join all values
 handlebars_helper!(join: |sep: str, args: Value| {
     if let Value::Array(arr) = args {
-        arr.iter().map(|x| x.as_str().expect("non-string included")).collect::<Vec<&str>>().join(sep)
+        let Some(values) = arr.iter().map(|x| x.as_str()).collect::<Option<Vec<&str>>>() else {
+            return Err(RenderErrorReason::Other(format!("join: non-string value included: {arr:?}")).into());
+        };
+        values.join(sep)
     } else {
-        unreachable!("non-array value provided: {args:?}")
+        return Err(RenderErrorReason::Other(format!("join: non-array value provided: {args:?}")).into());
     }
 });
 
@@ -28,7 +31,7 @@ handlebars_helper!(unpack_if_singleton: |arg: Value| {
             arg
         }
     } else {
-        unreachable!("non-array value provided: {arg:?}")
+        return Err(RenderErrorReason::Other(format!("unpack_if_singleton: non-array value provided: {arg:?}")).into());
     }
 });
 
@@ -36,16 +39,20 @@ handlebars_helper!(unpack_if_singleton: |arg: Value| {
 handlebars_helper!(multiple_spans: |files: Value, begin_line_numbers: Value| {
     match (&files, &begin_line_numbers) {
         (Value::Array(files), Value::Array(begin_line_numbers)) if files.len() == begin_line_numbers.len() => {
-            let formatted_values: Vec<_> = files.iter().zip(begin_line_numbers).filter_map(|(file, begin_line)| {
-                if file.is_null() {
-                    assert!(begin_line.is_null());
-                    None
-                } else {
-                    let file = file.as_str().expect("file was not a string");
-                    let begin_line = begin_line.as_u64().expect("begin line number was not a u64");
-                    Some(format!("{file}:{begin_line}"))
+            let mut formatted_values = vec![];
+            for (file, begin_line) in files.iter().zip(begin_line_numbers) {
+                match (file, begin_line) {
+                    (Value::Null, Value::Null) => {}
+                    (Value::String(file), Value::Number(begin_line)) if begin_line.is_u64() => {
+                        formatted_values.push(format!("{file}:{begin_line}"));
+                    }
+                    _ => {
+                        return Err(RenderErrorReason::Other(format!(
+                            "multiple_spans: invalid file name and line number: {file:?} {begin_line:?}"
+                        )).into());
+                    }
                 }
-            }).collect();
+            }
             if formatted_values.len() == 1 {
                 formatted_values.into_iter().next().unwrap()
             } else {
@@ -53,9 +60,15 @@ handlebars_helper!(multiple_spans: |files: Value, begin_line_numbers: Value| {
             }
         }
         (Value::Array(files), Value::Array(begin_line_numbers)) => {
-            unreachable!("the arrays did not have the same length: {files:?} {begin_line_numbers:?}")
+            return Err(RenderErrorReason::Other(format!(
+                "multiple_spans: the arrays did not have the same length: {files:?} {begin_line_numbers:?}"
+            )).into());
+        }
+        _ => {
+            return Err(RenderErrorReason::Other(format!(
+                "multiple_spans: non-array values provided: {files:?} {begin_line_numbers:?}"
+            )).into());
         }
-        _ => unreachable!("non-array values provided: {files:?} {begin_line_numbers:?}"),
     }
 });
 
@@ -0,0 +1,2 @@
+#[cfg(feature = "tls")]
+pub fn connect() {}
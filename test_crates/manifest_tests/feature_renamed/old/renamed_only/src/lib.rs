@@ -0,0 +1,2 @@
+#[cfg(feature = "unstable-tls")]
+pub fn connect() {}
@@ -0,0 +1,3 @@
+pub fn id() -> base::Id {
+    base::Id
+}
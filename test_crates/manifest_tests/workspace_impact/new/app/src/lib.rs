@@ -0,0 +1,3 @@
+pub fn run() {
+    let _id = base::Id;
+}
@@ -0,0 +1,2 @@
+/// Exposed in the public API of `api`, but not of `app`.
+pub struct Id;
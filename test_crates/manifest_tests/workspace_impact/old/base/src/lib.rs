@@ -0,0 +1,4 @@
+/// Exposed in the public API of `api`, but not of `app`.
+pub struct Id;
+
+pub fn removed() {}
@@ -0,0 +1,75 @@
+//! Tests the `audit` subcommand against a local registry, so no network access is needed.
+mod local_registry;
+
+use local_registry::LocalRegistry;
+
+/// The crate opts into the `strict-zero` version policy,
+/// under which `0.y.z` patch releases may only contain patch-level changes.
+const STRICT_ZERO: &str = "\
+[package.metadata.cargo-semver-checks]
+version-policy = \"strict-zero\"
+";
+
+#[test]
+fn audit_finds_under_bumped_release_in_local_registry() {
+    let root = std::env::temp_dir().join(format!("semver-checks-audit-{}", std::process::id()));
+    let mut registry = LocalRegistry::new(&root);
+    registry.publish("audited", "0.1.0", STRICT_ZERO, "pub fn greet() {}\n");
+    // Deprecating an item is a minor change, but `strict-zero` makes 0.1.1 a patch release.
+    registry.publish(
+        "audited",
+        "0.1.1",
+        STRICT_ZERO,
+        "#[deprecated]\npub fn greet() {}\n",
+    );
+    // Removing it is a major change, which 0.2.0 declares.
+    registry.publish("audited", "0.2.0", STRICT_ZERO, "pub fn hello() {}\n");
+
+    let mut cmd = registry.command();
+    cmd.current_dir(&root).args([
+        "semver-checks",
+        "audit",
+        "audited",
+        "-Z",
+        "unstable-options",
+    ]);
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8(output.stdout.clone()).unwrap();
+
+    let row = |versions: &str| {
+        stdout
+            .lines()
+            .find(|line| line.starts_with(versions))
+            .unwrap_or_else(|| panic!("no row for {versions}: {output:?}"))
+            .split_whitespace()
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        row("0.1.0 -> 0.1.1"),
+        [
+            "0.1.0",
+            "->",
+            "0.1.1",
+            "patch",
+            "minor",
+            "violation",
+            "function_marked_deprecated"
+        ]
+    );
+    assert_eq!(
+        row("0.1.1 -> 0.2.0"),
+        [
+            "0.1.1",
+            "->",
+            "0.2.0",
+            "major",
+            "major",
+            "ok",
+            "function_missing"
+        ]
+    );
+    // The most severe violation is the under-bumped patch release.
+    assert_eq!(output.status.code(), Some(4), "{output:?}");
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
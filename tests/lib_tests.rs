@@ -1,4 +1,6 @@
-use cargo_semver_checks::{ActualSemverUpdate, Check, GlobalConfig, ReleaseType, Rustdoc};
+use cargo_semver_checks::{
+    ActualSemverUpdate, Check, CheckOutcome, GlobalConfig, ReleaseType, Rustdoc, TargetChange,
+};
 
 #[test]
 fn major_required_bump_if_breaking_change() {
@@ -29,3 +31,164 @@ fn major_required_bump_if_breaking_change_and_major_bump_detected() {
     assert_eq!(required_bump, None);
     assert_eq!(crate_report.detected_bump(), ActualSemverUpdate::Major);
 }
+
+#[test]
+fn breaking_change_exits_with_major_required() {
+    let current = Rustdoc::from_root("test_crates/trait_missing/old/");
+    let baseline = Rustdoc::from_root("test_crates/trait_missing/new/");
+    let mut check = Check::new(current);
+    let check = check.set_baseline(baseline);
+    let report = check.check_release(&mut GlobalConfig::new()).unwrap();
+    assert_eq!(report.exit_code(), CheckOutcome::MajorRequired);
+    assert_eq!(report.exit_code().code(), 5);
+}
+
+#[test]
+fn breaking_change_with_major_bump_exits_with_success() {
+    let current = Rustdoc::from_root("test_crates/trait_missing_with_major_bump/old/");
+    let baseline = Rustdoc::from_root("test_crates/trait_missing_with_major_bump/new/");
+    let mut check = Check::new(current);
+    let check = check.set_baseline(baseline);
+    let report = check.check_release(&mut GlobalConfig::new()).unwrap();
+    assert_eq!(report.exit_code(), CheckOutcome::Success);
+    assert_eq!(report.exit_code().code(), 0);
+}
+
+#[test]
+fn library_target_changes_require_major_bump_unless_overridden() {
+    let current = Rustdoc::from_root("test_crates/manifest_tests/lib_target_changes/new/");
+    let baseline = Rustdoc::from_root("test_crates/manifest_tests/lib_target_changes/old/");
+    let mut check = Check::new(current);
+    let check = check.set_baseline(baseline);
+    let report = check.check_release(&mut GlobalConfig::new()).unwrap();
+    assert!(!report.success());
+    let crate_reports = report.crate_reports();
+
+    let renamed = &crate_reports["lib_renamed"];
+    assert_eq!(renamed.required_bump(), Some(ReleaseType::Major));
+    assert_eq!(
+        renamed.target_changes(),
+        [TargetChange::LibRenamed {
+            baseline: "lib_renamed".into(),
+            current: "new_lib_name".into(),
+        }]
+    );
+
+    let rlib_removed = &crate_reports["rlib_removed"];
+    assert_eq!(rlib_removed.required_bump(), Some(ReleaseType::Major));
+    assert_eq!(
+        rlib_removed.target_changes(),
+        [TargetChange::CrateTypeRemoved {
+            crate_type: "rlib".into(),
+        }]
+    );
+
+    // `lib_target_renamed` is set to `warn` in the package's lint table.
+    let renamed_warn = &crate_reports["lib_renamed_warn"];
+    assert_eq!(renamed_warn.required_bump(), None);
+    assert_eq!(
+        renamed_warn.target_changes(),
+        [TargetChange::LibRenamed {
+            baseline: "lib_renamed_warn".into(),
+            current: "new_lib_name_warn".into(),
+        }]
+    );
+}
+
+/// Collects what's written to it, to check what a run printed.
+#[derive(Clone, Default)]
+struct SharedBuffer(std::rc::Rc<std::cell::RefCell<Vec<u8>>>);
+
+impl std::io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn workspace_impact_follows_exposed_types() {
+    let current = Rustdoc::from_root("test_crates/manifest_tests/workspace_impact/new/");
+    let baseline = Rustdoc::from_root("test_crates/manifest_tests/workspace_impact/old/");
+    let mut check = Check::new(current);
+    let check = check.set_baseline(baseline).set_workspace_impact(true);
+    let stderr = SharedBuffer::default();
+    let mut config = GlobalConfig::new();
+    config.set_stderr(Box::new(stderr.clone()));
+    config.set_log_level(Some(log::Level::Info));
+    let report = check.check_release(&mut config).unwrap();
+    assert!(!report.success());
+
+    // `api` returns a type of `base` from its public API, while `app` only uses it internally.
+    let impacts: Vec<&[String]> = report
+        .workspace_impact()
+        .iter()
+        .map(|impact| impact.chain())
+        .collect();
+    assert_eq!(impacts, [["base".to_string(), "api".to_string()]]);
+    assert_eq!(
+        report.workspace_impact()[0].detected_bump(),
+        ActualSemverUpdate::NotChanged
+    );
+
+    // Exposure is detected conservatively, and the output says so.
+    let stderr = String::from_utf8(stderr.0.borrow().clone()).unwrap();
+    assert!(
+        stderr.contains("api may be affected by breaking changes in base"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn workspace_impact_warns_about_unchecked_dependents() {
+    let current = Rustdoc::from_root("test_crates/manifest_tests/workspace_impact/new/");
+    let baseline = Rustdoc::from_root("test_crates/manifest_tests/workspace_impact/old/");
+    let mut check = Check::new(current);
+    let check = check
+        .set_baseline(baseline)
+        .set_packages(vec!["base".into()])
+        .set_workspace_impact(true);
+    let stderr = SharedBuffer::default();
+    let mut config = GlobalConfig::new();
+    config.set_stderr(Box::new(stderr.clone()));
+    let report = check.check_release(&mut config).unwrap();
+
+    // Without the rustdoc of `api` and `app`, they can't be reported as affected,
+    // but they aren't silently skipped either.
+    assert!(report.workspace_impact().is_empty());
+    let stderr = String::from_utf8(stderr.0.borrow().clone()).unwrap();
+    for dependent in ["api", "app"] {
+        assert!(
+            stderr.contains(&format!(
+                "{dependent} depends on base, which has breaking changes"
+            )),
+            "{stderr}"
+        );
+    }
+}
+
+#[test]
+fn renamed_features_are_compared_under_their_new_name() {
+    let current = Rustdoc::from_root("test_crates/manifest_tests/feature_renamed/new/");
+    let baseline = Rustdoc::from_root("test_crates/manifest_tests/feature_renamed/old/");
+    let mut check = Check::new(current);
+    let check = check.set_baseline(baseline);
+    let report = check.check_release(&mut GlobalConfig::new()).unwrap();
+    let crate_reports = report.crate_reports();
+
+    // `unstable-tls` was renamed to `tls`, so `feature_missing` doesn't fire for it.
+    let renamed_only = &crate_reports["renamed_only"];
+    assert!(renamed_only.success());
+    assert_eq!(renamed_only.required_bump(), None);
+
+    // The baseline is built with `unstable-tls`, since the current version is built with `tls`,
+    // so the removal of the function behind it is found.
+    let renamed_with_removal = &crate_reports["renamed_with_removal"];
+    assert_eq!(
+        renamed_with_removal.required_bump(),
+        Some(ReleaseType::Major)
+    );
+}
@@ -0,0 +1,107 @@
+//! A local registry that replaces crates.io, so registry crates can be tested
+//! without network access.
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use assert_cmd::Command;
+use sha2::Digest as _;
+
+pub struct LocalRegistry {
+    root: PathBuf,
+    /// The index entries of each published crate, by crate name.
+    index: BTreeMap<String, Vec<String>>,
+}
+
+impl LocalRegistry {
+    /// Creates an empty registry in `root`, with a cargo home whose configuration
+    /// replaces crates.io with it.
+    pub fn new(root: &Path) -> Self {
+        std::fs::create_dir_all(root.join("registry/index")).unwrap();
+        let cargo_home = root.join("cargo-home");
+        std::fs::create_dir_all(&cargo_home).unwrap();
+        std::fs::write(
+            cargo_home.join("config.toml"),
+            format!(
+                "[source.crates-io]\n\
+                replace-with = \"local-test-registry\"\n\
+                \n\
+                [source.local-test-registry]\n\
+                local-registry = {:?}\n",
+                root.join("registry").display().to_string(),
+            ),
+        )
+        .unwrap();
+
+        Self {
+            root: root.to_owned(),
+            index: BTreeMap::new(),
+        }
+    }
+
+    /// Publishes a version of a crate, whose manifest has `manifest_extra` appended
+    /// after its `[package]` table.
+    pub fn publish(&mut self, name: &str, version: &str, manifest_extra: &str, lib_rs: &str) {
+        let manifest = format!(
+            "[package]\n\
+            name = \"{name}\"\n\
+            version = \"{version}\"\n\
+            edition = \"2021\"\n\
+            {manifest_extra}"
+        );
+        let mut crate_file = vec![];
+        {
+            let encoder =
+                flate2::write::GzEncoder::new(&mut crate_file, flate2::Compression::default());
+            let mut builder = tar::Builder::new(encoder);
+            for (path, contents) in [("Cargo.toml", manifest.as_str()), ("src/lib.rs", lib_rs)] {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(contents.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder
+                    .append_data(
+                        &mut header,
+                        format!("{name}-{version}/{path}"),
+                        contents.as_bytes(),
+                    )
+                    .unwrap();
+            }
+            builder.into_inner().unwrap().finish().unwrap();
+        }
+        let registry = self.root.join("registry");
+        std::fs::write(
+            registry.join(format!("{name}-{version}.crate")),
+            &crate_file,
+        )
+        .unwrap();
+
+        // Local registries are validated when opened, so the checksum must match.
+        let checksum = format!("{:x}", sha2::Sha256::digest(&crate_file));
+        let entries = self.index.entry(name.to_string()).or_default();
+        entries.push(format!(
+            r#"{{"name":"{name}","vers":"{version}","deps":[],"cksum":"{checksum}","features":{{}},"yanked":false}}"#
+        ));
+        let index_path = registry.join("index").join(index_path(name));
+        std::fs::create_dir_all(index_path.parent().unwrap()).unwrap();
+        std::fs::write(index_path, entries.join("\n")).unwrap();
+    }
+
+    /// A `cargo-semver-checks` command that uses this registry in place of crates.io,
+    /// and caches rustdoc inside the registry's root.
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::cargo_bin("cargo-semver-checks").unwrap();
+        cmd.env("CARGO_HOME", self.root.join("cargo-home"))
+            .env("XDG_CACHE_HOME", self.root.join("cache"));
+        cmd
+    }
+}
+
+/// The path of a crate's file within the registry index, as cargo lays it out.
+fn index_path(name: &str) -> String {
+    match name.len() {
+        1 => format!("1/{name}"),
+        2 => format!("2/{name}"),
+        3 => format!("3/{}/{name}", &name[..1]),
+        _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+    }
+}
@@ -0,0 +1,35 @@
+//! Tests `--message-format json`, which prints progress events as JSON lines on stdout
+//! and moves all the human-readable output to stderr.
+use assert_cmd::Command;
+
+#[test]
+fn json_message_format_prints_only_json_lines_on_stdout() {
+    let mut cmd = Command::cargo_bin("cargo-semver-checks").unwrap();
+    cmd.current_dir("test_crates/enum_missing").args([
+        "semver-checks",
+        "check-release",
+        "--manifest-path=new/Cargo.toml",
+        "--baseline-root=old/",
+        "-Z",
+        "unstable-options",
+        "--message-format",
+        "json",
+    ]);
+    let output = cmd.output().unwrap();
+    // `enum_missing` has breaking changes, so the check fails but still runs to completion.
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let events: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|err| panic!("{err}: {line:?}")))
+        .collect();
+    assert!(
+        events.iter().any(|event| event["event"] == "crate_finish"),
+        "{stdout}"
+    );
+
+    // The human-readable report still goes to stderr.
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("enum_missing"), "{stderr}");
+}
@@ -0,0 +1,53 @@
+//! Tests `--source-snippets` with baselines whose source isn't in the checked project.
+mod local_registry;
+
+use local_registry::LocalRegistry;
+
+#[test]
+fn removed_item_shows_registry_baseline_snippet() {
+    let root = std::env::temp_dir().join(format!(
+        "semver-checks-registry-snippets-{}",
+        std::process::id()
+    ));
+    let mut registry = LocalRegistry::new(&root);
+    registry.publish(
+        "snippeted",
+        "1.0.0",
+        "",
+        "pub fn kept() {}\n\npub fn removed() {}\n",
+    );
+
+    let current = root.join("current");
+    std::fs::create_dir_all(current.join("src")).unwrap();
+    std::fs::write(
+        current.join("Cargo.toml"),
+        "[package]\nname = \"snippeted\"\nversion = \"1.0.1\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    std::fs::write(current.join("src/lib.rs"), "pub fn kept() {}\n").unwrap();
+
+    let mut cmd = registry.command();
+    cmd.current_dir(&current).args([
+        "semver-checks",
+        "check-release",
+        "--baseline-version=1.0.0",
+        "-Z",
+        "unstable-options",
+        "--source-snippets",
+    ]);
+    let output = cmd.output().unwrap();
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+
+    // The removed function only exists in the baseline, which cargo unpacked from the registry.
+    let stdout = String::from_utf8(output.stdout.clone()).unwrap();
+    let snippet = [
+        " --> src/lib.rs:3 (baseline version)",
+        "  |",
+        "3 | pub fn removed() {}",
+        "  | ^^^^^^^^^^^^^^^^^^^",
+    ]
+    .join("\n");
+    assert!(stdout.contains(&snippet), "{output:?}");
+
+    std::fs::remove_dir_all(&root).unwrap();
+}